@@ -52,7 +52,16 @@ export default function Dashboard() {
 
         // WebSocket for real-time updates
         wsManager.connect();
-        const unsub = wsManager.subscribe(() => {
+        const unsub = wsManager.subscribe((event) => {
+            if (event?.type === 'price_tick') {
+                // Live price for an open position — patch it in place
+                setPositions((prev) =>
+                    prev.map((p) =>
+                        p.symbol === event.symbol ? { ...p, current_price: event.price } : p
+                    )
+                );
+                return;
+            }
             // Refetch all data on cycle update
             fetchAll();
         });
@@ -118,7 +127,11 @@ export default function Dashboard() {
                         <BalanceChart data={balance} loading={loading} />
 
                         <div className="grid grid-cols-1 xl:grid-cols-2 gap-6">
-                            <TradeHistory trades={trades} loading={loading} />
+                            <TradeHistory
+                                trades={trades}
+                                quoteAsset={status?.quote_asset ?? 'USDC'}
+                                loading={loading}
+                            />
                             <CycleHistory cycles={cycles} loading={loading} />
                         </div>
                     </div>
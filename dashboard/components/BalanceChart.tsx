@@ -32,7 +32,7 @@ export default function BalanceChart({ data, loading }: Props) {
             hour: '2-digit',
             minute: '2-digit',
         }),
-        balance: d.balance_usdc,
+        balance: d.balance,
         pnl: d.total_pnl,
     }));
 
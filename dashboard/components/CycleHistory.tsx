@@ -67,7 +67,7 @@ export default function CycleHistory({ cycles, loading }: Props) {
                                         <span>F&G: {cycle.fear_greed}</span>
                                     )}
                                     <span className="font-mono">
-                                        ${cycle.balance_usdc.toFixed(2)}
+                                        {cycle.balance.toFixed(2)}
                                     </span>
                                 </div>
                             </div>
@@ -78,6 +78,12 @@ export default function CycleHistory({ cycles, loading }: Props) {
                                 </p>
                             )}
 
+                            {cycle.result?.startsWith('REJECTED') && (
+                                <p className="text-xs text-accent-amber mt-1">
+                                    ⛔ {cycle.result}
+                                </p>
+                            )}
+
                             {cycle.error && (
                                 <p className="text-xs text-accent-red mt-1">
                                     ⚠️ {cycle.error}
@@ -76,6 +76,8 @@ export default function PositionsList({ positions, loading }: Props) {
                                     {pos.stop_loss && (
                                         <span>
                                             SL: ${pos.stop_loss.toFixed(pos.stop_loss > 1 ? 2 : 6)}
+                                            {(pos.trail_pct !== null || pos.trail_distance !== null) &&
+                                                ' (trailing)'}
                                         </span>
                                     )}
                                     {pos.take_profit && (
@@ -28,7 +28,17 @@ export default function StatusCard({ status, loading }: Props) {
         <div className="glass-card gradient-border p-6 space-y-5">
             {/* Header */}
             <div className="flex items-center justify-between">
-                <h2 className="text-lg font-semibold text-text-primary">Bot Status</h2>
+                <h2 className="text-lg font-semibold text-text-primary">
+                    Bot Status
+                    <span
+                        className={`ml-2 px-2 py-0.5 rounded-full text-xs font-semibold uppercase ${status.mode === 'live'
+                                ? 'bg-accent-red/10 text-accent-red'
+                                : 'bg-bg-hover text-text-secondary'
+                            }`}
+                    >
+                        {status.mode}
+                    </span>
+                </h2>
                 <div className="flex items-center gap-2">
                     <div
                         className={`w-3 h-3 rounded-full ${isAlive
@@ -47,9 +57,9 @@ export default function StatusCard({ status, loading }: Props) {
 
             {/* Balance */}
             <div>
-                <p className="text-text-secondary text-sm mb-1">Balance (USDC)</p>
+                <p className="text-text-secondary text-sm mb-1">Balance ({status.quote_asset})</p>
                 <p className="text-3xl font-bold neon-text font-mono">
-                    ${status.balance_usdc.toFixed(2)}
+                    {status.balance.toFixed(2)}
                 </p>
             </div>
 
@@ -4,10 +4,11 @@ import { Trade } from '@/lib/api';
 
 interface Props {
     trades: Trade[];
+    quoteAsset: string;
     loading: boolean;
 }
 
-export default function TradeHistory({ trades, loading }: Props) {
+export default function TradeHistory({ trades, quoteAsset, loading }: Props) {
     if (loading) {
         return (
             <div className="glass-card p-6 animate-pulse">
@@ -39,7 +40,7 @@ export default function TradeHistory({ trades, loading }: Props) {
                                 <th className="text-left py-3 pr-4">Side</th>
                                 <th className="text-right py-3 pr-4">Price</th>
                                 <th className="text-right py-3 pr-4">Qty</th>
-                                <th className="text-right py-3">USDC</th>
+                                <th className="text-right py-3">{quoteAsset}</th>
                             </tr>
                         </thead>
                         <tbody>
@@ -57,8 +58,8 @@ export default function TradeHistory({ trades, loading }: Props) {
                                         })}
                                     </td>
                                     <td className="py-3 pr-4 font-semibold text-text-primary">
-                                        {trade.symbol.replace('USDC', '')}
-                                        <span className="text-text-muted">/USDC</span>
+                                        {trade.symbol.replace(new RegExp(`${quoteAsset}$`), '')}
+                                        <span className="text-text-muted">/{quoteAsset}</span>
                                     </td>
                                     <td className="py-3 pr-4">
                                         <span
@@ -77,7 +78,7 @@ export default function TradeHistory({ trades, loading }: Props) {
                                         {trade.quantity.toFixed(6)}
                                     </td>
                                     <td className="py-3 text-right font-mono text-text-primary">
-                                        ${trade.usdc_amount.toFixed(2)}
+                                        {trade.quote_amount.toFixed(2)}
                                     </td>
                                 </tr>
                             ))}
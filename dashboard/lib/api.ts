@@ -2,7 +2,9 @@ const API_BASE = process.env.NEXT_PUBLIC_API_URL || 'http://localhost:3001';
 
 export interface StatusResponse {
     is_alive: boolean;
-    balance_usdc: number;
+    mode: 'live' | 'testnet' | 'paper';
+    balance: number;
+    quote_asset: string;
     total_pnl: number;
     open_positions: number;
     total_trades: number;
@@ -10,6 +12,15 @@ export interface StatusResponse {
     win_rate: number;
     uptime_hours: number;
     last_cycle_at: string | null;
+    clock_drift_ms: number | null;
+    rate_limit: RateLimitUsage | null;
+}
+
+export interface RateLimitUsage {
+    used_weight_1m: number;
+    weight_budget_1m: number;
+    orders_10s: number;
+    orders_1d: number;
 }
 
 export interface Trade {
@@ -19,23 +30,24 @@ export interface Trade {
     side: string;
     quantity: number;
     price: number;
-    usdc_amount: number;
+    quote_amount: number;
     commission: number | null;
     executed_at: string;
 }
 
 export interface BalanceSnapshot {
     id: number;
-    balance_usdc: number;
+    balance: number;
     open_positions: number;
     total_pnl: number;
     recorded_at: string;
+    quote_asset: string;
 }
 
 export interface CycleLog {
     id: string;
     cycle_number: number;
-    balance_usdc: number;
+    balance: number;
     action: string;
     symbol: string | null;
     confidence: number | null;
@@ -45,6 +57,11 @@ export interface CycleLog {
     result: string | null;
     error: string | null;
     created_at: string;
+    decision_provider: string | null;
+    decision_model: string | null;
+    decision_latency_ms: number | null;
+    decision_input_tokens: number | null;
+    decision_output_tokens: number | null;
 }
 
 export interface Position {
@@ -58,9 +75,29 @@ export interface Position {
     take_profit: number | null;
     status: string;
     pnl: number | null;
+    gross_pnl: number | null;
+    entry_commission: number;
+    trail_pct: number | null;
+    trail_distance: number | null;
+    high_water_mark: number | null;
     opened_at: string;
 }
 
+export interface Discrepancy {
+    id: string;
+    mode: string;
+    kind: 'ORPHANED_HOLDING' | 'PHANTOM_POSITION' | 'QUANTITY_MISMATCH';
+    asset: string;
+    symbol: string;
+    position_id: string | null;
+    expected_qty: number;
+    actual_qty: number;
+    action: 'FLAGGED' | 'ADOPTED' | 'CLOSED';
+    detected_at: string;
+    last_seen_at: string;
+    resolved_at: string | null;
+}
+
 async function fetchApi<T>(endpoint: string): Promise<T> {
     const res = await fetch(`${API_BASE}${endpoint}`, {
         cache: 'no-store',
@@ -75,6 +112,8 @@ export const api = {
     getBalance: () => fetchApi<BalanceSnapshot[]>('/balance'),
     getCycles: () => fetchApi<CycleLog[]>('/cycles'),
     getPositions: () => fetchApi<Position[]>('/positions'),
+    getDiscrepancies: (openOnly = false) =>
+        fetchApi<Discrepancy[]>(`/discrepancies${openOnly ? '?open=true' : ''}`),
 
     trigger: async () => {
         const res = await fetch(`${API_BASE}/trigger`, { method: 'POST' });
@@ -0,0 +1,26 @@
+-- ============================================
+-- Paper trading — virtual ledger and order book
+-- ============================================
+
+-- Paper balances: one row per asset held by the simulator
+CREATE TABLE IF NOT EXISTS paper_balances (
+    asset           VARCHAR(20) PRIMARY KEY,
+    free            DOUBLE PRECISION NOT NULL DEFAULT 0.0 CHECK (free >= 0.0),
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Paper orders: simulated fills, queryable like exchange orders
+CREATE TABLE IF NOT EXISTS paper_orders (
+    order_id                BIGSERIAL PRIMARY KEY,
+    symbol                  VARCHAR(20) NOT NULL,
+    side                    VARCHAR(4) NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    status                  VARCHAR(20) NOT NULL DEFAULT 'FILLED',
+    executed_qty            DOUBLE PRECISION NOT NULL,
+    cummulative_quote_qty   DOUBLE PRECISION NOT NULL,
+    price                   DOUBLE PRECISION NOT NULL,
+    commission              DOUBLE PRECISION NOT NULL DEFAULT 0.0,
+    commission_asset        VARCHAR(20) NOT NULL,
+    created_at              TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_paper_orders_symbol ON paper_orders(symbol);
@@ -0,0 +1,7 @@
+-- ============================================
+-- Exchange-native exits — OCO order tracking on positions
+-- ============================================
+
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS oco_order_list_id BIGINT;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS tp_order_id       BIGINT;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS sl_order_id       BIGINT;
@@ -0,0 +1,8 @@
+-- ============================================
+-- Exchange order IDs on trades — dedupes fills recorded by both
+-- the engine and the user data stream
+-- ============================================
+
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS exchange_order_id BIGINT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_symbol_order_id ON trades(symbol, exchange_order_id);
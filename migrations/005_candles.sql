@@ -0,0 +1,19 @@
+-- ============================================
+-- Candles — OHLCV history from /api/v3/klines
+-- ============================================
+
+CREATE TABLE IF NOT EXISTS candles (
+    symbol          VARCHAR(20) NOT NULL,
+    interval        VARCHAR(4) NOT NULL,
+    open_time       TIMESTAMPTZ NOT NULL,
+    open            DOUBLE PRECISION NOT NULL,
+    high            DOUBLE PRECISION NOT NULL,
+    low             DOUBLE PRECISION NOT NULL,
+    close           DOUBLE PRECISION NOT NULL,
+    volume          DOUBLE PRECISION NOT NULL,
+    quote_volume    DOUBLE PRECISION NOT NULL,
+    trade_count     BIGINT NOT NULL DEFAULT 0,
+    close_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (symbol, interval, open_time)
+);
+-- The primary key index also serves newest-first range scans
@@ -0,0 +1,28 @@
+-- ============================================
+-- Order intents — every market order is written here with its
+-- newClientOrderId before it is sent, so an order whose response
+-- was lost can be looked up and reconciled
+-- ============================================
+
+CREATE TABLE IF NOT EXISTS order_intents (
+    client_order_id     VARCHAR(36) PRIMARY KEY,
+    symbol              VARCHAR(20) NOT NULL,
+    side                VARCHAR(4) NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    amount              DOUBLE PRECISION NOT NULL,        -- USDC for BUY, base quantity for SELL
+    position_id         UUID REFERENCES positions(id),    -- position being sold
+    stop_loss           DOUBLE PRECISION,                 -- requested exits for a BUY
+    take_profit         DOUBLE PRECISION,
+    reason              TEXT,                             -- close reason for a SELL
+    status              VARCHAR(10) NOT NULL DEFAULT 'PENDING'
+                        CHECK (status IN ('PENDING', 'PLACED', 'RECORDED', 'FAILED')),
+    exchange_order_id   BIGINT,
+    error               TEXT,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_order_intents_unresolved ON order_intents(created_at)
+    WHERE status IN ('PENDING', 'PLACED');
+
+-- Paper orders carry the same client order IDs so they can be looked up the same way
+ALTER TABLE paper_orders ADD COLUMN IF NOT EXISTS client_order_id VARCHAR(36) UNIQUE;
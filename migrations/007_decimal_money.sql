@@ -0,0 +1,40 @@
+-- ============================================
+-- Exact money — prices, quantities, balances and PnL move from
+-- DOUBLE PRECISION to NUMERIC so they round-trip Binance's decimal
+-- strings without binary rounding error. Candles stay floating point.
+-- ============================================
+
+ALTER TABLE positions
+    ALTER COLUMN quantity      TYPE NUMERIC USING quantity::numeric,
+    ALTER COLUMN entry_price   TYPE NUMERIC USING entry_price::numeric,
+    ALTER COLUMN current_price TYPE NUMERIC USING current_price::numeric,
+    ALTER COLUMN stop_loss     TYPE NUMERIC USING stop_loss::numeric,
+    ALTER COLUMN take_profit   TYPE NUMERIC USING take_profit::numeric,
+    ALTER COLUMN pnl           TYPE NUMERIC USING pnl::numeric;
+
+ALTER TABLE trades
+    ALTER COLUMN quantity    TYPE NUMERIC USING quantity::numeric,
+    ALTER COLUMN price       TYPE NUMERIC USING price::numeric,
+    ALTER COLUMN usdc_amount TYPE NUMERIC USING usdc_amount::numeric,
+    ALTER COLUMN commission  TYPE NUMERIC USING commission::numeric;
+
+ALTER TABLE cycle_logs
+    ALTER COLUMN balance_usdc TYPE NUMERIC USING balance_usdc::numeric;
+
+ALTER TABLE balance_history
+    ALTER COLUMN balance_usdc TYPE NUMERIC USING balance_usdc::numeric,
+    ALTER COLUMN total_pnl    TYPE NUMERIC USING total_pnl::numeric;
+
+ALTER TABLE paper_balances
+    ALTER COLUMN free TYPE NUMERIC USING free::numeric;
+
+ALTER TABLE paper_orders
+    ALTER COLUMN executed_qty          TYPE NUMERIC USING executed_qty::numeric,
+    ALTER COLUMN cummulative_quote_qty TYPE NUMERIC USING cummulative_quote_qty::numeric,
+    ALTER COLUMN price                 TYPE NUMERIC USING price::numeric,
+    ALTER COLUMN commission            TYPE NUMERIC USING commission::numeric;
+
+ALTER TABLE order_intents
+    ALTER COLUMN amount      TYPE NUMERIC USING amount::numeric,
+    ALTER COLUMN stop_loss   TYPE NUMERIC USING stop_loss::numeric,
+    ALTER COLUMN take_profit TYPE NUMERIC USING take_profit::numeric;
@@ -0,0 +1,11 @@
+-- ============================================
+-- Fee-adjusted PnL — commission is valued in USDC at fill time.
+-- positions.pnl is net of entry and exit commission; gross_pnl is
+-- the price move alone. trades.commission is now in USDC.
+-- ============================================
+
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS entry_commission NUMERIC NOT NULL DEFAULT 0;
+ALTER TABLE positions ADD COLUMN IF NOT EXISTS gross_pnl NUMERIC;
+
+-- Positions closed before this migration were recorded without fees
+UPDATE positions SET gross_pnl = pnl WHERE status = 'CLOSED' AND gross_pnl IS NULL;
@@ -0,0 +1,13 @@
+-- ============================================
+-- Configurable quote asset — amounts that were implicitly USDC are now
+-- in whatever QUOTE_ASSET is configured. Columns lose the "usdc" in their
+-- name, and balance snapshots record which asset they were measured in so
+-- a history spanning a quote-asset change isn't read as one series.
+-- ============================================
+
+ALTER TABLE trades RENAME COLUMN usdc_amount TO quote_amount;
+
+ALTER TABLE cycle_logs RENAME COLUMN balance_usdc TO balance;
+
+ALTER TABLE balance_history RENAME COLUMN balance_usdc TO balance;
+ALTER TABLE balance_history ADD COLUMN quote_asset VARCHAR(10) NOT NULL DEFAULT 'USDC';
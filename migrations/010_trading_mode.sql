@@ -0,0 +1,31 @@
+-- ============================================
+-- Trading mode — every position, trade, cycle, balance snapshot and
+-- order intent records whether it happened live, on the Binance Spot
+-- Testnet or on the paper ledger, and each mode only reads its own
+-- rows. Rows written before this migration are assumed live.
+-- ============================================
+
+ALTER TABLE positions       ADD COLUMN mode VARCHAR(10) NOT NULL DEFAULT 'live';
+ALTER TABLE trades          ADD COLUMN mode VARCHAR(10) NOT NULL DEFAULT 'live';
+ALTER TABLE cycle_logs      ADD COLUMN mode VARCHAR(10) NOT NULL DEFAULT 'live';
+ALTER TABLE balance_history ADD COLUMN mode VARCHAR(10) NOT NULL DEFAULT 'live';
+ALTER TABLE order_intents   ADD COLUMN mode VARCHAR(10) NOT NULL DEFAULT 'live';
+
+-- The default only backfills history; new rows must say which mode wrote them
+ALTER TABLE positions       ALTER COLUMN mode DROP DEFAULT,
+    ADD CONSTRAINT positions_mode_check       CHECK (mode IN ('live', 'testnet', 'paper'));
+ALTER TABLE trades          ALTER COLUMN mode DROP DEFAULT,
+    ADD CONSTRAINT trades_mode_check          CHECK (mode IN ('live', 'testnet', 'paper'));
+ALTER TABLE cycle_logs      ALTER COLUMN mode DROP DEFAULT,
+    ADD CONSTRAINT cycle_logs_mode_check      CHECK (mode IN ('live', 'testnet', 'paper'));
+ALTER TABLE balance_history ALTER COLUMN mode DROP DEFAULT,
+    ADD CONSTRAINT balance_history_mode_check CHECK (mode IN ('live', 'testnet', 'paper'));
+ALTER TABLE order_intents   ALTER COLUMN mode DROP DEFAULT,
+    ADD CONSTRAINT order_intents_mode_check   CHECK (mode IN ('live', 'testnet', 'paper'));
+
+-- Testnet and live order IDs come from separate sequences and can collide
+DROP INDEX IF EXISTS idx_trades_symbol_order_id;
+CREATE UNIQUE INDEX idx_trades_mode_symbol_order_id ON trades(mode, symbol, exchange_order_id);
+
+CREATE INDEX idx_positions_mode_status ON positions(mode, status);
+CREATE INDEX idx_cycle_logs_mode_created ON cycle_logs(mode, created_at DESC);
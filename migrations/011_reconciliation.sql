@@ -0,0 +1,27 @@
+-- ============================================
+-- Position reconciliation — disagreements between open positions and
+-- what the exchange account actually holds. One open row per
+-- (mode, kind, asset) is refreshed while the disagreement lasts and
+-- resolved once it clears.
+-- ============================================
+
+CREATE TABLE IF NOT EXISTS discrepancies (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    mode            VARCHAR(10) NOT NULL CHECK (mode IN ('live', 'testnet', 'paper')),
+    kind            VARCHAR(20) NOT NULL
+                    CHECK (kind IN ('ORPHANED_HOLDING', 'PHANTOM_POSITION', 'QUANTITY_MISMATCH')),
+    asset           VARCHAR(20) NOT NULL,
+    symbol          VARCHAR(20) NOT NULL,
+    position_id     UUID REFERENCES positions(id),    -- adopted or closed position
+    expected_qty    NUMERIC NOT NULL,                 -- total of open positions
+    actual_qty      NUMERIC NOT NULL,                 -- free + locked on the exchange
+    action          VARCHAR(10) NOT NULL DEFAULT 'FLAGGED'
+                    CHECK (action IN ('FLAGGED', 'ADOPTED', 'CLOSED')),
+    detected_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at     TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX idx_discrepancies_open ON discrepancies(mode, kind, asset)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_discrepancies_mode_detected ON discrepancies(mode, detected_at DESC);
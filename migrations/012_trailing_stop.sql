@@ -0,0 +1,17 @@
+-- ============================================
+-- Trailing stops — a position can have its stop-loss follow the highest
+-- price seen since entry, either a percentage or a fixed price distance
+-- (an ATR multiple resolved at entry) below it. The high-water mark is
+-- kept on the row so the stop survives restarts.
+-- ============================================
+
+ALTER TABLE positions
+    ADD COLUMN trail_pct        NUMERIC,    -- stop follows this % below the high
+    ADD COLUMN trail_distance   NUMERIC,    -- or this far below it, in the quote asset
+    ADD COLUMN high_water_mark  NUMERIC,    -- highest price since entry
+    ADD CONSTRAINT positions_single_trail CHECK (trail_pct IS NULL OR trail_distance IS NULL);
+
+-- The requested trail travels with the BUY so a recovered order keeps it
+ALTER TABLE order_intents
+    ADD COLUMN trail_pct            NUMERIC,
+    ADD COLUMN trail_atr_multiple   NUMERIC;
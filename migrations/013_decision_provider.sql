@@ -0,0 +1,10 @@
+-- ============================================
+-- Decision providers — each cycle records which provider answered
+-- (the Discord/OpenClaw relay or another), the model when known, and
+-- how long the answer took.
+-- ============================================
+
+ALTER TABLE cycle_logs
+    ADD COLUMN decision_provider    VARCHAR(20),
+    ADD COLUMN decision_model       VARCHAR(100),
+    ADD COLUMN decision_latency_ms  INTEGER;
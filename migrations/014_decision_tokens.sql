@@ -0,0 +1,9 @@
+-- ============================================
+-- Token usage — cycles answered by a directly called LLM record the
+-- prompt and completion tokens it billed, so cost per decision can be
+-- tracked alongside latency.
+-- ============================================
+
+ALTER TABLE cycle_logs
+    ADD COLUMN decision_input_tokens   INTEGER,
+    ADD COLUMN decision_output_tokens  INTEGER;
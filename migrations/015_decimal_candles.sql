@@ -0,0 +1,13 @@
+-- ============================================
+-- Exact candles — OHLCV moves from DOUBLE PRECISION to NUMERIC like the
+-- money columns did in 007. ATR trailing stops are measured from these
+-- prices, so they now carry Binance's decimal strings unrounded.
+-- ============================================
+
+ALTER TABLE candles
+    ALTER COLUMN open         TYPE NUMERIC USING open::numeric,
+    ALTER COLUMN high         TYPE NUMERIC USING high::numeric,
+    ALTER COLUMN low          TYPE NUMERIC USING low::numeric,
+    ALTER COLUMN close        TYPE NUMERIC USING close::numeric,
+    ALTER COLUMN volume       TYPE NUMERIC USING volume::numeric,
+    ALTER COLUMN quote_volume TYPE NUMERIC USING quote_volume::numeric;
@@ -1,9 +1,10 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::Json,
 };
 use chrono::Utc;
+use serde::Deserialize;
 use std::sync::Arc;
 use tracing::info;
 
@@ -27,36 +28,36 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Result<Json<StatusRes
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let balance = queries::get_balance_history(&state.pool, 1)
+    let balance = queries::get_balance_history(&state.pool, state.config.mode, 1)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .first()
-        .map(|b| b.balance_usdc)
-        .unwrap_or(0.0);
+        .map(|b| b.balance)
+        .unwrap_or_default();
 
-    let total_pnl = queries::get_total_pnl(&state.pool)
+    let total_pnl = queries::get_total_pnl(&state.pool, state.config.mode)
         .await
-        .unwrap_or(0.0);
+        .unwrap_or_default();
 
-    let open_positions = queries::count_open_positions(&state.pool)
+    let open_positions = queries::count_open_positions(&state.pool, state.config.mode)
         .await
         .unwrap_or(0) as i32;
 
-    let total_trades = queries::count_trades(&state.pool)
+    let total_trades = queries::count_trades(&state.pool, state.config.mode)
         .await
         .unwrap_or(0);
 
-    let total_cycles = queries::count_cycles(&state.pool)
+    let total_cycles = queries::count_cycles(&state.pool, state.config.mode)
         .await
         .unwrap_or(0);
 
-    let win_rate = queries::get_win_rate(&state.pool)
+    let win_rate = queries::get_win_rate(&state.pool, state.config.mode)
         .await
         .unwrap_or(0.0);
 
     let uptime_hours = (Utc::now() - bot.started_at).num_minutes() as f64 / 60.0;
 
-    let last_cycle = queries::get_recent_cycles(&state.pool, 1)
+    let last_cycle = queries::get_recent_cycles(&state.pool, state.config.mode, 1)
         .await
         .unwrap_or_default()
         .first()
@@ -64,7 +65,9 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Result<Json<StatusRes
 
     Ok(Json(StatusResponse {
         is_alive: !bot.is_dead,
-        balance_usdc: balance,
+        mode: state.config.mode.to_string(),
+        balance,
+        quote_asset: state.config.quote_asset.clone(),
         total_pnl,
         open_positions,
         total_trades,
@@ -72,12 +75,14 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Result<Json<StatusRes
         win_rate,
         uptime_hours,
         last_cycle_at: last_cycle,
+        clock_drift_ms: state.exchange.clock_drift_ms(),
+        rate_limit: state.exchange.rate_limit_usage(),
     }))
 }
 
 /// GET /trades — Recent 50 trades
 pub async fn trades(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Trade>>, StatusCode> {
-    let trades = queries::get_recent_trades(&state.pool, 50)
+    let trades = queries::get_recent_trades(&state.pool, state.config.mode, 50)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(trades))
@@ -87,7 +92,7 @@ pub async fn trades(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Trade
 pub async fn balance_history(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Vec<BalanceHistory>>, StatusCode> {
-    let history = queries::get_balance_history(&state.pool, 100)
+    let history = queries::get_balance_history(&state.pool, state.config.mode, 100)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(history))
@@ -95,7 +100,7 @@ pub async fn balance_history(
 
 /// GET /cycles — Recent 50 cycle logs
 pub async fn cycles(State(state): State<Arc<AppState>>) -> Result<Json<Vec<CycleLog>>, StatusCode> {
-    let logs = queries::get_recent_cycles(&state.pool, 50)
+    let logs = queries::get_recent_cycles(&state.pool, state.config.mode, 50)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(logs))
@@ -103,26 +108,60 @@ pub async fn cycles(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Cycle
 
 /// GET /positions — Open positions
 pub async fn positions(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Position>>, StatusCode> {
-    let positions = queries::get_open_positions(&state.pool)
+    let positions = queries::get_open_positions(&state.pool, state.config.mode)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(positions))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DiscrepancyQuery {
+    pub open: Option<bool>,
+}
+
+/// GET /discrepancies?open=true — Position reconciliation findings, newest first
+pub async fn discrepancies(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<DiscrepancyQuery>,
+) -> Result<Json<Vec<Discrepancy>>, StatusCode> {
+    let discrepancies =
+        queries::get_discrepancies(&state.pool, state.config.mode, q.open.unwrap_or(false), 100)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(discrepancies))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CandleQuery {
+    pub symbol: String,
+    pub interval: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /candles?symbol=BTCUSDC&interval=1h&limit=100 — Stored OHLCV history, oldest first
+pub async fn candles(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<CandleQuery>,
+) -> Result<Json<Vec<Candle>>, StatusCode> {
+    let interval = q
+        .interval
+        .or_else(|| state.config.kline_intervals.first().cloned())
+        .unwrap_or_else(|| "1h".to_string());
+    let limit = q.limit.unwrap_or(100).clamp(1, 1000);
+
+    let candles = queries::get_candles(&state.pool, &q.symbol.to_uppercase(), &interval, limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(candles))
+}
+
 /// POST /trigger — Manually trigger a trading cycle
 pub async fn trigger(State(state): State<Arc<AppState>>) -> &'static str {
     info!("🔧 Manual cycle trigger received");
 
-    let pool = state.pool.clone();
-    let config = state.config.clone();
-    let binance = state.binance.clone();
-    let discord = state.discord.clone();
-    let broadcast_tx = state.broadcast_tx.clone();
+    let engine = state.engine.clone();
 
     tokio::spawn(async move {
-        let engine = crate::trading::TradingEngine::new(
-            config, pool, binance, discord, broadcast_tx,
-        );
         if let Err(e) = engine.run_cycle().await {
             tracing::error!(error = %e, "Manual cycle failed");
         }
@@ -10,7 +10,7 @@ use tracing::{info, warn};
 
 use super::super::AppState;
 
-/// GET /ws — WebSocket upgrade for real-time cycle updates
+/// GET /ws — WebSocket upgrade for real-time cycle updates and price ticks
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
@@ -18,6 +18,8 @@ pub async fn ws_handler(
     ws.on_upgrade(move |socket| handle_socket(socket, state))
 }
 
+// Collapsing into a match guard would move `data` inside the guard
+#[allow(clippy::collapsible_match)]
 async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
     info!("New WebSocket client connected");
 
@@ -25,14 +27,14 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
 
     loop {
         tokio::select! {
-            // Receive broadcast cycle updates and forward to client
+            // Receive broadcast events and forward to client
             result = rx.recv() => {
                 match result {
                     Ok(update) => {
                         let json = match serde_json::to_string(&update) {
                             Ok(j) => j,
                             Err(e) => {
-                                warn!(error = %e, "Failed to serialize cycle update");
+                                warn!(error = %e, "Failed to serialize dashboard event");
                                 continue;
                             }
                         };
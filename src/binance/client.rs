@@ -1,27 +1,115 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use hmac::{Hmac, Mac};
+use reqwest::header::RETRY_AFTER;
+use reqwest::Method;
+use rust_decimal::Decimal;
 use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use super::error::BinanceError;
+use super::filters::SymbolFilters;
+use super::rate_limit::{order_count, request_weight, RateLimiter};
 use super::types::*;
+use super::user_stream::BalanceCache;
+use crate::db::models::RateLimitUsage;
+use crate::exchange::Exchange;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// How long exchangeInfo filters are trusted before re-fetching
+const FILTER_CACHE_TTL: Duration = Duration::from_secs(3600);
+/// Retries after the first attempt for transient failures
+const MAX_RETRIES: u32 = 3;
+/// First backoff delay; doubles on every retry
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest wait worth sitting out inside a cycle. Longer Retry-After
+/// values (e.g. an IP ban) are surfaced to the caller instead.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Binance endpoint security types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Security {
+    /// Market data, no credentials
+    Public,
+    /// API key header only (user data stream endpoints)
+    ApiKey,
+    /// API key header plus timestamp and HMAC signature
+    Signed,
+}
+
+/// Server-minus-local clock offset, taking the server's timestamp as the
+/// midpoint of the request's round trip
+fn clock_offset(server_time: i64, sent_at: i64, received_at: i64) -> i64 {
+    server_time - (sent_at + received_at) / 2
+}
+
+/// Exponential backoff delay before retry number `attempt` (0-based)
+fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF * 2u32.pow(attempt)
+}
+
 #[derive(Clone)]
 pub struct BinanceClient {
     base_url: String,
     api_key: String,
     secret_key: String,
     http: reqwest::Client,
+    filters: Arc<RwLock<FilterCache>>,
+    balances: BalanceCache,
+    clock: Arc<ClockOffset>,
+    recv_window_ms: u64,
+    limiter: RateLimiter,
+    quote_asset: String,
+}
+
+/// Measured `server time - local time`, shared by all clones of the client
+#[derive(Default)]
+struct ClockOffset {
+    offset_ms: AtomicI64,
+    synced: AtomicBool,
+}
+
+/// exchangeInfo symbol filters, shared by all clones of the client
+#[derive(Default)]
+struct FilterCache {
+    by_symbol: HashMap<String, SymbolFilters>,
+    loaded_at: Option<Instant>,
+}
+
+impl FilterCache {
+    fn is_fresh(&self) -> bool {
+        self.loaded_at
+            .map(|t| t.elapsed() < FILTER_CACHE_TTL)
+            .unwrap_or(false)
+    }
 }
 
 impl BinanceClient {
-    pub fn new(base_url: &str, api_key: &str, secret_key: &str) -> Self {
+    pub fn new(
+        base_url: &str,
+        api_key: &str,
+        secret_key: &str,
+        recv_window_ms: u64,
+        weight_budget: u32,
+        quote_asset: &str,
+    ) -> Self {
         Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             api_key: api_key.to_string(),
             secret_key: secret_key.to_string(),
             http: reqwest::Client::new(),
+            filters: Arc::new(RwLock::new(FilterCache::default())),
+            balances: BalanceCache::default(),
+            clock: Arc::new(ClockOffset::default()),
+            recv_window_ms,
+            limiter: RateLimiter::new(weight_budget),
+            quote_asset: quote_asset.to_string(),
         }
     }
 
@@ -33,164 +121,369 @@ impl BinanceClient {
         hex::encode(mac.finalize().into_bytes())
     }
 
-    /// Get current timestamp in milliseconds
-    fn timestamp() -> u64 {
-        chrono::Utc::now().timestamp_millis() as u64
+    /// Current Binance server time in milliseconds: local clock plus the measured offset
+    fn timestamp(&self) -> u64 {
+        (chrono::Utc::now().timestamp_millis() + self.clock.offset_ms.load(Ordering::Relaxed)) as u64
     }
 
-    /// Get USDC balance
-    pub async fn get_usdc_balance(&self) -> Result<f64> {
-        let timestamp = Self::timestamp();
-        let query = format!("timestamp={}", timestamp);
-        let signature = self.sign(&query);
+    /// Measured server-minus-local clock offset in ms, once the first sync succeeded
+    pub fn clock_drift_ms(&self) -> Option<i64> {
+        self.clock
+            .synced
+            .load(Ordering::Relaxed)
+            .then(|| self.clock.offset_ms.load(Ordering::Relaxed))
+    }
 
-        let url = format!(
-            "{}/api/v3/account?{}&signature={}",
-            self.base_url, query, signature
-        );
+    /// Sample `/api/v3/time` and store the offset, assuming the server read
+    /// its clock halfway through the round trip
+    pub async fn sync_time(&self) -> Result<i64> {
+        let sent_at = chrono::Utc::now().timestamp_millis();
+        let body = self
+            .send_once(Method::GET, "/api/v3/time", "", Security::Public)
+            .await?;
+        let received_at = chrono::Utc::now().timestamp_millis();
+
+        let time: ServerTime =
+            serde_json::from_str(&body).context("Failed to parse server time")?;
+        let offset = clock_offset(time.server_time, sent_at, received_at);
+
+        self.clock.offset_ms.store(offset, Ordering::Relaxed);
+        self.clock.synced.store(true, Ordering::Relaxed);
+        Ok(offset)
+    }
 
-        let resp = self
-            .http
-            .get(&url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await
-            .context("Failed to fetch Binance account")?;
+    /// Re-sync the clock offset forever at the given interval
+    pub async fn run_time_sync(self, every: Duration) {
+        let mut ticker = tokio::time::interval(every);
+        ticker.tick().await; // First tick fires immediately; startup already synced
+        loop {
+            ticker.tick().await;
+            match self.sync_time().await {
+                Ok(offset_ms) => info!(offset_ms, "🕐 Binance server time synced"),
+                Err(e) => warn!(error = %e, "Binance server time sync failed"),
+            }
+        }
+    }
 
+    /// Send a request, retrying transient failures with exponential backoff
+    /// (or the server's Retry-After). Returns the response body.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        params: &str,
+        security: Security,
+    ) -> Result<String, BinanceError> {
+        // A repeated order placement could fill twice if the first one landed
+        let idempotent = !(method == Method::POST && security == Security::Signed);
+
+        let mut attempt = 0;
+        loop {
+            let err = match self.send_once(method.clone(), path, params, security).await {
+                Ok(body) => return Ok(body),
+                Err(e) => e,
+            };
+
+            let delay = err.retry_after().unwrap_or_else(|| backoff(attempt));
+            if attempt >= MAX_RETRIES || !err.is_transient(idempotent) || delay > MAX_RETRY_DELAY {
+                warn!(path, error = %err, "Binance request failed");
+                return Err(err);
+            }
+
+            // Our clock drifted past recvWindow — re-measure before trying again
+            if matches!(err, BinanceError::Timestamp { .. }) {
+                if let Err(e) = self.sync_time().await {
+                    warn!(error = %e, "Server time resync failed");
+                }
+            }
+
+            attempt += 1;
+            warn!(
+                path,
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                error = %err,
+                "Transient Binance error — retrying"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// One attempt. Signed requests get a fresh timestamp and signature each time.
+    async fn send_once(
+        &self,
+        method: Method,
+        path: &str,
+        params: &str,
+        security: Security,
+    ) -> Result<String, BinanceError> {
+        // The limiter may hold us past recvWindow, so stamp and sign only after it
+        self.limiter
+            .acquire(request_weight(&method, path, params), order_count(&method, path))
+            .await?;
+
+        let query = match security {
+            Security::Signed => {
+                let auth = format!(
+                    "recvWindow={}&timestamp={}",
+                    self.recv_window_ms,
+                    self.timestamp()
+                );
+                let query = if params.is_empty() {
+                    auth
+                } else {
+                    format!("{}&{}", params, auth)
+                };
+                format!("{}&signature={}", query, self.sign(&query))
+            }
+            Security::Public | Security::ApiKey => params.to_string(),
+        };
+
+        let url = if query.is_empty() {
+            format!("{}{}", self.base_url, path)
+        } else {
+            format!("{}{}?{}", self.base_url, path, query)
+        };
+
+        let mut req = self.http.request(method, &url);
+        if security != Security::Public {
+            req = req.header("X-MBX-APIKEY", &self.api_key);
+        }
+
+        let resp = req.send().await?;
         let status = resp.status();
-        let body = resp.text().await.context("Failed to read account response")?;
+        self.limiter.record(resp.headers());
+        let retry_after = resp
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs);
+        let body = resp.text().await?;
 
-        if !status.is_success() {
-            warn!(status = %status, body = %body, "Binance account request failed");
-            anyhow::bail!("Binance account failed ({}): {}", status, body);
+        if status.is_success() {
+            return Ok(body);
         }
 
+        let err = BinanceError::from_response(status.as_u16(), retry_after, &body);
+        if matches!(status.as_u16(), 418 | 429) {
+            // Every further call before Retry-After extends an IP ban
+            self.limiter.block_for(retry_after);
+        }
+        Err(err)
+    }
+
+    /// Request weight and order counts used in the current windows
+    pub fn rate_limit_usage(&self) -> RateLimitUsage {
+        self.limiter.usage()
+    }
+
+    /// Account balances, kept live by the user data stream when it is running
+    pub fn balances(&self) -> &BalanceCache {
+        &self.balances
+    }
+
+    /// Fetch the full account snapshot from `/api/v3/account`
+    async fn fetch_account(&self) -> Result<AccountInfo> {
+        let body = self
+            .send(Method::GET, "/api/v3/account", "", Security::Signed)
+            .await?;
+
         let account: AccountInfo = serde_json::from_str(&body)
             .context(format!("Failed to parse account info. Response: {}", &body[..body.len().min(500)]))?;
+        Ok(account)
+    }
 
-        let usdc_balance = account
-            .balances
-            .iter()
-            .find(|b| b.asset == "USDC")
-            .map(|b| b.free.parse::<f64>().unwrap_or(0.0))
-            .unwrap_or(0.0);
-
-        info!(balance = usdc_balance, "USDC balance fetched");
-        Ok(usdc_balance)
+    /// Load every balance into the cache and mark it live
+    pub async fn seed_balance_cache(&self) -> Result<()> {
+        let account = self.fetch_account().await?;
+        self.balances.seed(&account.balances);
+        Ok(())
     }
 
-    /// Get all non-zero balances (for position reconciliation)
-    pub async fn get_all_balances(&self) -> Result<Vec<(String, f64)>> {
-        let timestamp = Self::timestamp();
-        let query = format!("timestamp={}", timestamp);
-        let signature = self.sign(&query);
-
-        let url = format!(
-            "{}/api/v3/account?{}&signature={}",
-            self.base_url, query, signature
-        );
+    /// The asset traded pairs are quoted in
+    pub fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
 
-        let resp = self
-            .http
-            .get(&url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await
-            .context("Failed to fetch Binance account")?;
+    /// Get free quote-asset balance
+    pub async fn get_quote_balance(&self) -> Result<Decimal> {
+        if let Some(balance) = self.balances.free(&self.quote_asset) {
+            return Ok(balance);
+        }
 
-        let account: AccountInfo = resp.json().await?;
+        let account = self.fetch_account().await?;
 
-        let balances: Vec<(String, f64)> = account
+        let balance = account
             .balances
             .iter()
-            .filter_map(|b| {
-                let free: f64 = b.free.parse().unwrap_or(0.0);
-                let locked: f64 = b.locked.parse().unwrap_or(0.0);
-                let total = free + locked;
-                if total > 0.0 && b.asset != "USDC" {
-                    Some((b.asset.clone(), total))
-                } else {
-                    None
-                }
-            })
+            .find(|b| b.asset == self.quote_asset)
+            .map(|b| b.free)
+            .unwrap_or_default();
+
+        info!(%balance, asset = %self.quote_asset, "Quote balance fetched");
+        Ok(balance)
+    }
+
+    /// Get all non-zero balances (for position reconciliation)
+    pub async fn get_all_balances(&self) -> Result<Vec<(String, Decimal)>> {
+        let totals = match self.balances.totals() {
+            Some(totals) => totals,
+            None => self
+                .fetch_account()
+                .await?
+                .balances
+                .iter()
+                .map(|b| (b.asset.clone(), b.free + b.locked))
+                .collect(),
+        };
+
+        let balances: Vec<(String, Decimal)> = totals
+            .into_iter()
+            .filter(|(asset, total)| *total > Decimal::ZERO && *asset != self.quote_asset)
             .collect();
 
         Ok(balances)
     }
 
-    /// Get 24h ticker data for USDC trading pairs
-    pub async fn get_tickers(&self) -> Result<Vec<Ticker24h>> {
-        let url = format!("{}/api/v3/ticker/24hr", self.base_url);
+    /// Open a user data stream session and return its listenKey
+    pub async fn create_listen_key(&self) -> Result<String> {
+        let body = self
+            .send(Method::POST, "/api/v3/userDataStream", "", Security::ApiKey)
+            .await?;
 
-        let resp = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to fetch tickers")?;
+        let key: ListenKey =
+            serde_json::from_str(&body).context("Failed to parse listenKey response")?;
+        Ok(key.listen_key)
+    }
+
+    /// Extend a listenKey's validity by another 60 minutes
+    pub async fn keepalive_listen_key(&self, listen_key: &str) -> Result<()> {
+        let params = format!("listenKey={}", listen_key);
+        self.send(Method::PUT, "/api/v3/userDataStream", &params, Security::ApiKey)
+            .await?;
+        Ok(())
+    }
+
+    /// Get 24h ticker data for pairs quoted in the quote asset
+    pub async fn get_tickers(&self) -> Result<Vec<Ticker24h>> {
+        let body = self
+            .send(Method::GET, "/api/v3/ticker/24hr", "", Security::Public)
+            .await?;
 
-        let all_tickers: Vec<Ticker24h> = resp.json().await?;
+        let all_tickers: Vec<Ticker24h> =
+            serde_json::from_str(&body).context("Failed to parse tickers response")?;
 
-        // Filter to only USDC pairs
-        let usdc_tickers: Vec<Ticker24h> = all_tickers
+        // Filter to only pairs in our quote asset
+        let tickers: Vec<Ticker24h> = all_tickers
             .into_iter()
-            .filter(|t| t.symbol.ends_with("USDC"))
+            .filter(|t| t.symbol.ends_with(self.quote_asset.as_str()))
             .collect();
 
-        info!(count = usdc_tickers.len(), "USDC tickers fetched");
-        Ok(usdc_tickers)
+        let usage = self.limiter.usage();
+        info!(
+            count = tickers.len(),
+            used_weight = usage.used_weight_1m,
+            weight_budget = usage.weight_budget_1m,
+            "Quote tickers fetched"
+        );
+        Ok(tickers)
     }
 
     /// Get 24h ticker for a specific symbol
     pub async fn get_ticker(&self, symbol: &str) -> Result<Ticker24h> {
-        let url = format!(
-            "{}/api/v3/ticker/24hr?symbol={}",
-            self.base_url, symbol
-        );
-
-        let resp = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to fetch ticker")?;
+        let params = format!("symbol={}", symbol);
+        let body = self
+            .send(Method::GET, "/api/v3/ticker/24hr", &params, Security::Public)
+            .await?;
 
-        let ticker: Ticker24h = resp.json().await?;
+        let ticker: Ticker24h =
+            serde_json::from_str(&body).context("Failed to parse ticker response")?;
         Ok(ticker)
     }
 
-    /// Execute a market buy order (denominated in USDC)
-    pub async fn market_buy(&self, symbol: &str, usdc_amount: f64) -> Result<OrderResponse> {
-        info!(symbol, usdc_amount, "Executing market BUY");
+    /// Get up to `limit` most recent candles for a symbol and interval
+    pub async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        let params = format!("symbol={}&interval={}&limit={}", symbol, interval, limit);
+        let body = self
+            .send(Method::GET, "/api/v3/klines", &params, Security::Public)
+            .await?;
 
-        let timestamp = Self::timestamp();
-        let query = format!(
-            "symbol={}&side=BUY&type=MARKET&quoteOrderQty={:.2}&timestamp={}",
-            symbol, usdc_amount, timestamp
-        );
-        let signature = self.sign(&query);
+        let rows: Vec<RawKline> =
+            serde_json::from_str(&body).context("Failed to parse klines response")?;
+        Ok(rows.iter().filter_map(RawKline::to_kline).collect())
+    }
 
-        let url = format!(
-            "{}/api/v3/order?{}&signature={}",
-            self.base_url, query, signature
-        );
+    /// Get trading filters for a symbol, refreshing the exchangeInfo cache when stale
+    pub async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        {
+            let cache = self.filters.read().await;
+            if cache.is_fresh() {
+                if let Some(filters) = cache.by_symbol.get(symbol) {
+                    return Ok(filters.clone());
+                }
+            }
+        }
 
-        let resp = self
-            .http
-            .post(&url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await
-            .context("Failed to execute market buy")?;
+        self.refresh_exchange_info().await?;
 
-        let status = resp.status();
-        let body = resp.text().await?;
+        let cache = self.filters.read().await;
+        cache
+            .by_symbol
+            .get(symbol)
+            .cloned()
+            .with_context(|| format!("Symbol {} not listed in exchangeInfo", symbol))
+    }
 
-        if !status.is_success() {
-            warn!(status = %status, body = %body, "Binance order failed");
-            anyhow::bail!("Binance order failed ({}): {}", status, body);
+    /// Reload filters for all spot symbols from `/api/v3/exchangeInfo`
+    async fn refresh_exchange_info(&self) -> Result<()> {
+        let body = self
+            .send(Method::GET, "/api/v3/exchangeInfo", "permissions=SPOT", Security::Public)
+            .await?;
+
+        let info: ExchangeInfo =
+            serde_json::from_str(&body).context("Failed to parse exchangeInfo")?;
+
+        let mut by_symbol = HashMap::with_capacity(info.symbols.len());
+        for symbol in &info.symbols {
+            match SymbolFilters::from_symbol_info(symbol) {
+                Ok(filters) => {
+                    by_symbol.insert(symbol.symbol.clone(), filters);
+                }
+                Err(e) => warn!(symbol = %symbol.symbol, error = %e, "Skipping symbol with bad filters"),
+            }
         }
 
+        info!(count = by_symbol.len(), "exchangeInfo filters cached");
+        let mut cache = self.filters.write().await;
+        cache.by_symbol = by_symbol;
+        cache.loaded_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Execute a market buy order (denominated in the quote asset)
+    pub async fn market_buy(
+        &self,
+        symbol: &str,
+        quote_amount: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        info!(symbol, %quote_amount, client_order_id, "Executing market BUY");
+
+        let filters = self.symbol_filters(symbol).await?;
+        let quote_qty = filters.quantize_quote(quote_amount);
+        filters.check_notional(quote_qty)?;
+
+        let params = format!(
+            "symbol={}&side=BUY&type=MARKET&quoteOrderQty={}&newClientOrderId={}",
+            symbol,
+            filters.format_quote(quote_qty),
+            client_order_id
+        );
+        let body = self
+            .send(Method::POST, "/api/v3/order", &params, Security::Signed)
+            .await?;
+
         let order: OrderResponse =
             serde_json::from_str(&body).context("Failed to parse order response")?;
 
@@ -203,37 +496,33 @@ impl BinanceClient {
     }
 
     /// Execute a market sell order (denominated in coin quantity)
-    pub async fn market_sell(&self, symbol: &str, quantity: f64) -> Result<OrderResponse> {
-        info!(symbol, quantity, "Executing market SELL");
-
-        let timestamp = Self::timestamp();
-        // Use appropriate precision for quantity
-        let query = format!(
-            "symbol={}&side=SELL&type=MARKET&quantity={:.8}&timestamp={}",
-            symbol, quantity, timestamp
-        );
-        let signature = self.sign(&query);
+    pub async fn market_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        info!(symbol, %quantity, client_order_id, "Executing market SELL");
+
+        let filters = self.symbol_filters(symbol).await?;
+        let quantity = filters.quantize_qty(quantity);
+        filters.check_qty(quantity)?;
+        if filters.min_notional > Decimal::ZERO {
+            let price = self.get_ticker(symbol).await?.last_price;
+            if price > Decimal::ZERO {
+                filters.check_notional(quantity * price)?;
+            }
+        }
 
-        let url = format!(
-            "{}/api/v3/order?{}&signature={}",
-            self.base_url, query, signature
+        let params = format!(
+            "symbol={}&side=SELL&type=MARKET&quantity={}&newClientOrderId={}",
+            symbol,
+            filters.format_qty(quantity),
+            client_order_id
         );
-
-        let resp = self
-            .http
-            .post(&url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await
-            .context("Failed to execute market sell")?;
-
-        let status = resp.status();
-        let body = resp.text().await?;
-
-        if !status.is_success() {
-            warn!(status = %status, body = %body, "Binance sell order failed");
-            anyhow::bail!("Binance sell order failed ({}): {}", status, body);
-        }
+        let body = self
+            .send(Method::POST, "/api/v3/order", &params, Security::Signed)
+            .await?;
 
         let order: OrderResponse =
             serde_json::from_str(&body).context("Failed to parse sell order response")?;
@@ -245,4 +534,225 @@ impl BinanceClient {
         );
         Ok(order)
     }
+
+    /// Place an OCO sell: a LIMIT_MAKER take-profit above the market and a
+    /// STOP_LOSS_LIMIT below it. When one leg fills Binance cancels the other.
+    pub async fn place_oco_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        take_profit: Decimal,
+        stop_price: Decimal,
+        stop_limit_price: Decimal,
+    ) -> Result<OcoOrder> {
+        info!(symbol, %quantity, %take_profit, %stop_price, "Placing OCO exit orders");
+
+        let filters = self.symbol_filters(symbol).await?;
+        let quantity = filters.quantize_qty(quantity);
+        filters.check_qty(quantity)?;
+
+        let params = format!(
+            "symbol={}&side=SELL&quantity={}&aboveType=LIMIT_MAKER&abovePrice={}\
+             &belowType=STOP_LOSS_LIMIT&belowStopPrice={}&belowPrice={}&belowTimeInForce=GTC",
+            symbol,
+            filters.format_qty(quantity),
+            filters.format_price(filters.quantize_price(take_profit)),
+            filters.format_price(filters.quantize_price(stop_price)),
+            filters.format_price(filters.quantize_price(stop_limit_price))
+        );
+        let body = self
+            .send(Method::POST, "/api/v3/orderList/oco", &params, Security::Signed)
+            .await?;
+
+        let list: OcoOrderResponse =
+            serde_json::from_str(&body).context("Failed to parse OCO order response")?;
+        let oco = list
+            .to_oco_order()
+            .context("OCO response missing take-profit or stop-loss leg")?;
+
+        info!(
+            order_list_id = oco.order_list_id,
+            list_status = %list.list_order_status,
+            "OCO exit orders placed"
+        );
+        Ok(oco)
+    }
+
+    /// Cancel both legs of an OCO order list
+    pub async fn cancel_oco(&self, symbol: &str, order_list_id: i64) -> Result<()> {
+        let params = format!("symbol={}&orderListId={}", symbol, order_list_id);
+        self.send(Method::DELETE, "/api/v3/orderList", &params, Security::Signed)
+            .await?;
+
+        info!(symbol, order_list_id, "OCO exit orders cancelled");
+        Ok(())
+    }
+
+    /// Query an existing order's status and fill totals
+    pub async fn get_order(&self, symbol: &str, order_id: u64) -> Result<OrderResponse> {
+        let params = format!("symbol={}&orderId={}", symbol, order_id);
+        let body = self
+            .send(Method::GET, "/api/v3/order", &params, Security::Signed)
+            .await?;
+
+        let order: OrderResponse =
+            serde_json::from_str(&body).context("Failed to parse order query response")?;
+        Ok(order)
+    }
+
+    /// Individual fills of an order, with their commissions
+    pub async fn get_order_fills(&self, symbol: &str, order_id: u64) -> Result<Vec<OrderFill>> {
+        let params = format!("symbol={}&orderId={}", symbol, order_id);
+        let body = self
+            .send(Method::GET, "/api/v3/myTrades", &params, Security::Signed)
+            .await?;
+
+        let fills: Vec<OrderFill> =
+            serde_json::from_str(&body).context("Failed to parse myTrades response")?;
+        Ok(fills)
+    }
+
+    /// Look up an order by its client order ID; `None` if Binance has no such order
+    pub async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<Option<OrderResponse>> {
+        let params = format!("symbol={}&origClientOrderId={}", symbol, client_order_id);
+        let body = match self
+            .send(Method::GET, "/api/v3/order", &params, Security::Signed)
+            .await
+        {
+            Ok(body) => body,
+            // -2013: Order does not exist
+            Err(BinanceError::Rejected { code: -2013, .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let order: OrderResponse =
+            serde_json::from_str(&body).context("Failed to parse order query response")?;
+        Ok(Some(order))
+    }
+}
+
+#[async_trait]
+impl Exchange for BinanceClient {
+    fn quote_asset(&self) -> &str {
+        BinanceClient::quote_asset(self)
+    }
+
+    async fn get_quote_balance(&self) -> Result<Decimal> {
+        BinanceClient::get_quote_balance(self).await
+    }
+
+    async fn get_all_balances(&self) -> Result<Vec<(String, Decimal)>> {
+        BinanceClient::get_all_balances(self).await
+    }
+
+    async fn get_tickers(&self) -> Result<Vec<Ticker24h>> {
+        BinanceClient::get_tickers(self).await
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker24h> {
+        BinanceClient::get_ticker(self, symbol).await
+    }
+
+    async fn market_buy(
+        &self,
+        symbol: &str,
+        quote_amount: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        BinanceClient::market_buy(self, symbol, quote_amount, client_order_id).await
+    }
+
+    async fn market_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        BinanceClient::market_sell(self, symbol, quantity, client_order_id).await
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: u64) -> Result<OrderResponse> {
+        BinanceClient::get_order(self, symbol, order_id).await
+    }
+
+    async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<Option<OrderResponse>> {
+        BinanceClient::query_order(self, symbol, client_order_id).await
+    }
+
+    async fn get_order_fills(&self, symbol: &str, order_id: u64) -> Result<Vec<OrderFill>> {
+        BinanceClient::get_order_fills(self, symbol, order_id).await
+    }
+
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        BinanceClient::get_klines(self, symbol, interval, limit).await
+    }
+
+    async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        BinanceClient::symbol_filters(self, symbol).await
+    }
+
+    async fn place_oco_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        take_profit: Decimal,
+        stop_price: Decimal,
+        stop_limit_price: Decimal,
+    ) -> Result<OcoOrder> {
+        BinanceClient::place_oco_sell(self, symbol, quantity, take_profit, stop_price, stop_limit_price)
+            .await
+    }
+
+    async fn cancel_oco(&self, symbol: &str, order_list_id: i64) -> Result<()> {
+        BinanceClient::cancel_oco(self, symbol, order_list_id).await
+    }
+
+    fn clock_drift_ms(&self) -> Option<i64> {
+        BinanceClient::clock_drift_ms(self)
+    }
+
+    fn rate_limit_usage(&self) -> Option<RateLimitUsage> {
+        Some(BinanceClient::rate_limit_usage(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::mock::{MockBinance, API_KEY, QUOTE_ASSET, SECRET_KEY};
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_signed_request_is_stamped_after_the_limiter_wait() {
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(50));
+        let client = BinanceClient::new(&mock.base_url, API_KEY, SECRET_KEY, 1000, 6000, QUOTE_ASSET);
+        // Held longer than the 1s recvWindow
+        client.limiter.block_for(Some(Duration::from_millis(1500)));
+
+        assert_eq!(client.get_quote_balance().await.unwrap(), dec!(50));
+        // A stale stamp is rejected (-1021) and only succeeds after a resync
+        assert!(!mock.requests().contains(&"GET /api/v3/time".to_string()));
+    }
+
+    #[test]
+    fn test_clock_offset_uses_round_trip_midpoint() {
+        // Request took 100ms; server stamped 1000ms ahead of our midpoint
+        assert_eq!(clock_offset(11_050, 10_000, 10_100), 1_000);
+        assert_eq!(clock_offset(9_950, 10_000, 10_100), -100);
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+    }
 }
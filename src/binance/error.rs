@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Error body Binance returns on failed requests
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    code: i64,
+    msg: String,
+}
+
+/// A failed Binance REST call, classified by what the caller can do about it
+#[derive(Debug, Error)]
+pub enum BinanceError {
+    /// The request never produced a response (connect, timeout, read failure)
+    #[error("Binance request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// 429 / 418 or a -1003 / -1015 rate-limit code. The request was not processed.
+    #[error("Binance rate limit hit ({status}): {msg}")]
+    RateLimited {
+        status: u16,
+        msg: String,
+        retry_after: Option<Duration>,
+    },
+
+    /// -1021: timestamp outside recvWindow. The request was not processed.
+    #[error("Binance rejected timestamp (-1021): {msg}")]
+    Timestamp { msg: String },
+
+    /// -2010 with an insufficient-balance message
+    #[error("Binance insufficient balance (-2010): {msg}")]
+    InsufficientBalance { msg: String },
+
+    /// Binance understood the request and refused it (bad parameters,
+    /// filter failures, order rejections, unknown orders, ...)
+    #[error("Binance rejected request ({code}): {msg}")]
+    Rejected { status: u16, code: i64, msg: String },
+
+    /// Our own rate limiter refused the call to stay under Binance's limits.
+    /// Nothing was sent.
+    #[error("Binance {limit} budget exhausted, retry in {}s", retry_after.as_secs())]
+    Throttled {
+        limit: &'static str,
+        retry_after: Duration,
+    },
+
+    /// 5xx or -1001 / -1007: Binance failed internally. For order placement
+    /// the outcome is unknown — the order may or may not exist.
+    #[error("Binance server error ({status}): {msg}")]
+    Server { status: u16, msg: String },
+}
+
+impl BinanceError {
+    /// Classify a non-2xx response
+    pub fn from_response(status: u16, retry_after: Option<Duration>, body: &str) -> Self {
+        let api: Option<ApiErrorBody> = serde_json::from_str(body).ok();
+        let (code, msg) = match api {
+            Some(a) => (Some(a.code), a.msg),
+            None => (None, body.chars().take(500).collect()),
+        };
+
+        match (status, code) {
+            (418 | 429, _) | (_, Some(-1003 | -1015)) => BinanceError::RateLimited {
+                status,
+                msg,
+                retry_after,
+            },
+            (_, Some(-1021)) => BinanceError::Timestamp { msg },
+            (_, Some(-2010)) if msg.to_lowercase().contains("insufficient balance") => {
+                BinanceError::InsufficientBalance { msg }
+            }
+            (500.., _) | (_, Some(-1001 | -1007)) => BinanceError::Server { status, msg },
+            (_, Some(code)) => BinanceError::Rejected { status, code, msg },
+            (_, None) => BinanceError::Rejected {
+                status,
+                code: 0,
+                msg,
+            },
+        }
+    }
+
+    /// Whether repeating the request may succeed. Non-idempotent requests
+    /// (order placement) are only retried when Binance guarantees the first
+    /// attempt was never processed.
+    pub fn is_transient(&self, idempotent: bool) -> bool {
+        match self {
+            BinanceError::RateLimited { .. } | BinanceError::Timestamp { .. } => true,
+            BinanceError::Transport(e) => idempotent || e.is_connect(),
+            BinanceError::Server { .. } => idempotent,
+            // The limiter already waited as long as it is willing to
+            BinanceError::Throttled { .. }
+            | BinanceError::InsufficientBalance { .. }
+            | BinanceError::Rejected { .. } => false,
+        }
+    }
+
+    /// Whether the request may have been executed even though it failed —
+    /// the response was lost or Binance errored after accepting it
+    pub fn is_ambiguous(&self) -> bool {
+        match self {
+            BinanceError::Transport(e) => !e.is_connect(),
+            BinanceError::Server { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Wait time Binance asked for via `Retry-After`, if any
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            BinanceError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_error_codes() {
+        let err = BinanceError::from_response(
+            400,
+            None,
+            r#"{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."}"#,
+        );
+        assert!(matches!(err, BinanceError::Timestamp { .. }));
+
+        let err = BinanceError::from_response(
+            400,
+            None,
+            r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#,
+        );
+        assert!(matches!(err, BinanceError::InsufficientBalance { .. }));
+
+        let err = BinanceError::from_response(
+            400,
+            None,
+            r#"{"code":-1013,"msg":"Filter failure: NOTIONAL"}"#,
+        );
+        assert!(matches!(err, BinanceError::Rejected { code: -1013, .. }));
+
+        let err = BinanceError::from_response(502, None, "<html>Bad Gateway</html>");
+        assert!(matches!(err, BinanceError::Server { status: 502, .. }));
+    }
+
+    #[test]
+    fn test_rate_limit_honors_retry_after() {
+        let err = BinanceError::from_response(
+            429,
+            Some(Duration::from_secs(7)),
+            r#"{"code":-1003,"msg":"Too much request weight used"}"#,
+        );
+        assert!(matches!(err, BinanceError::RateLimited { status: 429, .. }));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+        assert!(err.is_transient(false));
+    }
+
+    #[test]
+    fn test_orders_not_retried_on_unknown_outcome() {
+        let server = BinanceError::from_response(503, None, "");
+        assert!(server.is_transient(true));
+        assert!(!server.is_transient(false));
+        assert!(server.is_ambiguous());
+
+        let rejected = BinanceError::from_response(400, None, r#"{"code":-2010,"msg":"Order would immediately trigger."}"#);
+        assert!(!rejected.is_transient(true));
+        assert!(!rejected.is_ambiguous());
+    }
+}
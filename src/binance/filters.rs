@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Serialize;
+
+use super::types::{SymbolFilter, SymbolInfo};
+
+/// Trading rules for one symbol, extracted from `/api/v3/exchangeInfo`.
+/// Orders that violate these are rejected by Binance with -1013.
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolFilters {
+    pub symbol: String,
+    pub status: String,
+    pub tick_size: Decimal,
+    pub step_size: Decimal,
+    pub min_qty: Decimal,
+    pub max_qty: Decimal,
+    pub min_notional: Decimal,
+    pub quote_precision: u32,
+}
+
+impl SymbolFilters {
+    pub fn from_symbol_info(info: &SymbolInfo) -> Result<Self> {
+        let mut filters = SymbolFilters {
+            symbol: info.symbol.clone(),
+            status: info.status.clone(),
+            tick_size: Decimal::ZERO,
+            step_size: Decimal::ZERO,
+            min_qty: Decimal::ZERO,
+            max_qty: Decimal::MAX,
+            min_notional: Decimal::ZERO,
+            quote_precision: info.quote_asset_precision,
+        };
+
+        for filter in &info.filters {
+            match filter {
+                SymbolFilter::PriceFilter { tick_size } => {
+                    filters.tick_size = parse(tick_size, "tickSize")?;
+                }
+                SymbolFilter::LotSize {
+                    min_qty,
+                    max_qty,
+                    step_size,
+                } => {
+                    filters.min_qty = parse(min_qty, "minQty")?;
+                    filters.max_qty = parse(max_qty, "maxQty")?;
+                    filters.step_size = parse(step_size, "stepSize")?;
+                }
+                SymbolFilter::MinNotional { min_notional }
+                | SymbolFilter::Notional { min_notional } => {
+                    filters.min_notional = parse(min_notional, "minNotional")?;
+                }
+                SymbolFilter::Other => {}
+            }
+        }
+
+        Ok(filters)
+    }
+
+    /// Round a base quantity down to the symbol's stepSize
+    pub fn quantize_qty(&self, quantity: Decimal) -> Decimal {
+        floor_to_step(quantity, self.step_size)
+    }
+
+    /// Round a price down to the symbol's tickSize
+    pub fn quantize_price(&self, price: Decimal) -> Decimal {
+        floor_to_step(price, self.tick_size)
+    }
+
+    /// Round a quote amount down to the quote asset's precision
+    pub fn quantize_quote(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.quote_precision, RoundingStrategy::ToZero)
+    }
+
+    /// Format a quantized quantity with exactly the decimals stepSize allows
+    pub fn format_qty(&self, quantity: Decimal) -> String {
+        format!("{:.*}", step_decimals(self.step_size), quantity)
+    }
+
+    /// Format a quantized price with exactly the decimals tickSize allows
+    pub fn format_price(&self, price: Decimal) -> String {
+        format!("{:.*}", step_decimals(self.tick_size), price)
+    }
+
+    /// Format a quantized quote amount at the quote asset's precision
+    pub fn format_quote(&self, amount: Decimal) -> String {
+        format!("{:.*}", self.quote_precision as usize, amount)
+    }
+
+    /// Check a base quantity against LOT_SIZE bounds
+    pub fn check_qty(&self, quantity: Decimal) -> Result<()> {
+        if quantity <= Decimal::ZERO || quantity < self.min_qty {
+            anyhow::bail!(
+                "{} quantity {} below LOT_SIZE minimum {}",
+                self.symbol,
+                quantity,
+                self.min_qty
+            );
+        }
+        if quantity > self.max_qty {
+            anyhow::bail!(
+                "{} quantity {} above LOT_SIZE maximum {}",
+                self.symbol,
+                quantity,
+                self.max_qty
+            );
+        }
+        Ok(())
+    }
+
+    /// Check an order's quote value against the (MIN_)NOTIONAL filter
+    pub fn check_notional(&self, notional: Decimal) -> Result<()> {
+        if notional < self.min_notional {
+            anyhow::bail!(
+                "{} order value {:.8} below minimum notional {}",
+                self.symbol,
+                notional,
+                self.min_notional
+            );
+        }
+        Ok(())
+    }
+}
+
+fn parse(value: &str, field: &str) -> Result<Decimal> {
+    value
+        .parse::<Decimal>()
+        .map(|d| d.normalize())
+        .with_context(|| format!("Invalid {} in exchangeInfo: {}", field, value))
+}
+
+/// Floor `value` to a multiple of `step`
+fn floor_to_step(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).floor() * step).normalize()
+}
+
+/// Number of decimals implied by a step such as 0.00100000 (→ 3)
+fn step_decimals(step: Decimal) -> usize {
+    step.normalize().scale() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn filters() -> SymbolFilters {
+        SymbolFilters {
+            symbol: "SOLUSDC".to_string(),
+            status: "TRADING".to_string(),
+            tick_size: dec!(0.01),
+            step_size: dec!(0.001),
+            min_qty: dec!(0.001),
+            max_qty: dec!(9000),
+            min_notional: dec!(5),
+            quote_precision: 8,
+        }
+    }
+
+    #[test]
+    fn test_quantize_qty_floors_to_step() {
+        let f = filters();
+        assert_eq!(f.quantize_qty(dec!(1.23456789)), dec!(1.234));
+        assert_eq!(f.quantize_qty(dec!(0.3)), dec!(0.3));
+        assert_eq!(f.format_qty(f.quantize_qty(dec!(1.23456789))), "1.234");
+    }
+
+    #[test]
+    fn test_quantize_price_floors_to_tick() {
+        let f = filters();
+        assert_eq!(f.quantize_price(dec!(142.5678)), dec!(142.56));
+        assert_eq!(f.format_price(dec!(142.5)), "142.50");
+    }
+
+    #[test]
+    fn test_step_decimals() {
+        assert_eq!(step_decimals(dec!(0.00100000)), 3);
+        assert_eq!(step_decimals(dec!(0.01)), 2);
+        assert_eq!(step_decimals(dec!(1.0)), 0);
+        assert_eq!(step_decimals(dec!(0.00000001)), 8);
+    }
+
+    #[test]
+    fn test_notional_and_lot_checks() {
+        let f = filters();
+        assert!(f.check_notional(dec!(4.99)).is_err());
+        assert!(f.check_notional(dec!(5.0)).is_ok());
+        assert!(f.check_qty(dec!(0.0005)).is_err());
+        assert!(f.check_qty(dec!(10000)).is_err());
+        assert!(f.check_qty(dec!(1.0)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_exchange_info_filters() {
+        let json = r#"{
+            "symbol": "SOLUSDC", "status": "TRADING",
+            "baseAsset": "SOL", "quoteAsset": "USDC", "quoteAssetPrecision": 8,
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.01", "maxPrice": "10000", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00100000", "maxQty": "9000.00000000", "stepSize": "0.00100000"},
+                {"filterType": "ICEBERG_PARTS", "limit": 10},
+                {"filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true}
+            ]
+        }"#;
+        let info: SymbolInfo = serde_json::from_str(json).unwrap();
+        let f = SymbolFilters::from_symbol_info(&info).unwrap();
+        assert_eq!(f.tick_size, dec!(0.01));
+        assert_eq!(f.step_size, dec!(0.001));
+        assert_eq!(f.min_notional, dec!(5.0));
+    }
+}
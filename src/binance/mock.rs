@@ -0,0 +1,512 @@
+//! In-process mock of the Binance Spot REST API for tests.
+//!
+//! Serves the endpoints `BinanceClient` uses from an in-memory account:
+//! `/time`, `/account`, `/ticker/24hr`, `/exchangeInfo`, `/klines`, market
+//! `/order` placement and lookup, `/myTrades` and OCO lists. Signed requests
+//! are checked against the API key and HMAC secret like the real exchange,
+//! and failures can be scripted per endpoint.
+
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use hmac::{Hmac, Mac};
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use super::client::BinanceClient;
+
+pub const API_KEY: &str = "mock-api-key";
+pub const SECRET_KEY: &str = "mock-secret-key";
+pub const QUOTE_ASSET: &str = "USDC";
+
+/// Commission charged on every fill: in the base asset on a BUY, the quote on a SELL
+const FEE_RATE: Decimal = dec!(0.001);
+const TICK_SIZE: Decimal = dec!(0.01);
+const STEP_SIZE: Decimal = dec!(0.001);
+const MIN_NOTIONAL: Decimal = dec!(5);
+
+/// Endpoints that need an API key, timestamp and signature
+const SIGNED_PATHS: &[&str] = &[
+    "/api/v3/account",
+    "/api/v3/order",
+    "/api/v3/myTrades",
+    "/api/v3/orderList",
+    "/api/v3/orderList/oco",
+];
+
+/// A scripted error response for the next request to an endpoint
+#[derive(Debug, Clone)]
+pub struct Failure {
+    status: u16,
+    code: i64,
+    msg: String,
+    execute: bool,
+}
+
+impl Failure {
+    pub fn new(status: u16, code: i64, msg: &str) -> Self {
+        Self {
+            status,
+            code,
+            msg: msg.to_string(),
+            execute: false,
+        }
+    }
+
+    /// Process the request anyway and only lose the response, like a
+    /// timeout after the order reached the matching engine
+    pub fn after_executing(mut self) -> Self {
+        self.execute = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockOrder {
+    order_id: u64,
+    client_order_id: String,
+    symbol: String,
+    side: String,
+    order_type: String,
+    status: String,
+    executed_qty: Decimal,
+    quote_qty: Decimal,
+    fills: Vec<Value>,
+}
+
+impl MockOrder {
+    /// Order query shape: totals only, no fills
+    fn to_json(&self) -> Value {
+        json!({
+            "symbol": self.symbol,
+            "orderId": self.order_id,
+            "clientOrderId": self.client_order_id,
+            "status": self.status,
+            "side": self.side,
+            "type": self.order_type,
+            "executedQty": self.executed_qty.to_string(),
+            "cummulativeQuoteQty": self.quote_qty.to_string(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    balances: HashMap<String, Decimal>,
+    prices: BTreeMap<String, Decimal>,
+    /// exchangeInfo status by symbol; TRADING when unset
+    statuses: HashMap<String, String>,
+    orders: Vec<MockOrder>,
+    next_id: u64,
+    failures: HashMap<String, VecDeque<Failure>>,
+    requests: Vec<String>,
+}
+
+/// A running mock exchange. The server stops when the test's runtime does.
+#[derive(Clone)]
+pub struct MockBinance {
+    pub base_url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBinance {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            next_id: 1,
+            ..Default::default()
+        }));
+        let app = Router::new().fallback(handle).with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock Binance");
+        let addr = listener.local_addr().expect("mock Binance address");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self {
+            base_url: format!("http://{}", addr),
+            state,
+        }
+    }
+
+    /// A client for this mock with the matching credentials
+    pub fn client(&self) -> BinanceClient {
+        BinanceClient::new(&self.base_url, API_KEY, SECRET_KEY, 5000, 6000, QUOTE_ASSET)
+    }
+
+    pub fn set_balance(&self, asset: &str, free: Decimal) {
+        self.state().balances.insert(asset.to_string(), free);
+    }
+
+    pub fn balance(&self, asset: &str) -> Decimal {
+        self.state().balances.get(asset).copied().unwrap_or_default()
+    }
+
+    /// List a symbol at a last price; market orders fill at exactly this price
+    pub fn set_price(&self, symbol: &str, price: Decimal) {
+        self.state().prices.insert(symbol.to_string(), price);
+    }
+
+    /// Report a symbol in another exchangeInfo status, e.g. BREAK
+    pub fn set_status(&self, symbol: &str, status: &str) {
+        self.state().statuses.insert(symbol.to_string(), status.to_string());
+    }
+
+    /// Fail the next request to `method path` instead of serving it.
+    /// Repeated calls queue further failures.
+    pub fn fail_next(&self, method: Method, path: &str, failure: Failure) {
+        self.state()
+            .failures
+            .entry(format!("{} {}", method, path))
+            .or_default()
+            .push_back(failure);
+    }
+
+    /// Every request served so far, as "METHOD path"
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+}
+
+fn error(status: u16, code: i64, msg: &str) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_REQUEST);
+    (status, Json(json!({ "code": code, "msg": msg }))).into_response()
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// An API error as (HTTP status, Binance code, message)
+type ApiError = (u16, i64, &'static str);
+
+/// Check the API key header and the trailing HMAC signature of a signed request
+fn verify_signature(headers: &HeaderMap, query: &str) -> Result<(), ApiError> {
+    if headers.get("X-MBX-APIKEY").and_then(|v| v.to_str().ok()) != Some(API_KEY) {
+        return Err((401, -2015, "Invalid API-key, IP, or permissions for action."));
+    }
+
+    let (payload, signature) = query
+        .rsplit_once("&signature=")
+        .ok_or((400, -1102, "Mandatory parameter 'signature' was not sent."))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET_KEY.as_bytes()).expect("HMAC key");
+    mac.update(payload.as_bytes());
+    let valid = hex::decode(signature)
+        .map(|sig| mac.verify_slice(&sig).is_ok())
+        .unwrap_or(false);
+    if !valid {
+        return Err((400, -1022, "Signature for this request is not valid."));
+    }
+
+    let params = parse_query(payload);
+    let timestamp: i64 = params
+        .get("timestamp")
+        .and_then(|t| t.parse().ok())
+        .ok_or((400, -1102, "Mandatory parameter 'timestamp' was not sent."))?;
+    let recv_window: i64 = params
+        .get("recvWindow")
+        .and_then(|w| w.parse().ok())
+        .unwrap_or(5000);
+    if (chrono::Utc::now().timestamp_millis() - timestamp).abs() > recv_window {
+        return Err((400, -1021, "Timestamp for this request is outside of the recvWindow."));
+    }
+    Ok(())
+}
+
+async fn handle(
+    State(state): State<Arc<Mutex<MockState>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let path = uri.path().to_string();
+    let query = uri.query().unwrap_or("").to_string();
+    let key = format!("{} {}", method, path);
+
+    if SIGNED_PATHS.contains(&path.as_str()) {
+        if let Err((status, code, msg)) = verify_signature(&headers, &query) {
+            return error(status, code, msg);
+        }
+    }
+
+    let mut state = state.lock().unwrap();
+    state.requests.push(key.clone());
+
+    if let Some(failure) = state.failures.get_mut(&key).and_then(|q| q.pop_front()) {
+        if failure.execute {
+            let _ = route(&mut state, &method, &path, &parse_query(&query));
+        }
+        return error(failure.status, failure.code, &failure.msg);
+    }
+
+    route(&mut state, &method, &path, &parse_query(&query))
+}
+
+fn route(
+    state: &mut MockState,
+    method: &Method,
+    path: &str,
+    params: &HashMap<String, String>,
+) -> Response {
+    match (method.as_str(), path) {
+        ("GET", "/api/v3/time") => {
+            Json(json!({ "serverTime": chrono::Utc::now().timestamp_millis() })).into_response()
+        }
+        ("GET", "/api/v3/account") => {
+            let balances: Vec<Value> = state
+                .balances
+                .iter()
+                .map(|(asset, free)| json!({ "asset": asset, "free": free.to_string(), "locked": "0" }))
+                .collect();
+            Json(json!({ "balances": balances })).into_response()
+        }
+        ("GET", "/api/v3/ticker/24hr") => match params.get("symbol") {
+            Some(symbol) => match state.prices.get(symbol) {
+                Some(price) => Json(ticker(symbol, *price)).into_response(),
+                None => error(400, -1121, "Invalid symbol."),
+            },
+            None => {
+                let all: Vec<Value> = state.prices.iter().map(|(s, p)| ticker(s, *p)).collect();
+                Json(all).into_response()
+            }
+        },
+        ("GET", "/api/v3/exchangeInfo") => {
+            let symbols: Vec<Value> = state
+                .prices
+                .keys()
+                .map(|s| symbol_info(s, state.statuses.get(s).map_or("TRADING", String::as_str)))
+                .collect();
+            Json(json!({ "symbols": symbols })).into_response()
+        }
+        ("GET", "/api/v3/klines") => Json(json!([])).into_response(),
+        ("POST", "/api/v3/order") => place_market_order(state, params),
+        ("GET", "/api/v3/order") => {
+            let order = state.orders.iter().find(|o| {
+                params.get("orderId").map(|id| id == &o.order_id.to_string()).unwrap_or(false)
+                    || params.get("origClientOrderId") == Some(&o.client_order_id)
+            });
+            match order {
+                Some(order) => Json(order.to_json()).into_response(),
+                None => error(400, -2013, "Order does not exist."),
+            }
+        }
+        ("GET", "/api/v3/myTrades") => {
+            let fills = state
+                .orders
+                .iter()
+                .find(|o| params.get("orderId") == Some(&o.order_id.to_string()))
+                .map(|o| o.fills.clone())
+                .unwrap_or_default();
+            Json(fills).into_response()
+        }
+        ("POST", "/api/v3/orderList/oco") => place_oco(state, params),
+        ("DELETE", "/api/v3/orderList") => {
+            let list_id = params.get("orderListId").cloned().unwrap_or_default();
+            for order in state.orders.iter_mut() {
+                if order.client_order_id.starts_with(&format!("oco-{}-", list_id)) {
+                    order.status = "CANCELED".to_string();
+                }
+            }
+            Json(json!({ "orderListId": list_id })).into_response()
+        }
+        _ => error(404, -1100, "Unknown endpoint."),
+    }
+}
+
+fn ticker(symbol: &str, price: Decimal) -> Value {
+    json!({
+        "symbol": symbol,
+        "priceChange": "0",
+        "priceChangePercent": "0",
+        "lastPrice": price.to_string(),
+        "highPrice": price.to_string(),
+        "lowPrice": price.to_string(),
+        "volume": "1000",
+        "quoteVolume": (price * dec!(1000)).to_string(),
+    })
+}
+
+fn symbol_info(symbol: &str, status: &str) -> Value {
+    json!({
+        "symbol": symbol,
+        "status": status,
+        "quoteAssetPrecision": 8,
+        "filters": [
+            { "filterType": "PRICE_FILTER", "tickSize": TICK_SIZE.to_string() },
+            { "filterType": "LOT_SIZE", "minQty": STEP_SIZE.to_string(), "maxQty": "9000", "stepSize": STEP_SIZE.to_string() },
+            { "filterType": "NOTIONAL", "minNotional": MIN_NOTIONAL.to_string() }
+        ]
+    })
+}
+
+/// Fill a MARKET order in full at the last price and move the balances
+fn place_market_order(state: &mut MockState, params: &HashMap<String, String>) -> Response {
+    let symbol = params.get("symbol").cloned().unwrap_or_default();
+    let side = params.get("side").cloned().unwrap_or_default();
+    let Some(price) = state.prices.get(&symbol).copied() else {
+        return error(400, -1121, "Invalid symbol.");
+    };
+    let Some(base) = symbol.strip_suffix(QUOTE_ASSET).map(str::to_string) else {
+        return error(400, -1121, "Invalid symbol.");
+    };
+    let client_order_id = params.get("newClientOrderId").cloned().unwrap_or_default();
+    if state.orders.iter().any(|o| o.client_order_id == client_order_id) {
+        return error(400, -2010, "Duplicate order sent.");
+    }
+
+    let decimal = |name: &str| params.get(name).and_then(|v| v.parse::<Decimal>().ok());
+    let quantity = match side.as_str() {
+        "BUY" => decimal("quoteOrderQty").map(|q| {
+            (q / price).round_dp_with_strategy(STEP_SIZE.scale(), RoundingStrategy::ToZero)
+        }),
+        _ => decimal("quantity"),
+    };
+    let Some(quantity) = quantity.filter(|q| *q > Decimal::ZERO) else {
+        return error(400, -1013, "Filter failure: LOT_SIZE");
+    };
+    let quote_qty = quantity * price;
+
+    let (spend_asset, spend) = match side.as_str() {
+        "BUY" => (QUOTE_ASSET.to_string(), quote_qty),
+        _ => (base.clone(), quantity),
+    };
+    let available = state.balances.get(&spend_asset).copied().unwrap_or_default();
+    if available < spend {
+        return error(400, -2010, "Account has insufficient balance for requested action.");
+    }
+
+    let (commission, commission_asset) = match side.as_str() {
+        "BUY" => (quantity * FEE_RATE, base.clone()),
+        _ => (quote_qty * FEE_RATE, QUOTE_ASSET.to_string()),
+    };
+    let (receive_asset, receive) = match side.as_str() {
+        "BUY" => (base, quantity - commission),
+        _ => (QUOTE_ASSET.to_string(), quote_qty - commission),
+    };
+    *state.balances.entry(spend_asset).or_default() -= spend;
+    *state.balances.entry(receive_asset).or_default() += receive;
+
+    let order_id = state.next_id;
+    state.next_id += 1;
+    let fills = vec![json!({
+        "price": price.to_string(),
+        "qty": quantity.to_string(),
+        "commission": commission.to_string(),
+        "commissionAsset": commission_asset,
+        "tradeId": order_id,
+    })];
+    let order = MockOrder {
+        order_id,
+        client_order_id,
+        symbol,
+        side,
+        order_type: "MARKET".to_string(),
+        status: "FILLED".to_string(),
+        executed_qty: quantity,
+        quote_qty,
+        fills: fills.clone(),
+    };
+
+    let mut body = order.to_json();
+    body["fills"] = Value::Array(fills);
+    state.orders.push(order);
+    Json(body).into_response()
+}
+
+/// Accept an OCO sell as two resting legs; they never fill on their own
+fn place_oco(state: &mut MockState, params: &HashMap<String, String>) -> Response {
+    let symbol = params.get("symbol").cloned().unwrap_or_default();
+    let list_id = state.next_id;
+    state.next_id += 1;
+
+    let mut reports = Vec::new();
+    for order_type in ["LIMIT_MAKER", "STOP_LOSS_LIMIT"] {
+        let order_id = state.next_id;
+        state.next_id += 1;
+        state.orders.push(MockOrder {
+            order_id,
+            client_order_id: format!("oco-{}-{}", list_id, order_type),
+            symbol: symbol.clone(),
+            side: "SELL".to_string(),
+            order_type: order_type.to_string(),
+            status: "NEW".to_string(),
+            executed_qty: Decimal::ZERO,
+            quote_qty: Decimal::ZERO,
+            fills: Vec::new(),
+        });
+        reports.push(json!({ "orderId": order_id, "type": order_type }));
+    }
+
+    Json(json!({
+        "orderListId": list_id,
+        "listOrderStatus": "EXECUTING",
+        "orderReports": reports,
+    }))
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::BinanceError;
+
+    #[tokio::test]
+    async fn test_signed_order_moves_balances() {
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(100));
+        mock.set_price("SOLUSDC", dec!(20));
+        let client = mock.client();
+
+        assert_eq!(client.get_quote_balance().await.unwrap(), dec!(100));
+        let order = client.market_buy("SOLUSDC", dec!(50), "sb-test").await.unwrap();
+
+        assert_eq!(order.status, "FILLED");
+        assert_eq!(order.executed_qty, dec!(2.5));
+        assert_eq!(order.fills[0].commission, dec!(0.0025));
+        assert_eq!(mock.balance("USDC"), dec!(50));
+        assert_eq!(mock.balance("SOL"), dec!(2.4975));
+
+        let found = client.query_order("SOLUSDC", "sb-test").await.unwrap().unwrap();
+        assert_eq!(found.order_id, order.order_id);
+        assert!(client.query_order("SOLUSDC", "sb-missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_bad_signature_rejected() {
+        let mock = MockBinance::start().await;
+        let client = BinanceClient::new(&mock.base_url, API_KEY, "wrong-secret", 5000, 6000, QUOTE_ASSET);
+
+        let err = client.get_quote_balance().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BinanceError>(),
+            Some(BinanceError::Rejected { code: -1022, .. })
+        ));
+        assert!(mock.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scripted_failure_is_retried() {
+        let mock = MockBinance::start().await;
+        mock.set_price("SOLUSDC", dec!(20));
+        mock.fail_next(Method::GET, "/api/v3/ticker/24hr", Failure::new(503, -1001, "Internal error"));
+
+        let ticker = mock.client().get_ticker("SOLUSDC").await.unwrap();
+        assert_eq!(ticker.last_price, dec!(20));
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
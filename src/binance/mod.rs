@@ -1,5 +1,15 @@
 pub mod client;
+pub mod error;
+pub mod filters;
+#[cfg(test)]
+pub mod mock;
+pub mod rate_limit;
+pub mod streams;
 pub mod types;
+pub mod user_stream;
 
 pub use client::BinanceClient;
+pub use error::BinanceError;
+pub use filters::SymbolFilters;
 pub use types::*;
+pub use user_stream::UserDataStream;
@@ -0,0 +1,257 @@
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use super::error::BinanceError;
+use crate::db::models::RateLimitUsage;
+
+/// Orders allowed per 10 seconds before we hold back (Binance limit: 100)
+const ORDER_BUDGET_10S: u32 = 90;
+/// Orders allowed per day before we refuse (Binance limit: 200,000)
+const ORDER_BUDGET_1D: u32 = 190_000;
+/// Longest the limiter will sleep for a window to roll over; beyond that it refuses
+const MAX_LIMIT_WAIT: Duration = Duration::from_secs(30);
+/// Back-off assumed for a 418/429 that carries no Retry-After
+const DEFAULT_BAN: Duration = Duration::from_secs(60);
+
+const MINUTE_MS: i64 = 60_000;
+const TEN_SECONDS_MS: i64 = 10_000;
+const DAY_MS: i64 = 86_400_000;
+
+/// Request weight of an endpoint, per the Binance spot API docs
+pub fn request_weight(method: &Method, path: &str, params: &str) -> u32 {
+    match (method.as_str(), path) {
+        // The all-symbols ticker is by far the most expensive call we make
+        ("GET", "/api/v3/ticker/24hr") if !params.contains("symbol=") => 80,
+        ("GET", "/api/v3/ticker/24hr") => 2,
+        ("GET", "/api/v3/account") => 20,
+        ("GET", "/api/v3/exchangeInfo") => 20,
+        ("GET", "/api/v3/order") => 4,
+        ("GET", "/api/v3/myTrades") => 20,
+        ("GET", "/api/v3/klines") => 2,
+        ("POST" | "PUT", "/api/v3/userDataStream") => 2,
+        _ => 1,
+    }
+}
+
+/// Orders an endpoint adds to the order-rate counters
+pub fn order_count(method: &Method, path: &str) -> u32 {
+    match (method.as_str(), path) {
+        ("POST", "/api/v3/order") => 1,
+        ("POST", "/api/v3/orderList/oco") => 2,
+        _ => 0,
+    }
+}
+
+/// Usage within one fixed window (Binance counters reset on window boundaries)
+#[derive(Debug, Default)]
+struct Window {
+    bucket: i64,
+    used: u32,
+}
+
+impl Window {
+    fn used(&self, bucket: i64) -> u32 {
+        if self.bucket == bucket {
+            self.used
+        } else {
+            0
+        }
+    }
+
+    fn add(&mut self, bucket: i64, n: u32) {
+        self.used = self.used(bucket) + n;
+        self.bucket = bucket;
+    }
+
+    /// Binance's reported count is authoritative, but requests still in
+    /// flight may already be counted locally — keep the larger of the two
+    fn observe(&mut self, bucket: i64, reported: u32) {
+        self.used = self.used(bucket).max(reported);
+        self.bucket = bucket;
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    weight_1m: Window,
+    orders_10s: Window,
+    orders_1d: Window,
+    blocked_until: Option<Instant>,
+}
+
+/// Client-side view of Binance's IP weight and account order-rate limits,
+/// fed by `X-MBX-USED-WEIGHT-1M` / `X-MBX-ORDER-COUNT-*` response headers.
+/// Shared by all clones of the client.
+#[derive(Clone)]
+pub struct RateLimiter {
+    weight_budget: u32,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl RateLimiter {
+    pub fn new(weight_budget: u32) -> Self {
+        Self {
+            weight_budget,
+            state: Arc::new(Mutex::new(LimiterState::default())),
+        }
+    }
+
+    /// Wait until the request fits in every budget, then reserve it.
+    /// Refuses instead of waiting longer than `MAX_LIMIT_WAIT`.
+    pub async fn acquire(&self, weight: u32, orders: u32) -> Result<(), BinanceError> {
+        loop {
+            let wait = match self.try_reserve(weight, orders, now_ms()) {
+                Ok(()) => return Ok(()),
+                Err((limit, wait)) if wait > MAX_LIMIT_WAIT => {
+                    warn!(limit, wait_s = wait.as_secs(), "Binance budget exhausted — refusing request");
+                    return Err(BinanceError::Throttled {
+                        limit,
+                        retry_after: wait,
+                    });
+                }
+                Err((limit, wait)) => {
+                    warn!(limit, wait_ms = wait.as_millis() as u64, "Binance budget near limit — delaying request");
+                    wait
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Reserve the request at `now` (ms), or name the exhausted budget and
+    /// how long until it frees up
+    fn try_reserve(&self, weight: u32, orders: u32, now: i64) -> Result<(), (&'static str, Duration)> {
+        let mut state = self.state.lock().expect("rate limiter poisoned");
+
+        if let Some(until) = state.blocked_until {
+            let remaining = until.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                return Err(("ban", remaining));
+            }
+            state.blocked_until = None;
+        }
+
+        if state.weight_1m.used(now / MINUTE_MS) + weight > self.weight_budget {
+            return Err(("request weight", until_next(now, MINUTE_MS)));
+        }
+        if orders > 0 {
+            if state.orders_10s.used(now / TEN_SECONDS_MS) + orders > ORDER_BUDGET_10S {
+                return Err(("orders/10s", until_next(now, TEN_SECONDS_MS)));
+            }
+            if state.orders_1d.used(now / DAY_MS) + orders > ORDER_BUDGET_1D {
+                return Err(("orders/day", until_next(now, DAY_MS)));
+            }
+        }
+
+        state.weight_1m.add(now / MINUTE_MS, weight);
+        state.orders_10s.add(now / TEN_SECONDS_MS, orders);
+        state.orders_1d.add(now / DAY_MS, orders);
+        Ok(())
+    }
+
+    /// Update counters from a response's usage headers
+    pub fn record(&self, headers: &HeaderMap) {
+        self.record_at(headers, now_ms());
+    }
+
+    fn record_at(&self, headers: &HeaderMap, now: i64) {
+        let header = |name: &str| -> Option<u32> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        let mut state = self.state.lock().expect("rate limiter poisoned");
+
+        if let Some(used) = header("x-mbx-used-weight-1m") {
+            state.weight_1m.observe(now / MINUTE_MS, used);
+        }
+        if let Some(count) = header("x-mbx-order-count-10s") {
+            state.orders_10s.observe(now / TEN_SECONDS_MS, count);
+        }
+        if let Some(count) = header("x-mbx-order-count-1d") {
+            state.orders_1d.observe(now / DAY_MS, count);
+        }
+    }
+
+    /// Stop all calls after a 429/418 until Binance's Retry-After has passed
+    pub fn block_for(&self, retry_after: Option<Duration>) {
+        let until = Instant::now() + retry_after.unwrap_or(DEFAULT_BAN);
+        let mut state = self.state.lock().expect("rate limiter poisoned");
+        state.blocked_until = Some(state.blocked_until.map_or(until, |u| u.max(until)));
+    }
+
+    /// Current usage in each window
+    pub fn usage(&self) -> RateLimitUsage {
+        let now = now_ms();
+        let state = self.state.lock().expect("rate limiter poisoned");
+        RateLimitUsage {
+            used_weight_1m: state.weight_1m.used(now / MINUTE_MS),
+            weight_budget_1m: self.weight_budget,
+            orders_10s: state.orders_10s.used(now / TEN_SECONDS_MS),
+            orders_1d: state.orders_1d.used(now / DAY_MS),
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Time until the next `window_ms` boundary
+fn until_next(now: i64, window_ms: i64) -> Duration {
+    Duration::from_millis((window_ms - now.rem_euclid(window_ms)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_request_weights() {
+        assert_eq!(request_weight(&Method::GET, "/api/v3/ticker/24hr", ""), 80);
+        assert_eq!(request_weight(&Method::GET, "/api/v3/ticker/24hr", "symbol=BTCUSDC"), 2);
+        assert_eq!(request_weight(&Method::POST, "/api/v3/order", "symbol=BTCUSDC"), 1);
+        assert_eq!(order_count(&Method::POST, "/api/v3/orderList/oco"), 2);
+        assert_eq!(order_count(&Method::GET, "/api/v3/order"), 0);
+    }
+
+    #[test]
+    fn test_weight_budget_waits_for_next_minute() {
+        let limiter = RateLimiter::new(100);
+        let now = 5 * MINUTE_MS + 45_000;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("90"));
+        limiter.record_at(&headers, now);
+
+        assert!(limiter.try_reserve(10, 0, now).is_ok());
+        let (limit, wait) = limiter.try_reserve(1, 0, now).unwrap_err();
+        assert_eq!(limit, "request weight");
+        assert_eq!(wait, Duration::from_secs(15));
+
+        // A new minute resets the counter
+        assert!(limiter.try_reserve(80, 0, 6 * MINUTE_MS).is_ok());
+    }
+
+    #[test]
+    fn test_order_rate_budget() {
+        let limiter = RateLimiter::new(6000);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mbx-order-count-10s", HeaderValue::from_static("89"));
+        limiter.record_at(&headers, 1_000);
+
+        assert!(limiter.try_reserve(1, 1, 1_000).is_ok());
+        assert_eq!(limiter.try_reserve(1, 1, 1_000).unwrap_err().0, "orders/10s");
+        // Non-order requests are unaffected
+        assert!(limiter.try_reserve(1, 0, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_ban_blocks_everything() {
+        let limiter = RateLimiter::new(6000);
+        limiter.block_for(Some(Duration::from_secs(120)));
+        let (limit, wait) = limiter.try_reserve(1, 0, 0).unwrap_err();
+        assert_eq!(limit, "ban");
+        assert!(wait > MAX_LIMIT_WAIT);
+    }
+}
@@ -0,0 +1,224 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+// ─── Market Streams ──────────────────────────────────────
+
+/// Envelope used by the combined stream endpoint (`/stream?streams=...`)
+#[derive(Debug, Deserialize)]
+struct CombinedStreamMessage {
+    stream: String,
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct MiniTickerPayload {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    close: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BookTickerPayload {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "b")]
+    bid: String,
+}
+
+/// A parsed market stream update
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketEvent {
+    /// `<symbol>@miniTicker` — last price, roughly once per second
+    MiniTicker { symbol: String, close: Decimal },
+    /// `<symbol>@bookTicker` — best bid (what a market sell would hit),
+    /// on every top-of-book change
+    BookTicker { symbol: String, bid: Decimal },
+}
+
+/// Build a combined stream URL subscribing to miniTicker and bookTicker for each symbol
+pub fn market_stream_url(stream_base: &str, symbols: &[String]) -> String {
+    let streams: Vec<String> = symbols
+        .iter()
+        .flat_map(|s| {
+            let s = s.to_lowercase();
+            [format!("{}@miniTicker", s), format!("{}@bookTicker", s)]
+        })
+        .collect();
+    format!(
+        "{}/stream?streams={}",
+        stream_base.trim_end_matches('/'),
+        streams.join("/")
+    )
+}
+
+/// Parse a combined stream text frame. Unknown streams yield `None`.
+pub fn parse_market_event(text: &str) -> Option<MarketEvent> {
+    let msg: CombinedStreamMessage = serde_json::from_str(text).ok()?;
+
+    if msg.stream.ends_with("@miniTicker") {
+        let p: MiniTickerPayload = serde_json::from_value(msg.data).ok()?;
+        Some(MarketEvent::MiniTicker {
+            symbol: p.symbol,
+            close: p.close.parse().ok()?,
+        })
+    } else if msg.stream.ends_with("@bookTicker") {
+        let p: BookTickerPayload = serde_json::from_value(msg.data).ok()?;
+        Some(MarketEvent::BookTicker {
+            symbol: p.symbol,
+            bid: p.bid.parse().ok()?,
+        })
+    } else {
+        None
+    }
+}
+
+// ─── User Data Stream ────────────────────────────────────
+
+/// `executionReport` — one per order state change or partial fill
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutionReport {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "S")]
+    pub side: String,
+    /// Execution type: NEW, TRADE, CANCELED, EXPIRED, ...
+    #[serde(rename = "x")]
+    pub execution_type: String,
+    /// Order status after this event: NEW, PARTIALLY_FILLED, FILLED, ...
+    #[serde(rename = "X")]
+    pub order_status: String,
+    #[serde(rename = "i")]
+    pub order_id: u64,
+    /// Price of this fill
+    #[serde(rename = "L", default)]
+    pub last_price: String,
+    /// Cumulative filled base quantity
+    #[serde(rename = "z")]
+    pub cumulative_qty: String,
+    /// Cumulative filled quote quantity
+    #[serde(rename = "Z")]
+    pub cumulative_quote_qty: String,
+    /// Commission for this fill only
+    #[serde(rename = "n", default)]
+    pub commission: Option<String>,
+    #[serde(rename = "N", default)]
+    pub commission_asset: Option<String>,
+}
+
+impl ExecutionReport {
+    /// True once the order can receive no further fills
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.order_status.as_str(),
+            "FILLED" | "CANCELED" | "EXPIRED" | "EXPIRED_IN_MATCH" | "REJECTED"
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamBalance {
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "f", with = "rust_decimal::serde::str")]
+    pub free: Decimal,
+    #[serde(rename = "l", with = "rust_decimal::serde::str")]
+    pub locked: Decimal,
+}
+
+/// A parsed user data stream event
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "e")]
+pub enum UserEvent {
+    #[serde(rename = "executionReport")]
+    ExecutionReport(ExecutionReport),
+    /// Balances of assets changed by the last account update
+    #[serde(rename = "outboundAccountPosition")]
+    AccountPosition {
+        #[serde(rename = "B")]
+        balances: Vec<StreamBalance>,
+    },
+    #[serde(rename = "listenKeyExpired")]
+    ListenKeyExpired,
+    #[serde(other)]
+    Other,
+}
+
+/// Parse a user data stream text frame
+pub fn parse_user_event(text: &str) -> Option<UserEvent> {
+    serde_json::from_str(text).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_market_stream_url() {
+        let url = market_stream_url(
+            "wss://stream.binance.com:9443/",
+            &["BTCUSDC".to_string(), "SOLUSDC".to_string()],
+        );
+        assert_eq!(
+            url,
+            "wss://stream.binance.com:9443/stream?streams=btcusdc@miniTicker/btcusdc@bookTicker/solusdc@miniTicker/solusdc@bookTicker"
+        );
+    }
+
+    #[test]
+    fn test_parse_mini_ticker() {
+        let text = r#"{"stream":"btcusdc@miniTicker","data":{"e":"24hrMiniTicker","E":1,"s":"BTCUSDC","c":"64000.10","o":"1","h":"1","l":"1","v":"1","q":"1"}}"#;
+        assert_eq!(
+            parse_market_event(text),
+            Some(MarketEvent::MiniTicker {
+                symbol: "BTCUSDC".to_string(),
+                close: dec!(64000.10)
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_book_ticker() {
+        let text = r#"{"stream":"btcusdc@bookTicker","data":{"u":400900217,"s":"BTCUSDC","b":"63999.00","B":"1.2","a":"64001.00","A":"0.5"}}"#;
+        assert_eq!(
+            parse_market_event(text),
+            Some(MarketEvent::BookTicker {
+                symbol: "BTCUSDC".to_string(),
+                bid: dec!(63999)
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_execution_report() {
+        let text = r#"{"e":"executionReport","E":1,"s":"SOLUSDC","c":"abc","S":"SELL","o":"LIMIT_MAKER","f":"GTC","q":"1.0","p":"150","P":"0","F":"0","g":-1,"C":"","x":"TRADE","X":"FILLED","r":"NONE","i":9,"l":"1.0","z":"1.0","L":"150","n":"0.15","N":"USDC","T":1,"t":5,"I":1,"w":false,"m":true,"M":true,"O":1,"Z":"150.0","Y":"150","Q":"0"}"#;
+        match parse_user_event(text) {
+            Some(UserEvent::ExecutionReport(r)) => {
+                assert_eq!(r.order_id, 9);
+                assert_eq!(r.side, "SELL");
+                assert!(r.is_terminal());
+                assert_eq!(r.commission.as_deref(), Some("0.15"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_account_position() {
+        let text = r#"{"e":"outboundAccountPosition","E":1,"u":1,"B":[{"a":"USDC","f":"42.5","l":"0.0"}]}"#;
+        match parse_user_event(text) {
+            Some(UserEvent::AccountPosition { balances }) => {
+                assert_eq!(balances[0].asset, "USDC");
+                assert_eq!(balances[0].free, dec!(42.5));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_user_event_is_other() {
+        let text = r#"{"e":"balanceUpdate","E":1,"a":"BTC","d":"1.0","T":1}"#;
+        assert!(matches!(parse_user_event(text), Some(UserEvent::Other)));
+    }
+}
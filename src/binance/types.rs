@@ -1,4 +1,7 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ─── Account Info ────────────────────────────────────────
 
@@ -11,8 +14,24 @@ pub struct AccountInfo {
 #[derive(Debug, Deserialize)]
 pub struct AssetBalance {
     pub asset: String,
-    pub free: String,
-    pub locked: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub free: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub locked: Decimal,
+}
+
+// ─── User Data Stream ────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenKey {
+    pub listen_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTime {
+    pub server_time: i64,
 }
 
 // ─── Ticker 24h ──────────────────────────────────────────
@@ -21,18 +40,82 @@ pub struct AssetBalance {
 #[serde(rename_all = "camelCase")]
 pub struct Ticker24h {
     pub symbol: String,
-    pub price_change: String,
-    pub price_change_percent: String,
-    pub last_price: String,
-    pub high_price: String,
-    pub low_price: String,
-    pub volume: String,
-    pub quote_volume: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price_change: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price_change_percent: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub last_price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub high_price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub low_price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub volume: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quote_volume: Decimal,
 }
 
-// ─── Order Response ──────────────────────────────────────
+// ─── Klines ──────────────────────────────────────────────
 
+/// One `/api/v3/klines` row, as Binance sends it (a positional array).
+/// The trailing columns are only declared so the row length matches.
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct RawKline(
+    pub i64,    // open time (ms)
+    pub String, // open
+    pub String, // high
+    pub String, // low
+    pub String, // close
+    pub String, // volume
+    pub i64,    // close time (ms)
+    pub String, // quote asset volume
+    pub i64,    // number of trades
+    pub serde_json::Value, // taker buy base volume
+    pub serde_json::Value, // taker buy quote volume
+    pub serde_json::Value, // unused
+);
+
+/// A parsed OHLCV candle
+#[derive(Debug, Clone, Serialize)]
+pub struct Kline {
+    pub open_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub quote_volume: Decimal,
+    pub trade_count: i64,
+    pub close_time: DateTime<Utc>,
+}
+
+impl RawKline {
+    pub fn to_kline(&self) -> Option<Kline> {
+        Some(Kline {
+            open_time: DateTime::from_timestamp_millis(self.0)?,
+            open: self.1.parse().ok()?,
+            high: self.2.parse().ok()?,
+            low: self.3.parse().ok()?,
+            close: self.4.parse().ok()?,
+            volume: self.5.parse().ok()?,
+            quote_volume: self.7.parse().ok()?,
+            trade_count: self.8,
+            close_time: DateTime::from_timestamp_millis(self.6)?,
+        })
+    }
+}
+
+/// Kline intervals accepted by Binance
+pub const KLINE_INTERVALS: &[&str] = &[
+    "1s", "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "12h", "1d", "3d", "1w",
+    "1M",
+];
+
+// ─── Order Response ──────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderResponse {
     pub symbol: String,
@@ -41,55 +124,328 @@ pub struct OrderResponse {
     pub side: String,
     #[serde(rename = "type")]
     pub order_type: String,
-    pub executed_qty: String,
-    pub cummulative_quote_qty: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub executed_qty: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub cummulative_quote_qty: Decimal,
+    /// Only present on order placement responses, not on order queries
+    /// (see `Exchange::get_order_fills`)
+    #[serde(default)]
     pub fills: Vec<OrderFill>,
 }
 
-#[derive(Debug, Deserialize)]
+/// One fill of an order. Also the shape of a `/api/v3/myTrades` row.
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderFill {
-    pub price: String,
-    pub qty: String,
-    pub commission: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub qty: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub commission: Decimal,
     pub commission_asset: String,
 }
 
+// ─── OCO Order List ──────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderResponse {
+    pub order_list_id: i64,
+    pub list_order_status: String,
+    pub order_reports: Vec<OcoOrderReport>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderReport {
+    pub order_id: u64,
+    #[serde(rename = "type")]
+    pub order_type: String,
+}
+
 // ─── Computed types for internal use ─────────────────────
 
+/// Exchange-side exit orders protecting a position
+#[derive(Debug, Clone)]
+pub struct OcoOrder {
+    pub order_list_id: i64,
+    pub take_profit_order_id: u64,
+    pub stop_loss_order_id: u64,
+}
+
+impl OcoOrderResponse {
+    /// Identify the take-profit (LIMIT_MAKER) and stop-loss (STOP_LOSS_LIMIT) legs
+    pub fn to_oco_order(&self) -> Option<OcoOrder> {
+        let leg = |order_type: &str| {
+            self.order_reports
+                .iter()
+                .find(|r| r.order_type == order_type)
+                .map(|r| r.order_id)
+        };
+        Some(OcoOrder {
+            order_list_id: self.order_list_id,
+            take_profit_order_id: leg("LIMIT_MAKER")?,
+            stop_loss_order_id: leg("STOP_LOSS_LIMIT")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecutedTrade {
+    pub order_id: u64,
     pub symbol: String,
     pub side: String,
-    pub quantity: f64,
-    pub avg_price: f64,
-    pub usdc_amount: f64,
-    pub commission: f64,
+    /// Base quantity filled
+    pub quantity: Decimal,
+    /// Base quantity left after commission charged in the base asset —
+    /// what a BUY actually added to the account
+    pub net_quantity: Decimal,
+    pub avg_price: Decimal,
+    pub quote_amount: Decimal,
+    /// All commission on the order, valued in the quote asset at fill time
+    pub commission_quote: Decimal,
 }
 
 impl OrderResponse {
-    /// Convert to an ExecutedTrade with computed averages
-    pub fn to_executed_trade(&self) -> ExecutedTrade {
-        let quantity: f64 = self.executed_qty.parse().unwrap_or(0.0);
-        let usdc_amount: f64 = self.cummulative_quote_qty.parse().unwrap_or(0.0);
-        let avg_price = if quantity > 0.0 {
-            usdc_amount / quantity
+    /// Whether the order executed in full
+    pub fn is_filled(&self) -> bool {
+        self.status == "FILLED"
+    }
+
+    /// Whether the order can still receive fills
+    pub fn is_working(&self) -> bool {
+        matches!(self.status.as_str(), "NEW" | "PENDING_NEW" | "PARTIALLY_FILLED")
+    }
+
+    /// The traded asset, e.g. SOL for SOLUSDC
+    pub fn base_asset(&self, quote_asset: &str) -> &str {
+        self.symbol.strip_suffix(quote_asset).unwrap_or(&self.symbol)
+    }
+
+    /// Total commission charged in a specific asset
+    pub fn commission_in(&self, asset: &str) -> Decimal {
+        self.fills
+            .iter()
+            .filter(|f| f.commission_asset == asset)
+            .map(|f| f.commission)
+            .sum()
+    }
+
+    /// Commission assets that are neither side of the pair (e.g. BNB) and
+    /// need a market price to be valued in the quote asset
+    pub fn foreign_commission_assets(&self, quote_asset: &str) -> Vec<String> {
+        let mut assets: Vec<String> = self
+            .fills
+            .iter()
+            .filter(|f| f.commission > Decimal::ZERO)
+            .map(|f| f.commission_asset.clone())
+            .filter(|a| a != quote_asset && a != self.base_asset(quote_asset))
+            .collect();
+        assets.sort();
+        assets.dedup();
+        assets
+    }
+
+    /// Convert to an ExecutedTrade with computed averages. `prices` holds the
+    /// quote-asset price of each foreign commission asset; fees in an unpriced
+    /// asset are left out of `commission_quote`.
+    pub fn to_executed_trade(
+        &self,
+        quote_asset: &str,
+        prices: &HashMap<String, Decimal>,
+    ) -> ExecutedTrade {
+        let quantity = self.executed_qty;
+        let quote_amount = self.cummulative_quote_qty;
+        let avg_price = if quantity > Decimal::ZERO {
+            quote_amount / quantity
         } else {
-            0.0
+            Decimal::ZERO
         };
-        let commission: f64 = self
+
+        let base_asset = self.base_asset(quote_asset);
+        let commission_quote = self
             .fills
             .iter()
-            .map(|f| f.commission.parse::<f64>().unwrap_or(0.0))
+            .filter_map(|f| {
+                commission_in_quote(
+                    &f.commission_asset,
+                    f.commission,
+                    base_asset,
+                    quote_asset,
+                    f.price,
+                    prices,
+                )
+            })
             .sum();
 
         ExecutedTrade {
+            order_id: self.order_id,
             symbol: self.symbol.clone(),
             side: self.side.clone(),
             quantity,
+            net_quantity: quantity - self.commission_in(base_asset),
             avg_price,
-            usdc_amount,
-            commission,
+            quote_amount,
+            commission_quote,
         }
     }
 }
+
+/// Value a commission in the quote asset: quote fees as-is, base fees at the
+/// fill price, anything else at its market price if known
+pub fn commission_in_quote(
+    asset: &str,
+    amount: Decimal,
+    base_asset: &str,
+    quote_asset: &str,
+    fill_price: Decimal,
+    prices: &HashMap<String, Decimal>,
+) -> Option<Decimal> {
+    if amount.is_zero() || asset == quote_asset {
+        Some(amount)
+    } else if asset == base_asset {
+        Some(amount * fill_price)
+    } else {
+        prices.get(asset).map(|price| amount * price)
+    }
+}
+
+// ─── Exchange Info ───────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ExchangeInfo {
+    pub symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub status: String,
+    pub quote_asset_precision: u32,
+    pub filters: Vec<SymbolFilter>,
+}
+
+/// The subset of Binance symbol filters we enforce client-side
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SymbolFilter {
+    #[serde(rename_all = "camelCase")]
+    PriceFilter { tick_size: String },
+    #[serde(rename_all = "camelCase")]
+    LotSize {
+        min_qty: String,
+        max_qty: String,
+        step_size: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MinNotional { min_notional: String },
+    #[serde(rename_all = "camelCase")]
+    Notional { min_notional: String },
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_kline_row() {
+        let json = r#"[[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]]"#;
+        let rows: Vec<RawKline> = serde_json::from_str(json).unwrap();
+        let k = rows[0].to_kline().unwrap();
+        assert_eq!(k.open_time.timestamp_millis(), 1499040000000);
+        assert_eq!(k.high, dec!(0.8));
+        assert_eq!(k.close, dec!(0.015771));
+        assert_eq!(k.quote_volume.to_string(), "2434.19055334");
+        assert_eq!(k.trade_count, 308);
+    }
+
+    #[test]
+    fn test_malformed_ticker_price_is_an_error() {
+        let json = r#"{"symbol":"SOLUSDC","priceChange":"1.5","priceChangePercent":"1.52","lastPrice":"100.25",
+            "highPrice":"101","lowPrice":"98","volume":"1000","quoteVolume":"100250"}"#;
+        let ticker: Ticker24h = serde_json::from_str(json).unwrap();
+        assert_eq!(ticker.last_price, dec!(100.25));
+        let bad = json.replace(r#""lastPrice":"100.25""#, r#""lastPrice":"n/a""#);
+        assert!(serde_json::from_str::<Ticker24h>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_executed_trade_is_exact() {
+        let json = r#"{
+            "symbol": "PEPEUSDC", "orderId": 1, "status": "FILLED", "side": "BUY", "type": "MARKET",
+            "executedQty": "1000000.00", "cummulativeQuoteQty": "10.10000000",
+            "fills": [
+                {"price": "0.00001010", "qty": "600000.00", "commission": "600.00", "commissionAsset": "PEPE"},
+                {"price": "0.00001010", "qty": "400000.00", "commission": "400.00", "commissionAsset": "PEPE"}
+            ]
+        }"#;
+        let order: OrderResponse = serde_json::from_str(json).unwrap();
+        let trade = order.to_executed_trade("USDC", &HashMap::new());
+        assert_eq!(trade.avg_price, dec!(0.0000101));
+        assert_eq!(trade.net_quantity, dec!(999000));
+        assert_eq!(trade.commission_quote, dec!(0.0101));
+        assert_eq!(order.commission_in("PEPE"), dec!(1000));
+        assert_eq!(order.commission_in("BNB"), Decimal::ZERO);
+        assert!(order.foreign_commission_assets("USDC").is_empty());
+    }
+
+    #[test]
+    fn test_bnb_commission_valued_in_quote() {
+        let json = r#"{
+            "symbol": "SOLUSDC", "orderId": 2, "status": "FILLED", "side": "BUY", "type": "MARKET",
+            "executedQty": "2.000", "cummulativeQuoteQty": "300.00000000",
+            "fills": [
+                {"price": "150.00", "qty": "2.000", "commission": "0.00037500", "commissionAsset": "BNB"}
+            ]
+        }"#;
+        let order: OrderResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(order.foreign_commission_assets("USDC"), vec!["BNB".to_string()]);
+
+        // BNB fees don't reduce the SOL received
+        let prices = HashMap::from([("BNB".to_string(), dec!(600))]);
+        let trade = order.to_executed_trade("USDC", &prices);
+        assert_eq!(trade.net_quantity, dec!(2));
+        assert_eq!(trade.commission_quote, dec!(0.225));
+
+        // Without a BNB price the fee can't be valued
+        let unpriced = order.to_executed_trade("USDC", &HashMap::new());
+        assert_eq!(unpriced.commission_quote, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_order_status_helpers() {
+        let json = r#"{
+            "symbol": "SOLUSDC", "orderId": 3, "status": "EXPIRED", "side": "BUY", "type": "MARKET",
+            "executedQty": "0.00000000", "cummulativeQuoteQty": "0.00000000"
+        }"#;
+        let mut order: OrderResponse = serde_json::from_str(json).unwrap();
+        assert!(!order.is_filled());
+        assert!(!order.is_working());
+        assert_eq!(order.to_executed_trade("USDC", &HashMap::new()).avg_price, Decimal::ZERO);
+
+        order.status = "PARTIALLY_FILLED".to_string();
+        assert!(order.is_working());
+    }
+
+    #[test]
+    fn test_oco_legs_identified_by_type() {
+        let json = r#"{
+            "orderListId": 42, "listOrderStatus": "EXECUTING",
+            "orderReports": [
+                {"orderId": 7, "type": "STOP_LOSS_LIMIT"},
+                {"orderId": 8, "type": "LIMIT_MAKER"}
+            ]
+        }"#;
+        let list: OcoOrderResponse = serde_json::from_str(json).unwrap();
+        let oco = list.to_oco_order().unwrap();
+        assert_eq!(oco.order_list_id, 42);
+        assert_eq!(oco.take_profit_order_id, 8);
+        assert_eq!(oco.stop_loss_order_id, 7);
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{info, warn};
+
+use super::client::BinanceClient;
+use super::streams::{parse_user_event, ExecutionReport, UserEvent};
+use super::types::{commission_in_quote, AssetBalance};
+use crate::config::TradingMode;
+use crate::db::queries;
+use crate::exchange::quote_prices;
+
+/// listenKeys expire after 60 minutes without a keepalive
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Delay before reconnecting after the stream drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// In-memory account balances kept current by the user data stream.
+/// Only trusted while a stream session is live; otherwise callers poll REST.
+#[derive(Clone, Default)]
+pub struct BalanceCache {
+    inner: Arc<RwLock<BalanceState>>,
+}
+
+#[derive(Default)]
+struct BalanceState {
+    /// asset → (free, locked)
+    balances: HashMap<String, (Decimal, Decimal)>,
+    live: bool,
+}
+
+impl BalanceCache {
+    /// Replace the whole cache from an account snapshot and mark it live
+    pub fn seed(&self, balances: &[AssetBalance]) {
+        let mut state = self.inner.write().expect("balance cache poisoned");
+        state.balances = balances
+            .iter()
+            .map(|b| (b.asset.clone(), (b.free, b.locked)))
+            .collect();
+        state.live = true;
+    }
+
+    /// Apply one asset's new balance from an `outboundAccountPosition` event
+    pub fn update(&self, asset: &str, free: Decimal, locked: Decimal) {
+        let mut state = self.inner.write().expect("balance cache poisoned");
+        state.balances.insert(asset.to_string(), (free, locked));
+    }
+
+    /// Stop serving cached values until the next stream session seeds it again
+    pub fn invalidate(&self) {
+        let mut state = self.inner.write().expect("balance cache poisoned");
+        state.live = false;
+    }
+
+    /// Free balance of an asset, or `None` if the cache isn't live
+    pub fn free(&self, asset: &str) -> Option<Decimal> {
+        let state = self.inner.read().expect("balance cache poisoned");
+        if !state.live {
+            return None;
+        }
+        Some(state.balances.get(asset).map(|b| b.0).unwrap_or_default())
+    }
+
+    /// All (asset, free + locked) pairs, or `None` if the cache isn't live
+    pub fn totals(&self) -> Option<Vec<(String, Decimal)>> {
+        let state = self.inner.read().expect("balance cache poisoned");
+        if !state.live {
+            return None;
+        }
+        Some(
+            state
+                .balances
+                .iter()
+                .map(|(asset, (free, locked))| (asset.clone(), free + locked))
+                .collect(),
+        )
+    }
+}
+
+/// listenKey-based user data stream. Keeps the client's balance cache
+/// current and records every fill on the account into `trades` —
+/// including orders placed outside the bot.
+pub struct UserDataStream {
+    client: BinanceClient,
+    pool: PgPool,
+    mode: TradingMode,
+    stream_base: String,
+}
+
+impl UserDataStream {
+    pub fn new(client: BinanceClient, pool: PgPool, mode: TradingMode, stream_base: &str) -> Self {
+        Self {
+            client,
+            pool,
+            mode,
+            stream_base: stream_base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Run forever, opening a fresh listenKey session after every disconnect
+    pub async fn run(self) {
+        info!("👤 User data stream started");
+
+        loop {
+            if let Err(e) = self.session().await {
+                warn!(error = %e, "User data stream dropped — reconnecting");
+            }
+            self.client.balances().invalidate();
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn session(&self) -> Result<()> {
+        let listen_key = self.client.create_listen_key().await?;
+        let url = format!("{}/ws/{}", self.stream_base, listen_key);
+
+        let (mut ws, _) = connect_async(url.as_str())
+            .await
+            .context("Failed to connect to user data stream")?;
+
+        // Seed after connecting so no update between snapshot and stream is lost
+        self.client.seed_balance_cache().await?;
+        info!("User data stream connected — balance cache live");
+
+        // Commission (in the quote asset) accumulated per order across partial fills
+        let mut commissions: HashMap<u64, Decimal> = HashMap::new();
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await; // First tick fires immediately
+
+        loop {
+            tokio::select! {
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(text))) => match parse_user_event(&text) {
+                        Some(UserEvent::AccountPosition { balances }) => {
+                            for b in balances {
+                                self.client.balances().update(&b.asset, b.free, b.locked);
+                            }
+                        }
+                        Some(UserEvent::ExecutionReport(report)) => {
+                            self.handle_execution(report, &mut commissions).await;
+                        }
+                        Some(UserEvent::ListenKeyExpired) => anyhow::bail!("listenKey expired"),
+                        Some(UserEvent::Other) | None => {}
+                    },
+                    Some(Ok(Message::Close(_))) | None => anyhow::bail!("User data stream closed"),
+                    Some(Err(e)) => return Err(e.into()),
+                    _ => {}
+                },
+                _ = keepalive.tick() => {
+                    self.client.keepalive_listen_key(&listen_key).await?;
+                }
+            }
+        }
+    }
+
+    /// Record an order's fills once it reaches a terminal state
+    async fn handle_execution(&self, report: ExecutionReport, commissions: &mut HashMap<u64, Decimal>) {
+        if report.execution_type == "TRADE" {
+            let fee = self.fill_commission(&report).await;
+            *commissions.entry(report.order_id).or_default() += fee;
+        }
+
+        if !report.is_terminal() {
+            return;
+        }
+        let commission = commissions.remove(&report.order_id).unwrap_or_default();
+
+        let quantity: Decimal = report.cumulative_qty.parse().unwrap_or_default();
+        if quantity <= Decimal::ZERO {
+            return;
+        }
+        let quote_qty: Decimal = report.cumulative_quote_qty.parse().unwrap_or_default();
+
+        info!(
+            symbol = %report.symbol,
+            side = %report.side,
+            order_id = report.order_id,
+            %quantity,
+            commission_asset = ?report.commission_asset,
+            "Fill reported by user data stream"
+        );
+
+        if let Err(e) = queries::insert_trade(
+            &self.pool,
+            self.mode,
+            None,
+            &report.symbol,
+            &report.side,
+            quantity,
+            quote_qty / quantity,
+            quote_qty,
+            commission,
+            Some(report.order_id as i64),
+        )
+        .await
+        {
+            warn!(error = %e, order_id = report.order_id, "Failed to record streamed fill");
+        }
+    }
+
+    /// One fill's commission, valued in the quote asset
+    async fn fill_commission(&self, report: &ExecutionReport) -> Decimal {
+        let amount: Decimal = report
+            .commission
+            .as_deref()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or_default();
+        let Some(asset) = report.commission_asset.as_deref() else {
+            return Decimal::ZERO;
+        };
+        let quote_asset = self.client.quote_asset();
+        let base_asset = report.symbol.strip_suffix(quote_asset).unwrap_or(&report.symbol);
+        let fill_price: Decimal = report.last_price.parse().unwrap_or_default();
+
+        let prices = if amount.is_zero() || asset == quote_asset || asset == base_asset {
+            HashMap::new()
+        } else {
+            quote_prices(&self.client, &[asset.to_string()]).await
+        };
+        commission_in_quote(asset, amount, base_asset, quote_asset, fill_price, &prices)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_balance_cache_only_serves_when_live() {
+        let cache = BalanceCache::default();
+        assert_eq!(cache.free("USDC"), None);
+
+        cache.seed(&[AssetBalance {
+            asset: "USDC".to_string(),
+            free: dec!(25.5),
+            locked: dec!(1.0),
+        }]);
+        assert_eq!(cache.free("USDC"), Some(dec!(25.5)));
+        assert_eq!(cache.free("BTC"), Some(Decimal::ZERO));
+
+        cache.update("USDC", dec!(20), Decimal::ZERO);
+        assert_eq!(cache.free("USDC"), Some(dec!(20)));
+
+        cache.invalidate();
+        assert_eq!(cache.free("USDC"), None);
+        assert!(cache.totals().is_none());
+    }
+}
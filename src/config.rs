@@ -1,4 +1,119 @@
 use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+use crate::binance::KLINE_INTERVALS;
+
+/// Environment the bot trades in. Picks the exchange implementation and
+/// endpoints, and is stamped on every position, trade and cycle row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradingMode {
+    /// Real orders on Binance with real funds
+    Live,
+    /// Real orders on the Binance Spot Testnet with test funds
+    Testnet,
+    /// Simulated fills against a Postgres ledger, priced from live market data
+    Paper,
+}
+
+impl TradingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradingMode::Live => "live",
+            TradingMode::Testnet => "testnet",
+            TradingMode::Paper => "paper",
+        }
+    }
+
+    /// REST endpoint unless overridden by BINANCE_BASE_URL
+    fn default_base_url(&self) -> &'static str {
+        match self {
+            TradingMode::Testnet => "https://testnet.binance.vision",
+            TradingMode::Live | TradingMode::Paper => "https://api.binance.com",
+        }
+    }
+
+    /// WebSocket endpoint unless overridden by BINANCE_STREAM_URL
+    fn default_stream_url(&self) -> &'static str {
+        match self {
+            TradingMode::Testnet => "wss://stream.testnet.binance.vision",
+            TradingMode::Live | TradingMode::Paper => "wss://stream.binance.com:9443",
+        }
+    }
+}
+
+impl std::fmt::Display for TradingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TradingMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "live" => Ok(TradingMode::Live),
+            "testnet" => Ok(TradingMode::Testnet),
+            "paper" => Ok(TradingMode::Paper),
+            other => anyhow::bail!("Unknown TRADING_MODE '{}' (expected live, testnet or paper)", other),
+        }
+    }
+}
+
+/// Where trading decisions come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecisionProviderKind {
+    /// OpenClaw, relayed through a Discord channel
+    Discord,
+    /// A chat model called directly over HTTP
+    Llm,
+}
+
+impl std::str::FromStr for DecisionProviderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "discord" | "openclaw" => Ok(DecisionProviderKind::Discord),
+            "llm" => Ok(DecisionProviderKind::Llm),
+            other => anyhow::bail!("Unknown DECISION_PROVIDER '{}' (expected discord or llm)", other),
+        }
+    }
+}
+
+/// Wire format of the chat API the LLM provider talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmApi {
+    /// Anthropic Messages API (`POST /messages`)
+    Messages,
+    /// OpenAI-style Chat Completions API (`POST /chat/completions`)
+    ChatCompletions,
+}
+
+impl LlmApi {
+    fn default_base_url(&self) -> &'static str {
+        match self {
+            LlmApi::Messages => "https://api.anthropic.com/v1",
+            LlmApi::ChatCompletions => "https://api.openai.com/v1",
+        }
+    }
+}
+
+impl std::str::FromStr for LlmApi {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "messages" | "anthropic" => Ok(LlmApi::Messages),
+            "chat_completions" | "openai" => Ok(LlmApi::ChatCompletions),
+            other => anyhow::bail!("Unknown LLM_API '{}' (expected messages or chat_completions)", other),
+        }
+    }
+}
+
+/// Value CONFIRM_LIVE_TRADING must hold before live mode will start
+const LIVE_CONFIRMATION: &str = "yes";
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -9,18 +124,82 @@ pub struct Config {
     pub binance_api_key: String,
     pub binance_secret_key: String,
     pub binance_base_url: String,
+    pub binance_stream_url: String,
+    /// How long a signed request stays valid after its timestamp
+    pub binance_recv_window_ms: u64,
+    /// How often the server clock offset is re-measured
+    pub binance_time_sync_secs: u64,
+    /// Request weight per minute the client allows itself (Binance's IP limit is 6000)
+    pub binance_weight_budget: u32,
+
+    // Trading mode / paper trading
+    pub mode: TradingMode,
+    /// Asset every traded pair is quoted in and balances are measured in (USDC, USDT, FDUSD, EUR…)
+    pub quote_asset: String,
+    /// Quote-asset balance a fresh paper ledger starts with
+    pub paper_starting_balance: Decimal,
+    pub paper_fee_rate: Decimal,
+    pub paper_slippage_bps: Decimal,
+
+    // Decisions
+    pub decision_provider: DecisionProviderKind,
+    /// Seconds from asking to a usable decision; a corrective re-ask is
+    /// only sent while some of this is left
+    pub decision_time_budget_secs: u64,
 
     // Discord / OpenClaw
     pub discord_bot_token: String,
     pub discord_channel_id: String,
     pub openclaw_user_id: String,
+    /// Receive OpenClaw's replies over the Gateway websocket instead of polling
+    pub discord_gateway: bool,
+    pub discord_gateway_url: String,
+
+    // Direct LLM
+    pub llm_api: LlmApi,
+    /// API root the endpoint path is appended to, e.g. https://api.anthropic.com/v1
+    pub llm_base_url: String,
+    pub llm_api_key: String,
+    pub llm_model: String,
+    pub llm_max_tokens: u32,
+    /// How long to wait for an answer before the cycle HOLDs
+    pub llm_timeout_secs: u64,
 
     // Server
     pub api_host: String,
     pub api_port: u16,
 
     // Trading
-    pub min_balance_usdc: f64,
+    /// Quote-asset reserve the bot never trades below
+    pub min_balance: Decimal,
+    /// Gap between an OCO's stop trigger and its limit price, in percent
+    pub oco_stop_limit_buffer_pct: Decimal,
+    /// Kline interval ATR-based trailing stops are measured on
+    pub trailing_atr_interval: String,
+    /// How far (in %) a trailing stop must rise before a live OCO is replaced
+    pub trailing_oco_min_move_pct: Decimal,
+
+    // Tradable universe
+    /// Base assets the bot may buy; `None` allows any asset not denied
+    pub symbol_allowlist: Option<HashSet<String>>,
+    /// Base assets the bot must never buy
+    pub symbol_denylist: HashSet<String>,
+
+    // Reconciliation
+    /// How often open positions are checked against exchange holdings
+    pub reconcile_interval_secs: u64,
+    /// Open a position for an untracked holding instead of only flagging it
+    pub reconcile_adopt_orphans: bool,
+    /// Close a position the exchange no longer holds instead of only flagging it
+    pub reconcile_close_phantoms: bool,
+    /// Assets held outside the bot's positions (e.g. BNB for fee discounts)
+    pub reconcile_ignore_assets: Vec<String>,
+
+    // Market data
+    /// Kline intervals ingested into `candles` each cycle
+    pub kline_intervals: Vec<String>,
+    /// Candles fetched per symbol and interval
+    pub kline_limit: u32,
 
     // Kill switch
     pub kill_secret: String,
@@ -30,6 +209,27 @@ impl Config {
     pub fn from_env() -> Result<Self> {
         dotenvy::dotenv().ok(); // Load .env if present, ignore if missing
 
+        let mode: TradingMode = std::env::var("TRADING_MODE")
+            .unwrap_or_else(|_| "paper".to_string())
+            .parse()?;
+        let binance_base_url = std::env::var("BINANCE_BASE_URL")
+            .unwrap_or_else(|_| mode.default_base_url().to_string());
+        check_mode(
+            mode,
+            &binance_base_url,
+            std::env::var("CONFIRM_LIVE_TRADING").ok().as_deref(),
+        )?;
+
+        // Credentials are only required for the provider in use
+        let decision_provider: DecisionProviderKind = std::env::var("DECISION_PROVIDER")
+            .unwrap_or_else(|_| "discord".to_string())
+            .parse()?;
+        let discord = decision_provider == DecisionProviderKind::Discord;
+        let llm = decision_provider == DecisionProviderKind::Llm;
+        let llm_api: LlmApi = std::env::var("LLM_API")
+            .unwrap_or_else(|_| "messages".to_string())
+            .parse()?;
+
         Ok(Config {
             database_url: std::env::var("DATABASE_URL")
                 .context("DATABASE_URL not set")?,
@@ -37,25 +237,251 @@ impl Config {
                 .context("BINANCE_API_KEY not set")?,
             binance_secret_key: std::env::var("BINANCE_SECRET_KEY")
                 .context("BINANCE_SECRET_KEY not set")?,
-            binance_base_url: std::env::var("BINANCE_BASE_URL")
-                .unwrap_or_else(|_| "https://api.binance.com".to_string()),
-            discord_bot_token: std::env::var("DISCORD_BOT_TOKEN")
-                .context("DISCORD_BOT_TOKEN not set")?,
-            discord_channel_id: std::env::var("DISCORD_CHANNEL_ID")
-                .context("DISCORD_CHANNEL_ID not set")?,
-            openclaw_user_id: std::env::var("OPENCLAW_USER_ID")
-                .context("OPENCLAW_USER_ID not set")?,
+            binance_base_url,
+            binance_stream_url: std::env::var("BINANCE_STREAM_URL")
+                .unwrap_or_else(|_| mode.default_stream_url().to_string()),
+            binance_recv_window_ms: std::env::var("BINANCE_RECV_WINDOW_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .context("BINANCE_RECV_WINDOW_MS must be a valid number")?,
+            binance_time_sync_secs: std::env::var("BINANCE_TIME_SYNC_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .context("BINANCE_TIME_SYNC_SECS must be a valid number")?,
+            binance_weight_budget: std::env::var("BINANCE_WEIGHT_BUDGET")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .context("BINANCE_WEIGHT_BUDGET must be a valid number")?,
+            mode,
+            quote_asset: parse_quote_asset(
+                &std::env::var("QUOTE_ASSET").unwrap_or_else(|_| "USDC".to_string()),
+            )?,
+            paper_starting_balance: std::env::var("PAPER_STARTING_BALANCE")
+                .unwrap_or_else(|_| "100.0".to_string())
+                .parse()
+                .context("PAPER_STARTING_BALANCE must be a valid number")?,
+            paper_fee_rate: std::env::var("PAPER_FEE_RATE")
+                .unwrap_or_else(|_| "0.001".to_string())
+                .parse()
+                .context("PAPER_FEE_RATE must be a valid number")?,
+            paper_slippage_bps: std::env::var("PAPER_SLIPPAGE_BPS")
+                .unwrap_or_else(|_| "5.0".to_string())
+                .parse()
+                .context("PAPER_SLIPPAGE_BPS must be a valid number")?,
+            decision_provider,
+            decision_time_budget_secs: std::env::var("DECISION_TIME_BUDGET_SECS")
+                .unwrap_or_else(|_| "180".to_string())
+                .parse()
+                .context("DECISION_TIME_BUDGET_SECS must be a valid number")?,
+            discord_bot_token: provider_var("DISCORD_BOT_TOKEN", discord)?,
+            discord_channel_id: provider_var("DISCORD_CHANNEL_ID", discord)?,
+            openclaw_user_id: provider_var("OPENCLAW_USER_ID", discord)?,
+            discord_gateway: std::env::var("DISCORD_GATEWAY")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .context("DISCORD_GATEWAY must be true or false")?,
+            discord_gateway_url: std::env::var("DISCORD_GATEWAY_URL")
+                .unwrap_or_else(|_| "wss://gateway.discord.gg".to_string()),
+            llm_api,
+            llm_base_url: std::env::var("LLM_BASE_URL")
+                .unwrap_or_else(|_| llm_api.default_base_url().to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            llm_api_key: provider_var("LLM_API_KEY", llm)?,
+            llm_model: provider_var("LLM_MODEL", llm)?,
+            llm_max_tokens: std::env::var("LLM_MAX_TOKENS")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()
+                .context("LLM_MAX_TOKENS must be a valid number")?,
+            llm_timeout_secs: std::env::var("LLM_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("LLM_TIMEOUT_SECS must be a valid number")?,
             api_host: std::env::var("API_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             api_port: std::env::var("API_PORT")
                 .unwrap_or_else(|_| "3001".to_string())
                 .parse()
                 .context("API_PORT must be a valid port number")?,
-            min_balance_usdc: std::env::var("MIN_BALANCE_USDC")
+            // MIN_BALANCE_USDC is the pre-QUOTE_ASSET name, still honoured
+            min_balance: std::env::var("MIN_BALANCE")
+                .or_else(|_| std::env::var("MIN_BALANCE_USDC"))
                 .unwrap_or_else(|_| "5.0".to_string())
                 .parse()
-                .context("MIN_BALANCE_USDC must be a valid number")?,
+                .context("MIN_BALANCE must be a valid number")?,
+            oco_stop_limit_buffer_pct: std::env::var("OCO_STOP_LIMIT_BUFFER_PCT")
+                .unwrap_or_else(|_| "0.5".to_string())
+                .parse()
+                .context("OCO_STOP_LIMIT_BUFFER_PCT must be a valid number")?,
+            trailing_atr_interval: parse_kline_intervals(
+                &std::env::var("TRAILING_ATR_INTERVAL").unwrap_or_else(|_| "1h".to_string()),
+            )?
+            .into_iter()
+            .next()
+            .context("TRAILING_ATR_INTERVAL must be a kline interval")?,
+            trailing_oco_min_move_pct: std::env::var("TRAILING_OCO_MIN_MOVE_PCT")
+                .unwrap_or_else(|_| "0.5".to_string())
+                .parse()
+                .context("TRAILING_OCO_MIN_MOVE_PCT must be a valid number")?,
+            symbol_allowlist: read_asset_list("SYMBOL_ALLOWLIST_FILE", "symbols/allowlist.txt")?,
+            symbol_denylist: read_asset_list("SYMBOL_DENYLIST_FILE", "symbols/denylist.txt")?
+                .unwrap_or_default(),
+            reconcile_interval_secs: std::env::var("RECONCILE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .context("RECONCILE_INTERVAL_SECS must be a valid number")?,
+            reconcile_adopt_orphans: std::env::var("RECONCILE_ADOPT_ORPHANS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("RECONCILE_ADOPT_ORPHANS must be true or false")?,
+            reconcile_close_phantoms: std::env::var("RECONCILE_CLOSE_PHANTOMS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("RECONCILE_CLOSE_PHANTOMS must be true or false")?,
+            reconcile_ignore_assets: std::env::var("RECONCILE_IGNORE_ASSETS")
+                .unwrap_or_else(|_| "BNB".to_string())
+                .split(',')
+                .map(|a| a.trim().to_ascii_uppercase())
+                .filter(|a| !a.is_empty())
+                .collect(),
+            kline_intervals: parse_kline_intervals(
+                &std::env::var("KLINE_INTERVALS").unwrap_or_else(|_| "1h".to_string()),
+            )?,
+            kline_limit: std::env::var("KLINE_LIMIT")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .context("KLINE_LIMIT must be a valid number")?,
             kill_secret: std::env::var("KILL_SECRET")
                 .unwrap_or_else(|_| "changeme".to_string()),
         })
     }
 }
+
+/// A variable only one decision provider uses: required when `needed`,
+/// empty otherwise
+fn provider_var(var: &str, needed: bool) -> Result<String> {
+    match std::env::var(var) {
+        Ok(value) => Ok(value),
+        Err(_) if !needed => Ok(String::new()),
+        Err(_) => anyhow::bail!("{} not set", var),
+    }
+}
+
+/// Safety interlock: live mode needs an explicit confirmation, and no mode
+/// may point at the other environment's endpoint, so testnet fills can
+/// never be recorded as live history or the reverse.
+fn check_mode(mode: TradingMode, base_url: &str, confirmation: Option<&str>) -> Result<()> {
+    let testnet_url = base_url.contains("testnet");
+    match mode {
+        TradingMode::Live => {
+            if confirmation.map(str::trim) != Some(LIVE_CONFIRMATION) {
+                anyhow::bail!(
+                    "TRADING_MODE=live trades real funds — set CONFIRM_LIVE_TRADING={} to start",
+                    LIVE_CONFIRMATION
+                );
+            }
+            if testnet_url {
+                anyhow::bail!("TRADING_MODE=live but BINANCE_BASE_URL points at the testnet: {}", base_url);
+            }
+        }
+        TradingMode::Testnet => {
+            if base_url.contains("api.binance.com") {
+                anyhow::bail!("TRADING_MODE=testnet but BINANCE_BASE_URL points at live Binance: {}", base_url);
+            }
+        }
+        TradingMode::Paper => {}
+    }
+    Ok(())
+}
+
+/// Normalize an asset code like "usdt" to "USDT", rejecting anything that can't be one
+fn parse_quote_asset(raw: &str) -> Result<String> {
+    let asset = raw.trim().to_ascii_uppercase();
+    if asset.is_empty() || !asset.chars().all(|c| c.is_ascii_alphanumeric()) {
+        anyhow::bail!("QUOTE_ASSET must be an asset code like USDC or EUR, got '{}'", raw);
+    }
+    Ok(asset)
+}
+
+/// Load the asset list file named by `var` (or `default_path`). An empty
+/// `var` turns the list off; a configured file that can't be read is an error.
+fn read_asset_list(var: &str, default_path: &str) -> Result<Option<HashSet<String>>> {
+    let path = std::env::var(var).unwrap_or_else(|_| default_path.to_string());
+    if path.trim().is_empty() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("{} could not be read from {} — set {}= to disable it", var, path, var))?;
+    Ok(Some(parse_asset_list(&text)))
+}
+
+/// Asset codes from a list file: whitespace- or comma-separated, `#` comments
+fn parse_asset_list(text: &str) -> HashSet<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|a| !a.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect()
+}
+
+/// Parse a comma-separated interval list, rejecting anything Binance doesn't accept
+fn parse_kline_intervals(raw: &str) -> Result<Vec<String>> {
+    let intervals: Vec<String> = raw
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if let Some(bad) = intervals.iter().find(|i| !KLINE_INTERVALS.contains(&i.as_str())) {
+        anyhow::bail!("Unknown kline interval '{}' in KLINE_INTERVALS", bad);
+    }
+    Ok(intervals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_parsing() {
+        assert_eq!("LIVE".parse::<TradingMode>().unwrap(), TradingMode::Live);
+        assert_eq!(" testnet ".parse::<TradingMode>().unwrap(), TradingMode::Testnet);
+        assert_eq!("paper".parse::<TradingMode>().unwrap(), TradingMode::Paper);
+        assert!("demo".parse::<TradingMode>().is_err());
+        assert!("binance".parse::<TradingMode>().is_err());
+    }
+
+    #[test]
+    fn test_decision_provider_parsing() {
+        assert_eq!("OpenClaw".parse::<DecisionProviderKind>().unwrap(), DecisionProviderKind::Discord);
+        assert_eq!("llm".parse::<DecisionProviderKind>().unwrap(), DecisionProviderKind::Llm);
+        assert_eq!("openai".parse::<LlmApi>().unwrap(), LlmApi::ChatCompletions);
+        assert_eq!(" messages ".parse::<LlmApi>().unwrap(), LlmApi::Messages);
+        assert!("gemini".parse::<LlmApi>().is_err());
+    }
+
+    #[test]
+    fn test_live_requires_confirmation() {
+        let live = TradingMode::Live.default_base_url();
+        assert!(check_mode(TradingMode::Live, live, None).is_err());
+        assert!(check_mode(TradingMode::Live, live, Some("true")).is_err());
+        assert!(check_mode(TradingMode::Live, live, Some("yes")).is_ok());
+        assert!(check_mode(TradingMode::Paper, live, None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_asset_list() {
+        let assets = parse_asset_list("# halal\nbtc\nETH, sol  # reviewed\n\n  # XRP\n");
+        let expected: HashSet<String> = ["BTC", "ETH", "SOL"].iter().map(|a| a.to_string()).collect();
+        assert_eq!(assets, expected);
+    }
+
+    #[test]
+    fn test_mode_rejects_other_environment_url() {
+        let live = TradingMode::Live.default_base_url();
+        let testnet = TradingMode::Testnet.default_base_url();
+        assert!(check_mode(TradingMode::Live, testnet, Some("yes")).is_err());
+        assert!(check_mode(TradingMode::Testnet, live, None).is_err());
+        assert!(check_mode(TradingMode::Testnet, testnet, None).is_ok());
+    }
+}
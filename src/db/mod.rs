@@ -1,2 +1,4 @@
 pub mod models;
 pub mod queries;
+#[cfg(test)]
+pub mod test_db;
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -19,18 +20,34 @@ pub struct BotStatus {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Position {
     pub id: Uuid,
+    /// Trading mode the row was written in (live / testnet / paper)
+    pub mode: String,
     pub symbol: String,
     pub side: String,
-    pub quantity: f64,
-    pub entry_price: f64,
-    pub current_price: Option<f64>,
-    pub stop_loss: Option<f64>,
-    pub take_profit: Option<f64>,
+    pub quantity: Decimal,
+    pub entry_price: Decimal,
+    pub current_price: Option<Decimal>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
     pub status: String,
-    pub pnl: Option<f64>,
+    /// Realized PnL net of entry and exit commission
+    pub pnl: Option<Decimal>,
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
     pub close_reason: Option<String>,
+    pub oco_order_list_id: Option<i64>,
+    pub tp_order_id: Option<i64>,
+    pub sl_order_id: Option<i64>,
+    /// Commission paid on the entry fill, in the quote asset
+    pub entry_commission: Decimal,
+    /// Realized PnL from the price move alone
+    pub gross_pnl: Option<Decimal>,
+    /// Trailing stop: the stop follows this % below `high_water_mark`…
+    pub trail_pct: Option<Decimal>,
+    /// …or this far below it, in the quote asset
+    pub trail_distance: Option<Decimal>,
+    /// Highest price seen since entry, for trailing positions
+    pub high_water_mark: Option<Decimal>,
 }
 
 // ─── Trade ───────────────────────────────────────────────
@@ -38,13 +55,18 @@ pub struct Position {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Trade {
     pub id: Uuid,
+    /// Trading mode the row was written in (live / testnet / paper)
+    pub mode: String,
     pub position_id: Option<Uuid>,
     pub symbol: String,
     pub side: String,
-    pub quantity: f64,
-    pub price: f64,
-    pub usdc_amount: f64,
-    pub commission: Option<f64>,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    /// Value of the fill in the quote asset
+    pub quote_amount: Decimal,
+    /// Commission in the quote asset, whatever asset it was charged in
+    pub commission: Option<Decimal>,
+    pub exchange_order_id: Option<i64>,
     pub executed_at: DateTime<Utc>,
 }
 
@@ -53,8 +75,10 @@ pub struct Trade {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct CycleLog {
     pub id: Uuid,
+    /// Trading mode the row was written in (live / testnet / paper)
+    pub mode: String,
     pub cycle_number: i32,
-    pub balance_usdc: f64,
+    pub balance: Decimal,
     pub action: String,
     pub symbol: Option<String>,
     pub confidence: Option<i32>,
@@ -65,6 +89,13 @@ pub struct CycleLog {
     pub result: Option<String>,
     pub error: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Provider that answered this cycle's prompt, its model and answer time
+    pub decision_provider: Option<String>,
+    pub decision_model: Option<String>,
+    pub decision_latency_ms: Option<i32>,
+    /// Prompt / completion tokens billed, for providers that report them
+    pub decision_input_tokens: Option<i32>,
+    pub decision_output_tokens: Option<i32>,
 }
 
 // ─── Balance History ─────────────────────────────────────
@@ -72,10 +103,113 @@ pub struct CycleLog {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct BalanceHistory {
     pub id: i32,
-    pub balance_usdc: f64,
+    /// Trading mode the row was written in (live / testnet / paper)
+    pub mode: String,
+    pub balance: Decimal,
     pub open_positions: i32,
-    pub total_pnl: f64,
+    pub total_pnl: Decimal,
     pub recorded_at: DateTime<Utc>,
+    /// Asset `balance` and `total_pnl` are measured in
+    pub quote_asset: String,
+}
+
+// ─── Candles ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Candle {
+    pub symbol: String,
+    pub interval: String,
+    pub open_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub quote_volume: Decimal,
+    pub trade_count: i64,
+    pub close_time: DateTime<Utc>,
+}
+
+// ─── Paper Trading ───────────────────────────────────────
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PaperOrder {
+    pub order_id: i64,
+    pub symbol: String,
+    pub side: String,
+    pub status: String,
+    pub executed_qty: Decimal,
+    pub cummulative_quote_qty: Decimal,
+    pub price: Decimal,
+    pub commission: Decimal,
+    pub commission_asset: String,
+    pub created_at: DateTime<Utc>,
+    pub client_order_id: Option<String>,
+}
+
+// ─── Order Intents ───────────────────────────────────────
+
+/// A market order recorded before submission. `PENDING` until the exchange
+/// confirms it, `PLACED` until its position/trade rows are written, then
+/// `RECORDED` — or `FAILED` if it never reached the book.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct OrderIntent {
+    pub client_order_id: String,
+    /// Trading mode the order was placed in (live / testnet / paper)
+    pub mode: String,
+    pub symbol: String,
+    pub side: String,
+    /// Quote asset to spend for a BUY, base quantity for a SELL
+    pub amount: Decimal,
+    pub position_id: Option<Uuid>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub reason: Option<String>,
+    pub status: String,
+    pub exchange_order_id: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Trailing stop requested with a BUY
+    pub trail_pct: Option<Decimal>,
+    pub trail_atr_multiple: Option<Decimal>,
+}
+
+impl OrderIntent {
+    /// The trailing stop this BUY was placed with, if any
+    pub fn trailing_stop(&self) -> Option<TrailingStop> {
+        match (self.trail_pct, self.trail_atr_multiple) {
+            (Some(pct), _) => Some(TrailingStop::Percent(pct)),
+            (None, Some(multiple)) => Some(TrailingStop::Atr(multiple)),
+            (None, None) => None,
+        }
+    }
+}
+
+// ─── Discrepancies ───────────────────────────────────────
+
+/// A disagreement between open positions and exchange holdings found by
+/// reconciliation. `FLAGGED` rows stay open until the disagreement clears;
+/// `ADOPTED` and `CLOSED` rows record a fix and are resolved when written.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub id: Uuid,
+    /// Trading mode the row was written in (live / testnet / paper)
+    pub mode: String,
+    /// ORPHANED_HOLDING, PHANTOM_POSITION or QUANTITY_MISMATCH
+    pub kind: String,
+    pub asset: String,
+    pub symbol: String,
+    /// Position adopted or closed to fix it
+    pub position_id: Option<Uuid>,
+    /// Total quantity of open positions in the asset
+    pub expected_qty: Decimal,
+    /// Free + locked on the exchange
+    pub actual_qty: Decimal,
+    pub action: String,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
 }
 
 // ─── Trading Decision (from OpenClaw) ────────────────────
@@ -86,8 +220,20 @@ pub struct TradingDecision {
     pub symbol: Option<String>,
     pub confidence: i32,
     pub reasoning: String,
-    pub stop_loss: Option<f64>,
-    pub take_profit: Option<f64>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    /// Trail the stop-loss below the highest price instead of fixing it
+    #[serde(default)]
+    pub trailing_stop: Option<TrailingStop>,
+}
+
+/// How a requested trailing stop follows the price: `{"percent": 3}` keeps
+/// it 3% below the high, `{"atr": 2}` two average true ranges below it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingStop {
+    Percent(Decimal),
+    Atr(Decimal),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -113,29 +259,62 @@ impl std::fmt::Display for TradingAction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CycleUpdate {
     pub cycle_number: i32,
-    pub balance_usdc: f64,
+    pub balance: Decimal,
     pub action: String,
     pub symbol: Option<String>,
     pub confidence: Option<i32>,
     pub reasoning: Option<String>,
-    pub pnl: f64,
+    pub pnl: Decimal,
     pub fear_greed: Option<i32>,
     pub timestamp: DateTime<Utc>,
 }
 
+// ─── Price Tick (broadcast via WebSocket) ─────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceTick {
+    pub symbol: String,
+    pub price: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Everything pushed to dashboard WebSocket subscribers, tagged by `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DashboardEvent {
+    Cycle(CycleUpdate),
+    PriceTick(PriceTick),
+}
+
 // ─── API Response Types ──────────────────────────────────
 
 #[derive(Debug, Serialize)]
 pub struct StatusResponse {
     pub is_alive: bool,
-    pub balance_usdc: f64,
-    pub total_pnl: f64,
+    /// Trading mode every figure below is scoped to (live / testnet / paper)
+    pub mode: String,
+    pub balance: Decimal,
+    /// Asset `balance` and `total_pnl` are measured in
+    pub quote_asset: String,
+    pub total_pnl: Decimal,
     pub open_positions: i32,
     pub total_trades: i64,
     pub total_cycles: i64,
     pub win_rate: f64,
     pub uptime_hours: f64,
     pub last_cycle_at: Option<DateTime<Utc>>,
+    /// Exchange-minus-local clock offset applied to signed requests
+    pub clock_drift_ms: Option<i64>,
+    pub rate_limit: Option<RateLimitUsage>,
+}
+
+/// Binance API budget consumption in the current windows
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitUsage {
+    pub used_weight_1m: u32,
+    pub weight_budget_1m: u32,
+    pub orders_10s: u32,
+    pub orders_1d: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use chrono::Utc;
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::models::*;
+use crate::binance::Kline;
+use crate::config::TradingMode;
 
 // ─── Bot Status ──────────────────────────────────────────
 
@@ -23,6 +26,8 @@ pub async fn kill_bot(pool: &PgPool, reason: &str) -> Result<()> {
     Ok(())
 }
 
+// Operator helper for reviving a dead bot; nothing in the bot calls it
+#[allow(dead_code)]
 pub async fn revive_bot(pool: &PgPool) -> Result<()> {
     sqlx::query(
         "UPDATE bot_status SET is_dead = FALSE, death_reason = NULL, updated_at = $1",
@@ -35,43 +40,63 @@ pub async fn revive_bot(pool: &PgPool) -> Result<()> {
 
 // ─── Positions ───────────────────────────────────────────
 
-pub async fn get_open_positions(pool: &PgPool) -> Result<Vec<Position>> {
-    let positions =
-        sqlx::query_as::<_, Position>("SELECT * FROM positions WHERE status = 'OPEN' ORDER BY opened_at DESC")
-            .fetch_all(pool)
-            .await?;
+pub async fn get_open_positions(pool: &PgPool, mode: TradingMode) -> Result<Vec<Position>> {
+    let positions = sqlx::query_as::<_, Position>(
+        "SELECT * FROM positions WHERE mode = $1 AND status = 'OPEN' ORDER BY opened_at DESC",
+    )
+    .bind(mode.as_str())
+    .fetch_all(pool)
+    .await?;
     Ok(positions)
 }
 
-pub async fn get_position_by_symbol(pool: &PgPool, symbol: &str) -> Result<Option<Position>> {
+pub async fn get_position_by_symbol(
+    pool: &PgPool,
+    mode: TradingMode,
+    symbol: &str,
+) -> Result<Option<Position>> {
     let position = sqlx::query_as::<_, Position>(
-        "SELECT * FROM positions WHERE symbol = $1 AND status = 'OPEN' LIMIT 1",
+        "SELECT * FROM positions WHERE mode = $1 AND symbol = $2 AND status = 'OPEN' LIMIT 1",
     )
+    .bind(mode.as_str())
     .bind(symbol)
     .fetch_optional(pool)
     .await?;
     Ok(position)
 }
 
+pub async fn get_position_by_id(pool: &PgPool, position_id: Uuid) -> Result<Option<Position>> {
+    let position = sqlx::query_as::<_, Position>("SELECT * FROM positions WHERE id = $1")
+        .bind(position_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(position)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_position(
     pool: &PgPool,
+    mode: TradingMode,
     symbol: &str,
     side: &str,
-    quantity: f64,
-    entry_price: f64,
-    stop_loss: Option<f64>,
-    take_profit: Option<f64>,
+    quantity: Decimal,
+    entry_price: Decimal,
+    entry_commission: Decimal,
+    stop_loss: Option<Decimal>,
+    take_profit: Option<Decimal>,
 ) -> Result<Uuid> {
     let id = Uuid::new_v4();
     sqlx::query(
-        "INSERT INTO positions (id, symbol, side, quantity, entry_price, stop_loss, take_profit, status, opened_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, 'OPEN', $8)",
+        "INSERT INTO positions (id, mode, symbol, side, quantity, entry_price, entry_commission, stop_loss, take_profit, status, opened_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'OPEN', $10)",
     )
     .bind(id)
+    .bind(mode.as_str())
     .bind(symbol)
     .bind(side)
     .bind(quantity)
     .bind(entry_price)
+    .bind(entry_commission)
     .bind(stop_loss)
     .bind(take_profit)
     .bind(Utc::now())
@@ -83,13 +108,58 @@ pub async fn insert_position(
 pub async fn close_position(
     pool: &PgPool,
     position_id: Uuid,
-    pnl: f64,
+    pnl: Decimal,
+    gross_pnl: Decimal,
     reason: &str,
+) -> Result<()> {
+    // Adds to PnL already realized by earlier partial sells
+    sqlx::query(
+        "UPDATE positions SET status = 'CLOSED', pnl = COALESCE(pnl, 0) + $1,
+            gross_pnl = COALESCE(gross_pnl, 0) + $2, closed_at = $3, close_reason = $4
+         WHERE id = $5",
+    )
+    .bind(pnl)
+    .bind(gross_pnl)
+    .bind(Utc::now())
+    .bind(reason)
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Shrink an open position after a partial sell, booking the realized PnL
+/// and the entry commission attributed to the quantity sold
+pub async fn reduce_position(
+    pool: &PgPool,
+    position_id: Uuid,
+    remaining_quantity: Decimal,
+    entry_commission_sold: Decimal,
+    pnl: Decimal,
+    gross_pnl: Decimal,
 ) -> Result<()> {
     sqlx::query(
-        "UPDATE positions SET status = 'CLOSED', pnl = $1, closed_at = $2, close_reason = $3 WHERE id = $4",
+        "UPDATE positions SET quantity = $1, entry_commission = entry_commission - $2,
+            pnl = COALESCE(pnl, 0) + $3, gross_pnl = COALESCE(gross_pnl, 0) + $4
+         WHERE id = $5 AND status = 'OPEN'",
     )
+    .bind(remaining_quantity)
+    .bind(entry_commission_sold)
     .bind(pnl)
+    .bind(gross_pnl)
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Close a position the exchange no longer holds. No PnL is booked —
+/// how the holding left the account is unknown.
+pub async fn close_unsettled_position(pool: &PgPool, position_id: Uuid, reason: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE positions SET status = 'CLOSED', closed_at = $1, close_reason = $2
+         WHERE id = $3 AND status = 'OPEN'",
+    )
     .bind(Utc::now())
     .bind(reason)
     .bind(position_id)
@@ -98,7 +168,7 @@ pub async fn close_position(
     Ok(())
 }
 
-pub async fn update_position_price(pool: &PgPool, position_id: Uuid, price: f64) -> Result<()> {
+pub async fn update_position_price(pool: &PgPool, position_id: Uuid, price: Decimal) -> Result<()> {
     sqlx::query("UPDATE positions SET current_price = $1 WHERE id = $2")
         .bind(price)
         .bind(position_id)
@@ -107,9 +177,81 @@ pub async fn update_position_price(pool: &PgPool, position_id: Uuid, price: f64)
     Ok(())
 }
 
-pub async fn count_open_positions(pool: &PgPool) -> Result<i64> {
+/// Attach exchange-side OCO exit orders to a position
+pub async fn set_position_oco(
+    pool: &PgPool,
+    position_id: Uuid,
+    order_list_id: i64,
+    tp_order_id: i64,
+    sl_order_id: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE positions SET oco_order_list_id = $1, tp_order_id = $2, sl_order_id = $3 WHERE id = $4",
+    )
+    .bind(order_list_id)
+    .bind(tp_order_id)
+    .bind(sl_order_id)
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Make an open position's stop trail `trail_pct` % or `trail_distance` below its highs
+pub async fn set_position_trail(
+    pool: &PgPool,
+    position_id: Uuid,
+    trail_pct: Option<Decimal>,
+    trail_distance: Option<Decimal>,
+    high_water_mark: Decimal,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE positions SET trail_pct = $1, trail_distance = $2, high_water_mark = $3 WHERE id = $4",
+    )
+    .bind(trail_pct)
+    .bind(trail_distance)
+    .bind(high_water_mark)
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a new high for a trailing position, raising its stop-loss when given
+pub async fn update_trailing_stop(
+    pool: &PgPool,
+    position_id: Uuid,
+    high_water_mark: Decimal,
+    stop_loss: Option<Decimal>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE positions SET high_water_mark = GREATEST(high_water_mark, $1),
+            stop_loss = GREATEST(stop_loss, COALESCE($2, stop_loss))
+         WHERE id = $3 AND status = 'OPEN'",
+    )
+    .bind(high_water_mark)
+    .bind(stop_loss)
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Detach OCO orders (cancelled or no longer live) so the cycle evaluates stops again
+pub async fn clear_position_oco(pool: &PgPool, position_id: Uuid) -> Result<()> {
+    sqlx::query(
+        "UPDATE positions SET oco_order_list_id = NULL, tp_order_id = NULL, sl_order_id = NULL WHERE id = $1",
+    )
+    .bind(position_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn count_open_positions(pool: &PgPool, mode: TradingMode) -> Result<i64> {
     let row: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM positions WHERE status = 'OPEN'")
+        sqlx::query_as("SELECT COUNT(*) FROM positions WHERE mode = $1 AND status = 'OPEN'")
+            .bind(mode.as_str())
             .fetch_one(pool)
             .await?;
     Ok(row.0)
@@ -117,47 +259,58 @@ pub async fn count_open_positions(pool: &PgPool) -> Result<i64> {
 
 // ─── Trades ──────────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_trade(
     pool: &PgPool,
+    mode: TradingMode,
     position_id: Option<Uuid>,
     symbol: &str,
     side: &str,
-    quantity: f64,
-    price: f64,
-    usdc_amount: f64,
-    commission: f64,
+    quantity: Decimal,
+    price: Decimal,
+    quote_amount: Decimal,
+    commission: Decimal,
+    exchange_order_id: Option<i64>,
 ) -> Result<Uuid> {
-    let id = Uuid::new_v4();
-    sqlx::query(
-        "INSERT INTO trades (id, position_id, symbol, side, quantity, price, usdc_amount, commission, executed_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    // The same order may be recorded by the engine and the user data stream;
+    // whichever arrives second only fills in the position link.
+    let row: (Uuid,) = sqlx::query_as(
+        "INSERT INTO trades (id, mode, position_id, symbol, side, quantity, price, quote_amount, commission, exchange_order_id, executed_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+         ON CONFLICT (mode, symbol, exchange_order_id)
+         DO UPDATE SET position_id = COALESCE(EXCLUDED.position_id, trades.position_id)
+         RETURNING id",
     )
-    .bind(id)
+    .bind(Uuid::new_v4())
+    .bind(mode.as_str())
     .bind(position_id)
     .bind(symbol)
     .bind(side)
     .bind(quantity)
     .bind(price)
-    .bind(usdc_amount)
+    .bind(quote_amount)
     .bind(commission)
+    .bind(exchange_order_id)
     .bind(Utc::now())
-    .execute(pool)
+    .fetch_one(pool)
     .await?;
-    Ok(id)
+    Ok(row.0)
 }
 
-pub async fn get_recent_trades(pool: &PgPool, limit: i64) -> Result<Vec<Trade>> {
+pub async fn get_recent_trades(pool: &PgPool, mode: TradingMode, limit: i64) -> Result<Vec<Trade>> {
     let trades = sqlx::query_as::<_, Trade>(
-        "SELECT * FROM trades ORDER BY executed_at DESC LIMIT $1",
+        "SELECT * FROM trades WHERE mode = $1 ORDER BY executed_at DESC LIMIT $2",
     )
+    .bind(mode.as_str())
     .bind(limit)
     .fetch_all(pool)
     .await?;
     Ok(trades)
 }
 
-pub async fn count_trades(pool: &PgPool) -> Result<i64> {
-    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM trades")
+pub async fn count_trades(pool: &PgPool, mode: TradingMode) -> Result<i64> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM trades WHERE mode = $1")
+        .bind(mode.as_str())
         .fetch_one(pool)
         .await?;
     Ok(row.0)
@@ -165,9 +318,11 @@ pub async fn count_trades(pool: &PgPool) -> Result<i64> {
 
 // ─── Cycle Logs ──────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 pub async fn insert_cycle_log(
     pool: &PgPool,
-    balance_usdc: f64,
+    mode: TradingMode,
+    balance: Decimal,
     action: &str,
     symbol: Option<&str>,
     confidence: Option<i32>,
@@ -180,11 +335,12 @@ pub async fn insert_cycle_log(
 ) -> Result<Uuid> {
     let id = Uuid::new_v4();
     sqlx::query(
-        "INSERT INTO cycle_logs (id, balance_usdc, action, symbol, confidence, reasoning, raw_response, fear_greed, execution_ms, result, error, created_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        "INSERT INTO cycle_logs (id, mode, balance, action, symbol, confidence, reasoning, raw_response, fear_greed, execution_ms, result, error, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
     )
     .bind(id)
-    .bind(balance_usdc)
+    .bind(mode.as_str())
+    .bind(balance)
     .bind(action)
     .bind(symbol)
     .bind(confidence)
@@ -200,28 +356,57 @@ pub async fn insert_cycle_log(
     Ok(id)
 }
 
-pub async fn get_recent_cycles(pool: &PgPool, limit: i64) -> Result<Vec<CycleLog>> {
+/// Attach the answering provider, model, answer time and token usage
+/// (input, output) to a logged cycle
+pub async fn set_cycle_decision_source(
+    pool: &PgPool,
+    cycle_id: Uuid,
+    provider: &str,
+    model: Option<&str>,
+    latency_ms: i32,
+    tokens: (Option<i32>, Option<i32>),
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE cycle_logs SET decision_provider = $1, decision_model = $2, decision_latency_ms = $3,
+         decision_input_tokens = $4, decision_output_tokens = $5
+         WHERE id = $6",
+    )
+    .bind(provider)
+    .bind(model)
+    .bind(latency_ms)
+    .bind(tokens.0)
+    .bind(tokens.1)
+    .bind(cycle_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_recent_cycles(pool: &PgPool, mode: TradingMode, limit: i64) -> Result<Vec<CycleLog>> {
     let logs = sqlx::query_as::<_, CycleLog>(
-        "SELECT * FROM cycle_logs ORDER BY created_at DESC LIMIT $1",
+        "SELECT * FROM cycle_logs WHERE mode = $1 ORDER BY created_at DESC LIMIT $2",
     )
+    .bind(mode.as_str())
     .bind(limit)
     .fetch_all(pool)
     .await?;
     Ok(logs)
 }
 
-pub async fn count_cycles(pool: &PgPool) -> Result<i64> {
-    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cycle_logs")
+pub async fn count_cycles(pool: &PgPool, mode: TradingMode) -> Result<i64> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cycle_logs WHERE mode = $1")
+        .bind(mode.as_str())
         .fetch_one(pool)
         .await?;
     Ok(row.0)
 }
 
-pub async fn get_consecutive_losses(pool: &PgPool) -> Result<i64> {
+pub async fn get_consecutive_losses(pool: &PgPool, mode: TradingMode) -> Result<i64> {
     // Count consecutive cycles with negative PnL from the most recent
     let logs = sqlx::query_as::<_, CycleLog>(
-        "SELECT * FROM cycle_logs WHERE action IN ('BUY', 'SELL') ORDER BY created_at DESC LIMIT 20",
+        "SELECT * FROM cycle_logs WHERE mode = $1 AND action IN ('BUY', 'SELL') ORDER BY created_at DESC LIMIT 20",
     )
+    .bind(mode.as_str())
     .fetch_all(pool)
     .await?;
 
@@ -236,10 +421,11 @@ pub async fn get_consecutive_losses(pool: &PgPool) -> Result<i64> {
     Ok(streak)
 }
 
-pub async fn get_win_rate(pool: &PgPool) -> Result<f64> {
+pub async fn get_win_rate(pool: &PgPool, mode: TradingMode) -> Result<f64> {
     let total: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM cycle_logs WHERE action IN ('BUY', 'SELL') AND result IS NOT NULL",
+        "SELECT COUNT(*) FROM cycle_logs WHERE mode = $1 AND action IN ('BUY', 'SELL') AND result IS NOT NULL",
     )
+    .bind(mode.as_str())
     .fetch_one(pool)
     .await?;
 
@@ -248,8 +434,9 @@ pub async fn get_win_rate(pool: &PgPool) -> Result<f64> {
     }
 
     let wins: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM cycle_logs WHERE action IN ('BUY', 'SELL') AND result = 'WIN'",
+        "SELECT COUNT(*) FROM cycle_logs WHERE mode = $1 AND action IN ('BUY', 'SELL') AND result = 'WIN'",
     )
+    .bind(mode.as_str())
     .fetch_one(pool)
     .await?;
 
@@ -260,14 +447,19 @@ pub async fn get_win_rate(pool: &PgPool) -> Result<f64> {
 
 pub async fn insert_balance_snapshot(
     pool: &PgPool,
-    balance_usdc: f64,
+    mode: TradingMode,
+    balance: Decimal,
+    quote_asset: &str,
     open_positions: i32,
-    total_pnl: f64,
+    total_pnl: Decimal,
 ) -> Result<()> {
     sqlx::query(
-        "INSERT INTO balance_history (balance_usdc, open_positions, total_pnl, recorded_at) VALUES ($1, $2, $3, $4)",
+        "INSERT INTO balance_history (mode, balance, quote_asset, open_positions, total_pnl, recorded_at)
+         VALUES ($1, $2, $3, $4, $5, $6)",
     )
-    .bind(balance_usdc)
+    .bind(mode.as_str())
+    .bind(balance)
+    .bind(quote_asset)
     .bind(open_positions)
     .bind(total_pnl)
     .bind(Utc::now())
@@ -276,21 +468,387 @@ pub async fn insert_balance_snapshot(
     Ok(())
 }
 
-pub async fn get_balance_history(pool: &PgPool, limit: i64) -> Result<Vec<BalanceHistory>> {
+pub async fn get_balance_history(
+    pool: &PgPool,
+    mode: TradingMode,
+    limit: i64,
+) -> Result<Vec<BalanceHistory>> {
     let history = sqlx::query_as::<_, BalanceHistory>(
-        "SELECT * FROM balance_history ORDER BY recorded_at DESC LIMIT $1",
+        "SELECT * FROM balance_history WHERE mode = $1 ORDER BY recorded_at DESC LIMIT $2",
     )
+    .bind(mode.as_str())
     .bind(limit)
     .fetch_all(pool)
     .await?;
     Ok(history)
 }
 
-pub async fn get_total_pnl(pool: &PgPool) -> Result<f64> {
-    let row: (Option<f64>,) = sqlx::query_as(
-        "SELECT SUM(pnl) FROM positions WHERE status = 'CLOSED'",
+pub async fn get_total_pnl(pool: &PgPool, mode: TradingMode) -> Result<Decimal> {
+    let row: (Option<Decimal>,) = sqlx::query_as(
+        "SELECT SUM(pnl) FROM positions WHERE mode = $1 AND status = 'CLOSED'",
     )
+    .bind(mode.as_str())
     .fetch_one(pool)
     .await?;
-    Ok(row.0.unwrap_or(0.0))
+    Ok(row.0.unwrap_or_default())
+}
+
+// ─── Candles ─────────────────────────────────────────────
+
+/// Insert or refresh candles; the still-forming latest candle is overwritten each time
+pub async fn upsert_candles(
+    pool: &PgPool,
+    symbol: &str,
+    interval: &str,
+    klines: &[Kline],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for k in klines {
+        sqlx::query(
+            "INSERT INTO candles (symbol, interval, open_time, open, high, low, close, volume, quote_volume, trade_count, close_time)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             ON CONFLICT (symbol, interval, open_time) DO UPDATE SET
+                high = EXCLUDED.high, low = EXCLUDED.low, close = EXCLUDED.close,
+                volume = EXCLUDED.volume, quote_volume = EXCLUDED.quote_volume,
+                trade_count = EXCLUDED.trade_count, close_time = EXCLUDED.close_time",
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(k.open_time)
+        .bind(k.open)
+        .bind(k.high)
+        .bind(k.low)
+        .bind(k.close)
+        .bind(k.volume)
+        .bind(k.quote_volume)
+        .bind(k.trade_count)
+        .bind(k.close_time)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Most recent `limit` candles, returned oldest first
+pub async fn get_candles(
+    pool: &PgPool,
+    symbol: &str,
+    interval: &str,
+    limit: i64,
+) -> Result<Vec<Candle>> {
+    let candles = sqlx::query_as::<_, Candle>(
+        "SELECT * FROM (
+            SELECT * FROM candles WHERE symbol = $1 AND interval = $2
+            ORDER BY open_time DESC LIMIT $3
+         ) recent ORDER BY open_time ASC",
+    )
+    .bind(symbol)
+    .bind(interval)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(candles)
+}
+
+// ─── Paper Ledger ────────────────────────────────────────
+
+/// Seed an asset balance if the ledger has no row for it yet
+pub async fn seed_paper_balance(pool: &PgPool, asset: &str, amount: Decimal) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO paper_balances (asset, free, updated_at) VALUES ($1, $2, $3)
+         ON CONFLICT (asset) DO NOTHING",
+    )
+    .bind(asset)
+    .bind(amount)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_paper_balance(pool: &PgPool, asset: &str) -> Result<Decimal> {
+    let row: Option<(Decimal,)> = sqlx::query_as("SELECT free FROM paper_balances WHERE asset = $1")
+        .bind(asset)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|r| r.0).unwrap_or_default())
+}
+
+pub async fn get_paper_balances(pool: &PgPool) -> Result<Vec<(String, Decimal)>> {
+    let rows: Vec<(String, Decimal)> =
+        sqlx::query_as("SELECT asset, free FROM paper_balances WHERE free > 0 ORDER BY asset")
+            .fetch_all(pool)
+            .await?;
+    Ok(rows)
+}
+
+/// Atomically debit one asset, credit another and record the simulated order.
+/// `None`, without touching the ledger, if the debited balance is insufficient.
+#[allow(clippy::too_many_arguments)]
+pub async fn record_paper_fill(
+    pool: &PgPool,
+    symbol: &str,
+    side: &str,
+    debit_asset: &str,
+    debit_amount: Decimal,
+    credit_asset: &str,
+    credit_amount: Decimal,
+    executed_qty: Decimal,
+    quote_qty: Decimal,
+    price: Decimal,
+    commission: Decimal,
+    commission_asset: &str,
+    client_order_id: &str,
+) -> Result<Option<PaperOrder>> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    let debited = sqlx::query(
+        "UPDATE paper_balances SET free = free - $1, updated_at = $2 WHERE asset = $3 AND free >= $1",
+    )
+    .bind(debit_amount)
+    .bind(now)
+    .bind(debit_asset)
+    .execute(&mut *tx)
+    .await?;
+
+    if debited.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        "INSERT INTO paper_balances (asset, free, updated_at) VALUES ($1, $2, $3)
+         ON CONFLICT (asset) DO UPDATE SET free = paper_balances.free + EXCLUDED.free, updated_at = EXCLUDED.updated_at",
+    )
+    .bind(credit_asset)
+    .bind(credit_amount)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    let order = sqlx::query_as::<_, PaperOrder>(
+        "INSERT INTO paper_orders (symbol, side, status, executed_qty, cummulative_quote_qty, price, commission, commission_asset, created_at, client_order_id)
+         VALUES ($1, $2, 'FILLED', $3, $4, $5, $6, $7, $8, $9)
+         RETURNING *",
+    )
+    .bind(symbol)
+    .bind(side)
+    .bind(executed_qty)
+    .bind(quote_qty)
+    .bind(price)
+    .bind(commission)
+    .bind(commission_asset)
+    .bind(now)
+    .bind(client_order_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(order))
+}
+
+pub async fn get_paper_order(pool: &PgPool, order_id: i64) -> Result<Option<PaperOrder>> {
+    let order = sqlx::query_as::<_, PaperOrder>("SELECT * FROM paper_orders WHERE order_id = $1")
+        .bind(order_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(order)
+}
+
+pub async fn get_paper_order_by_client_id(
+    pool: &PgPool,
+    client_order_id: &str,
+) -> Result<Option<PaperOrder>> {
+    let order = sqlx::query_as::<_, PaperOrder>("SELECT * FROM paper_orders WHERE client_order_id = $1")
+        .bind(client_order_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(order)
+}
+
+// ─── Order Intents ───────────────────────────────────────
+
+/// Persist an order as PENDING before it is sent to the exchange
+pub async fn insert_order_intent(pool: &PgPool, intent: &OrderIntent) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO order_intents (client_order_id, mode, symbol, side, amount, position_id, stop_loss, take_profit, reason,
+                                    trail_pct, trail_atr_multiple, status, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'PENDING', $12, $12)",
+    )
+    .bind(&intent.client_order_id)
+    .bind(&intent.mode)
+    .bind(&intent.symbol)
+    .bind(&intent.side)
+    .bind(intent.amount)
+    .bind(intent.position_id)
+    .bind(intent.stop_loss)
+    .bind(intent.take_profit)
+    .bind(&intent.reason)
+    .bind(intent.trail_pct)
+    .bind(intent.trail_atr_multiple)
+    .bind(intent.created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Move an intent to a new status, keeping any exchange order ID already known
+pub async fn update_order_intent(
+    pool: &PgPool,
+    client_order_id: &str,
+    status: &str,
+    exchange_order_id: Option<i64>,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE order_intents
+         SET status = $1, exchange_order_id = COALESCE($2, exchange_order_id), error = $3, updated_at = $4
+         WHERE client_order_id = $5",
+    )
+    .bind(status)
+    .bind(exchange_order_id)
+    .bind(error)
+    .bind(Utc::now())
+    .bind(client_order_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Intents whose outcome hasn't been written to positions/trades yet, oldest first
+pub async fn get_unresolved_order_intents(pool: &PgPool, mode: TradingMode) -> Result<Vec<OrderIntent>> {
+    let intents = sqlx::query_as::<_, OrderIntent>(
+        "SELECT * FROM order_intents WHERE mode = $1 AND status IN ('PENDING', 'PLACED') ORDER BY created_at ASC",
+    )
+    .bind(mode.as_str())
+    .fetch_all(pool)
+    .await?;
+    Ok(intents)
+}
+
+/// A SELL for `position_id` whose outcome isn't settled yet, if any
+pub async fn get_unresolved_sell_intent(pool: &PgPool, position_id: Uuid) -> Result<Option<OrderIntent>> {
+    let intent = sqlx::query_as::<_, OrderIntent>(
+        "SELECT * FROM order_intents
+         WHERE position_id = $1 AND side = 'SELL' AND status IN ('PENDING', 'PLACED')
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(position_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(intent)
+}
+
+/// Position a recorded exchange order belongs to, if any
+pub async fn get_trade_position_id(
+    pool: &PgPool,
+    mode: TradingMode,
+    symbol: &str,
+    exchange_order_id: i64,
+) -> Result<Option<Uuid>> {
+    let position_id: Option<Option<Uuid>> = sqlx::query_scalar(
+        "SELECT position_id FROM trades WHERE mode = $1 AND symbol = $2 AND exchange_order_id = $3",
+    )
+    .bind(mode.as_str())
+    .bind(symbol)
+    .bind(exchange_order_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(position_id.flatten())
+}
+
+// ─── Discrepancies ───────────────────────────────────────
+
+/// Record a discrepancy, or refresh the open one for the same mode, kind and asset
+pub async fn flag_discrepancy(
+    pool: &PgPool,
+    mode: TradingMode,
+    kind: &str,
+    asset: &str,
+    symbol: &str,
+    expected_qty: Decimal,
+    actual_qty: Decimal,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO discrepancies (mode, kind, asset, symbol, expected_qty, actual_qty, action, detected_at, last_seen_at)
+         VALUES ($1, $2, $3, $4, $5, $6, 'FLAGGED', $7, $7)
+         ON CONFLICT (mode, kind, asset) WHERE resolved_at IS NULL
+         DO UPDATE SET expected_qty = EXCLUDED.expected_qty, actual_qty = EXCLUDED.actual_qty,
+                       last_seen_at = EXCLUDED.last_seen_at",
+    )
+    .bind(mode.as_str())
+    .bind(kind)
+    .bind(asset)
+    .bind(symbol)
+    .bind(expected_qty)
+    .bind(actual_qty)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Record a discrepancy that was fixed on sight by adopting or closing a position
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_fixed_discrepancy(
+    pool: &PgPool,
+    mode: TradingMode,
+    kind: &str,
+    asset: &str,
+    symbol: &str,
+    position_id: Uuid,
+    expected_qty: Decimal,
+    actual_qty: Decimal,
+    action: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO discrepancies (mode, kind, asset, symbol, position_id, expected_qty, actual_qty, action,
+                                    detected_at, last_seen_at, resolved_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $9)",
+    )
+    .bind(mode.as_str())
+    .bind(kind)
+    .bind(asset)
+    .bind(symbol)
+    .bind(position_id)
+    .bind(expected_qty)
+    .bind(actual_qty)
+    .bind(action)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Resolve open discrepancies whose `KIND:ASSET` key isn't in `still_open`
+pub async fn resolve_discrepancies(pool: &PgPool, mode: TradingMode, still_open: &[String]) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE discrepancies SET resolved_at = $1
+         WHERE mode = $2 AND resolved_at IS NULL AND NOT (kind || ':' || asset = ANY($3))",
+    )
+    .bind(Utc::now())
+    .bind(mode.as_str())
+    .bind(still_open)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn get_discrepancies(
+    pool: &PgPool,
+    mode: TradingMode,
+    open_only: bool,
+    limit: i64,
+) -> Result<Vec<Discrepancy>> {
+    let discrepancies = sqlx::query_as::<_, Discrepancy>(
+        "SELECT * FROM discrepancies WHERE mode = $1 AND (NOT $2 OR resolved_at IS NULL)
+         ORDER BY detected_at DESC LIMIT $3",
+    )
+    .bind(mode.as_str())
+    .bind(open_only)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(discrepancies)
 }
@@ -0,0 +1,42 @@
+//! Test Postgres for database-backed tests, taken from `TEST_DATABASE_URL`.
+//! Tests that need it skip (pass trivially) when the variable is unset.
+
+use sqlx::PgPool;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Database tests share one schema, so they run one at a time
+static LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A migrated, emptied test database, held exclusively until the guard drops.
+/// `None` when `TEST_DATABASE_URL` isn't set.
+pub async fn connect() -> Option<(MutexGuard<'static, ()>, PgPool)> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL not set — skipping database test");
+        return None;
+    };
+    let guard = LOCK.lock().await;
+
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("connect to TEST_DATABASE_URL");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrate test database");
+
+    sqlx::query(
+        "TRUNCATE discrepancies, order_intents, trades, positions, cycle_logs, balance_history,
+                  candles, paper_orders, paper_balances",
+    )
+    .execute(&pool)
+    .await
+    .expect("reset test database");
+    sqlx::query("UPDATE bot_status SET is_dead = FALSE, death_reason = NULL")
+        .execute(&pool)
+        .await
+        .expect("reset bot status");
+
+    Some((guard, pool))
+}
@@ -0,0 +1,366 @@
+//! Decision provider calling a chat model's HTTP API directly — the
+//! Anthropic Messages API or an OpenAI-style Chat Completions API — so
+//! cycles don't depend on a Discord bot being online.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use super::{DecisionProvider, DecisionResponse, PromptContext};
+use crate::config::LlmApi;
+use crate::openclaw::{build_correction, build_prompt};
+
+/// API version header the Messages API requires
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Name of the tool / response schema the decision comes back through
+const DECISION_SCHEMA_NAME: &str = "trading_decision";
+
+const SYSTEM_PROMPT: &str = "You are the decision engine of an autonomous crypto spot-trading bot \
+playing a survival game: if its balance reaches zero it shuts down forever. Each message describes \
+the account and the market. Answer with exactly one trading decision that follows every rule in the message.";
+
+#[derive(Clone)]
+pub struct LlmClient {
+    api: LlmApi,
+    base_url: String,
+    api_key: String,
+    model: String,
+    max_tokens: u32,
+    http: reqwest::Client,
+}
+
+/// The model's answer before it becomes a `DecisionResponse`
+struct Completion {
+    text: String,
+    model: Option<String>,
+    input_tokens: Option<i32>,
+    output_tokens: Option<i32>,
+}
+
+// ─── Messages API ────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    model: Option<String>,
+    content: Vec<ContentBlock>,
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text { text: String },
+    ToolUse { input: Value },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesUsage {
+    input_tokens: i32,
+    output_tokens: i32,
+}
+
+// ─── Chat Completions API ────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    model: Option<String>,
+    choices: Vec<ChatChoice>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: i32,
+    completion_tokens: i32,
+}
+
+impl LlmClient {
+    pub fn new(
+        api: LlmApi,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+        max_tokens: u32,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            api,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            max_tokens,
+            http: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Ask the model for a decision. `Ok(None)` when it doesn't answer
+    /// within the timeout.
+    async fn complete(&self, prompt: &str) -> Result<Option<Completion>> {
+        let request = match self.api {
+            LlmApi::Messages => self
+                .http
+                .post(format!("{}/messages", self.base_url))
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .json(&json!({
+                    "model": self.model,
+                    "max_tokens": self.max_tokens,
+                    "system": SYSTEM_PROMPT,
+                    "messages": [{ "role": "user", "content": prompt }],
+                    // Forcing the tool makes the answer arrive as schema-shaped JSON
+                    "tools": [{
+                        "name": DECISION_SCHEMA_NAME,
+                        "description": "Record this cycle's trading decision",
+                        "input_schema": decision_schema(),
+                    }],
+                    "tool_choice": { "type": "tool", "name": DECISION_SCHEMA_NAME },
+                })),
+            LlmApi::ChatCompletions => self
+                .http
+                .post(format!("{}/chat/completions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&json!({
+                    "model": self.model,
+                    "max_tokens": self.max_tokens,
+                    "messages": [
+                        { "role": "system", "content": SYSTEM_PROMPT },
+                        { "role": "user", "content": prompt },
+                    ],
+                    "response_format": {
+                        "type": "json_schema",
+                        "json_schema": { "name": DECISION_SCHEMA_NAME, "schema": decision_schema() },
+                    },
+                })),
+        };
+
+        // The client timeout covers both the request and reading the body
+        let (status, body) = match send(request).await {
+            Ok(reply) => reply,
+            Err(e) if e.is_timeout() => return Ok(None),
+            Err(e) => return Err(e).context("LLM request failed"),
+        };
+        if !status.is_success() {
+            anyhow::bail!("LLM request failed ({}): {}", status, body);
+        }
+
+        let completion = match self.api {
+            LlmApi::Messages => {
+                let resp: MessagesResponse =
+                    serde_json::from_str(&body).context("Failed to parse Messages API response")?;
+                let mut text = String::new();
+                for block in resp.content {
+                    match block {
+                        ContentBlock::ToolUse { input } => {
+                            text = input.to_string();
+                            break;
+                        }
+                        ContentBlock::Text { text: t } => text.push_str(&t),
+                        ContentBlock::Other => {}
+                    }
+                }
+                Completion {
+                    text,
+                    model: resp.model,
+                    input_tokens: resp.usage.as_ref().map(|u| u.input_tokens),
+                    output_tokens: resp.usage.as_ref().map(|u| u.output_tokens),
+                }
+            }
+            LlmApi::ChatCompletions => {
+                let resp: ChatResponse =
+                    serde_json::from_str(&body).context("Failed to parse Chat Completions response")?;
+                Completion {
+                    text: resp
+                        .choices
+                        .into_iter()
+                        .next()
+                        .and_then(|c| c.message.content)
+                        .unwrap_or_default(),
+                    model: resp.model,
+                    input_tokens: resp.usage.as_ref().map(|u| u.prompt_tokens),
+                    output_tokens: resp.usage.as_ref().map(|u| u.completion_tokens),
+                }
+            }
+        };
+
+        if completion.text.trim().is_empty() {
+            anyhow::bail!("LLM returned no decision");
+        }
+        Ok(Some(completion))
+    }
+}
+
+async fn send(request: reqwest::RequestBuilder) -> reqwest::Result<(reqwest::StatusCode, String)> {
+    let resp = request.send().await?;
+    let status = resp.status();
+    Ok((status, resp.text().await?))
+}
+
+/// JSON schema of `TradingDecision`, so the answer parses without cleanup
+fn decision_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "action": { "type": "string", "enum": ["BUY", "SELL", "HOLD"] },
+            "symbol": { "type": ["string", "null"] },
+            "confidence": { "type": "integer", "minimum": 0, "maximum": 100 },
+            "reasoning": { "type": "string" },
+            "stop_loss": { "type": ["number", "null"] },
+            "take_profit": { "type": ["number", "null"] },
+            "trailing_stop": {
+                "anyOf": [
+                    { "type": "object", "properties": { "percent": { "type": "number" } }, "required": ["percent"] },
+                    { "type": "object", "properties": { "atr": { "type": "number" } }, "required": ["atr"] },
+                    { "type": "null" },
+                ],
+            },
+        },
+        "required": ["action", "confidence", "reasoning"],
+    })
+}
+
+#[async_trait]
+impl DecisionProvider for LlmClient {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    async fn decide(&self, context: &PromptContext) -> Result<Option<DecisionResponse>> {
+        self.respond(&build_prompt(context)).await
+    }
+
+    async fn correct(
+        &self,
+        context: &PromptContext,
+        rejected: &str,
+        errors: &[String],
+    ) -> Result<Option<DecisionResponse>> {
+        // Each request is stateless, so the correction carries the original prompt
+        let prompt = format!("{}\n\n{}", build_prompt(context), build_correction(rejected, errors));
+        self.respond(&prompt).await
+    }
+}
+
+impl LlmClient {
+    async fn respond(&self, prompt: &str) -> Result<Option<DecisionResponse>> {
+        let started = Instant::now();
+        let Some(completion) = self.complete(prompt).await? else {
+            warn!(model = %self.model, "LLM response timeout");
+            return Ok(None);
+        };
+
+        let latency_ms = started.elapsed().as_millis() as i32;
+        info!(
+            model = ?completion.model,
+            input_tokens = ?completion.input_tokens,
+            output_tokens = ?completion.output_tokens,
+            latency_ms,
+            "LLM response received"
+        );
+        Ok(Some(DecisionResponse {
+            raw: completion.text,
+            provider: self.name().to_string(),
+            // The API reports the exact snapshot that answered
+            model: completion.model.or_else(|| Some(self.model.clone())),
+            latency_ms,
+            input_tokens: completion.input_tokens,
+            output_tokens: completion.output_tokens,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::mock::MockLlm;
+    use crate::db::models::TradingAction;
+    use crate::openclaw::parse_decision;
+    use rust_decimal_macros::dec;
+
+    fn context() -> PromptContext {
+        PromptContext {
+            balance: dec!(250),
+            quote_asset: "USDC".to_string(),
+            open_positions: Vec::new(),
+            tickers: Vec::new(),
+            fear_greed: 40,
+            consecutive_losses: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decide_over_both_apis() {
+        let stub = MockLlm::start().await;
+        stub.set_decision(json!({
+            "action": "BUY",
+            "symbol": "SOLUSDC",
+            "confidence": 80,
+            "reasoning": "Momentum",
+            "stop_loss": 95.0,
+            "take_profit": 110.0,
+        }));
+
+        for api in [LlmApi::Messages, LlmApi::ChatCompletions] {
+            let response = stub.client(api).decide(&context()).await.unwrap().unwrap();
+            let decision = parse_decision(&response.raw).unwrap();
+            assert_eq!(decision.action, TradingAction::Buy);
+            assert_eq!(decision.symbol.as_deref(), Some("SOLUSDC"));
+            assert_eq!(decision.stop_loss, Some(dec!(95)));
+            assert_eq!(response.provider, "llm");
+            assert_eq!(response.model.as_deref(), Some(crate::decision::mock::LLM_MODEL));
+            assert_eq!(response.input_tokens, Some(1200));
+            assert_eq!(response.output_tokens, Some(80));
+        }
+
+        let requests = stub.requests();
+        assert_eq!(requests[0].path, "/messages");
+        assert_eq!(requests[0].api_key.as_deref(), Some("stub-key"));
+        assert_eq!(requests[0].body["tool_choice"]["name"], DECISION_SCHEMA_NAME);
+        assert!(requests[0].body["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("Available USDC Balance:** 250.00"));
+        assert_eq!(requests[1].path, "/chat/completions");
+        assert_eq!(requests[1].api_key.as_deref(), Some("stub-key"));
+        assert_eq!(requests[1].body["messages"][0]["role"], "system");
+        assert_eq!(requests[1].body["response_format"]["type"], "json_schema");
+    }
+
+    #[tokio::test]
+    async fn test_decide_times_out_and_surfaces_api_errors() {
+        let stub = MockLlm::start().await;
+        stub.set_delay(Duration::from_millis(500));
+        let client = LlmClient::new(
+            LlmApi::Messages,
+            &stub.base_url,
+            "stub-key",
+            "stub-model",
+            1024,
+            Duration::from_millis(100),
+        );
+        assert!(client.decide(&context()).await.unwrap().is_none());
+
+        stub.set_delay(Duration::ZERO);
+        stub.set_status(529);
+        let err = stub.client(LlmApi::Messages).decide(&context()).await.unwrap_err();
+        assert!(err.to_string().contains("529"));
+    }
+}
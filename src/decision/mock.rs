@@ -0,0 +1,212 @@
+//! Test doubles for decision providers: a scripted provider that answers
+//! every cycle with a fixed reply (or times out) and records the contexts
+//! and corrections it was asked about, and an in-process stand-in for a chat model's HTTP
+//! API speaking both the Messages and Chat Completions formats.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{DecisionProvider, DecisionResponse, LlmClient, PromptContext};
+use crate::config::LlmApi;
+
+#[derive(Clone, Default)]
+pub struct MockProvider {
+    reply: Arc<Mutex<Option<String>>>,
+    /// Answer to a correction, when it differs from `reply`
+    correction: Arc<Mutex<Option<String>>>,
+    contexts: Arc<Mutex<Vec<PromptContext>>>,
+    corrections: Arc<Mutex<Vec<Vec<String>>>>,
+}
+
+impl MockProvider {
+    /// A provider answering `reply`, or timing out when `None`
+    pub fn new(reply: Option<&str>) -> Self {
+        let provider = Self::default();
+        *provider.reply.lock().unwrap() = reply.map(str::to_string);
+        provider
+    }
+
+    /// Answer corrections with `reply` instead of repeating the first answer
+    pub fn with_correction(self, reply: &str) -> Self {
+        *self.correction.lock().unwrap() = Some(reply.to_string());
+        self
+    }
+
+    /// Every context asked about so far
+    pub fn contexts(&self) -> Vec<PromptContext> {
+        self.contexts.lock().unwrap().clone()
+    }
+
+    /// The violations quoted in each correction so far
+    pub fn corrections(&self) -> Vec<Vec<String>> {
+        self.corrections.lock().unwrap().clone()
+    }
+
+    fn answer(&self, raw: Option<String>) -> Option<DecisionResponse> {
+        raw.map(|raw| DecisionResponse {
+            raw,
+            provider: self.name().to_string(),
+            model: Some("scripted".to_string()),
+            latency_ms: 0,
+            input_tokens: None,
+            output_tokens: None,
+        })
+    }
+}
+
+#[async_trait]
+impl DecisionProvider for MockProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn decide(&self, context: &PromptContext) -> Result<Option<DecisionResponse>> {
+        self.contexts.lock().unwrap().push(context.clone());
+        Ok(self.answer(self.reply.lock().unwrap().clone()))
+    }
+
+    async fn correct(
+        &self,
+        _context: &PromptContext,
+        _rejected: &str,
+        errors: &[String],
+    ) -> Result<Option<DecisionResponse>> {
+        self.corrections.lock().unwrap().push(errors.to_vec());
+        let correction = self.correction.lock().unwrap().clone();
+        Ok(self.answer(correction.or_else(|| self.reply.lock().unwrap().clone())))
+    }
+}
+
+// ─── Chat API stub ───────────────────────────────────────
+
+/// Model name the stub reports on every answer
+pub const LLM_MODEL: &str = "stub-model-2024";
+
+/// A request received by the stub
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub path: String,
+    /// `x-api-key` or the bearer token, whichever was sent
+    pub api_key: Option<String>,
+    pub body: Value,
+}
+
+struct LlmState {
+    decision: Value,
+    status: u16,
+    delay: Duration,
+    requests: Vec<LlmRequest>,
+}
+
+#[derive(Clone)]
+pub struct MockLlm {
+    pub base_url: String,
+    state: Arc<Mutex<LlmState>>,
+}
+
+impl MockLlm {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(LlmState {
+            decision: json!({ "action": "HOLD", "confidence": 50, "reasoning": "Stub" }),
+            status: 200,
+            delay: Duration::ZERO,
+            requests: Vec::new(),
+        }));
+        let app = Router::new()
+            .route("/messages", post(complete))
+            .route("/chat/completions", post(complete))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock LLM");
+        let addr = listener.local_addr().expect("mock LLM address");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self {
+            base_url: format!("http://{}", addr),
+            state,
+        }
+    }
+
+    /// A client calling this stub with the given wire format
+    pub fn client(&self, api: LlmApi) -> LlmClient {
+        LlmClient::new(api, &self.base_url, "stub-key", "stub-model", 1024, Duration::from_secs(5))
+    }
+
+    /// Decision the model answers with
+    pub fn set_decision(&self, decision: Value) {
+        self.state.lock().unwrap().decision = decision;
+    }
+
+    /// HTTP status of the next answers (an error body when not 200)
+    pub fn set_status(&self, status: u16) {
+        self.state.lock().unwrap().status = status;
+    }
+
+    /// How long the stub waits before answering
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().delay = delay;
+    }
+
+    /// Every request received so far
+    pub fn requests(&self) -> Vec<LlmRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+async fn complete(
+    State(state): State<Arc<Mutex<LlmState>>>,
+    uri: Uri,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    let api_key = headers
+        .get("x-api-key")
+        .or_else(|| headers.get("authorization"))
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim_start_matches("Bearer ").to_string());
+    let (decision, status, delay) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(LlmRequest {
+            path: uri.path().to_string(),
+            api_key,
+            body,
+        });
+        (state.decision.clone(), state.status, state.delay)
+    };
+    tokio::time::sleep(delay).await;
+
+    if status != 200 {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return (status, Json(json!({ "error": { "message": "Overloaded" } }))).into_response();
+    }
+
+    let reply = if uri.path() == "/messages" {
+        json!({
+            "model": LLM_MODEL,
+            "content": [
+                { "type": "text", "text": "Recording the decision." },
+                { "type": "tool_use", "id": "toolu_1", "name": "trading_decision", "input": decision },
+            ],
+            "usage": { "input_tokens": 1200, "output_tokens": 80 },
+        })
+    } else {
+        json!({
+            "model": LLM_MODEL,
+            "choices": [{ "message": { "role": "assistant", "content": decision.to_string() } }],
+            "usage": { "prompt_tokens": 1200, "completion_tokens": 80 },
+        })
+    };
+    Json(reply).into_response()
+}
@@ -0,0 +1,100 @@
+pub mod llm;
+#[cfg(test)]
+pub mod mock;
+pub mod validate;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+use crate::binance::Ticker24h;
+use crate::db::models::Position;
+use crate::market::top_by_volume;
+
+pub use llm::LlmClient;
+pub use validate::validate_decision;
+
+/// Shared handle to whichever decision provider is active.
+pub type SharedDecisionProvider = Arc<dyn DecisionProvider>;
+
+/// What the engine knows at decision time. Providers render it into a
+/// prompt in whatever form their model or relay expects.
+#[derive(Debug, Clone)]
+pub struct PromptContext {
+    pub balance: Decimal,
+    pub quote_asset: String,
+    pub open_positions: Vec<Position>,
+    /// Tradable tickers, any order
+    pub tickers: Vec<Ticker24h>,
+    pub fear_greed: i32,
+    pub consecutive_losses: i64,
+}
+
+impl PromptContext {
+    /// Most pairs a prompt lists
+    pub const LISTED_PAIRS: usize = 10;
+
+    /// The tickers the prompt offers, busiest first. A BUY may only name
+    /// one of these.
+    pub fn listed_tickers(&self) -> Vec<&Ticker24h> {
+        top_by_volume(&self.tickers, Self::LISTED_PAIRS)
+    }
+}
+
+/// A provider's answer: the raw text to parse, and where it came from
+#[derive(Debug, Clone)]
+pub struct DecisionResponse {
+    pub raw: String,
+    /// Provider name, as in `DecisionProvider::name`
+    pub provider: String,
+    /// Model that produced the answer, when the provider knows it
+    pub model: Option<String>,
+    /// Time from asking to answer
+    pub latency_ms: i32,
+    /// Prompt and completion tokens billed, when the provider reports them
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+}
+
+impl DecisionResponse {
+    /// Fold a follow-up answer into this one: the follow-up's text is what
+    /// gets parsed, while latency and tokens cover both exchanges
+    pub fn followed_by(self, next: DecisionResponse) -> DecisionResponse {
+        let add = |a: Option<i32>, b: Option<i32>| match (a, b) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        };
+        DecisionResponse {
+            raw: next.raw,
+            provider: next.provider,
+            model: next.model.or(self.model),
+            latency_ms: self.latency_ms + next.latency_ms,
+            input_tokens: add(self.input_tokens, next.input_tokens),
+            output_tokens: add(self.output_tokens, next.output_tokens),
+        }
+    }
+}
+
+/// Anything that can turn a cycle's market context into a trading decision.
+/// `DiscordClient` (the OpenClaw relay) and `LlmClient` (a chat API called
+/// directly) are the live implementations; test doubles implement this to
+/// drive `run_cycle`.
+#[async_trait]
+pub trait DecisionProvider: Send + Sync {
+    /// Short name recorded on each cycle log, e.g. "discord"
+    fn name(&self) -> &str;
+
+    /// Ask for a decision. `Ok(None)` means no answer arrived in time; the
+    /// engine HOLDs.
+    async fn decide(&self, context: &PromptContext) -> Result<Option<DecisionResponse>>;
+
+    /// Ask once more after `rejected` failed validation with `errors`.
+    /// Same contract as `decide`.
+    async fn correct(
+        &self,
+        context: &PromptContext,
+        rejected: &str,
+        errors: &[String],
+    ) -> Result<Option<DecisionResponse>>;
+}
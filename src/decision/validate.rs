@@ -0,0 +1,183 @@
+use rust_decimal::Decimal;
+
+use super::PromptContext;
+use crate::db::models::{TradingAction, TradingDecision, TrailingStop};
+use crate::openclaw::parse_decision;
+
+/// Parse a provider's answer and check it against the cycle it answers.
+/// Every violation is reported, worded so the list can be quoted back to
+/// the provider in a corrective follow-up.
+pub fn validate_decision(raw: &str, context: &PromptContext) -> Result<TradingDecision, Vec<String>> {
+    let decision = parse_decision(raw).map_err(|e| vec![e])?;
+    let mut errors = Vec::new();
+
+    if !(0..=100).contains(&decision.confidence) {
+        errors.push(format!("confidence {} is outside 0-100", decision.confidence));
+    }
+
+    match (&decision.action, decision.symbol.as_deref()) {
+        (TradingAction::Hold, _) => {}
+        (action, None) => errors.push(format!("symbol is required for {}", action)),
+        (TradingAction::Buy, Some(symbol)) => check_buy(&decision, symbol, context, &mut errors),
+        (TradingAction::Sell, Some(symbol)) => {
+            if !context.open_positions.iter().any(|p| p.symbol == symbol) {
+                errors.push(format!("there is no open position in {} to SELL", symbol));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(decision)
+    } else {
+        Err(errors)
+    }
+}
+
+fn check_buy(decision: &TradingDecision, symbol: &str, context: &PromptContext, errors: &mut Vec<String>) {
+    // Only the pairs the prompt showed, not every tradable one
+    let Some(ticker) = context.listed_tickers().into_iter().find(|t| t.symbol == symbol) else {
+        errors.push(format!("{} is not one of the listed pairs", symbol));
+        return;
+    };
+    // A BUY fills at about the last price, so that is the entry
+    let price = ticker.last_price;
+
+    match decision.stop_loss {
+        None => errors.push("stop_loss is required for BUY".to_string()),
+        Some(sl) if sl <= Decimal::ZERO => errors.push(format!("stop_loss {} must be positive", sl)),
+        Some(sl) if sl >= price => {
+            errors.push(format!("stop_loss {} must be below the current {} price {}", sl, symbol, price))
+        }
+        _ => {}
+    }
+    match decision.take_profit {
+        None => errors.push("take_profit is required for BUY".to_string()),
+        Some(tp) if tp <= price => {
+            errors.push(format!("take_profit {} must be above the entry price {}", tp, price))
+        }
+        _ => {}
+    }
+    match decision.trailing_stop {
+        Some(TrailingStop::Percent(pct)) | Some(TrailingStop::Atr(pct)) if pct <= Decimal::ZERO => {
+            errors.push(format!("trailing_stop {} must be positive", pct))
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::Ticker24h;
+    use crate::db::models::Position;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn ticker(symbol: &str, quote_volume: Decimal) -> Ticker24h {
+        Ticker24h {
+            symbol: symbol.to_string(),
+            price_change: dec!(2.5),
+            price_change_percent: dec!(2.5),
+            last_price: dec!(100.00),
+            high_price: dec!(102.00),
+            low_price: dec!(97.00),
+            volume: dec!(10000),
+            quote_volume,
+        }
+    }
+
+    fn context() -> PromptContext {
+        let position = Position {
+            id: Uuid::new_v4(),
+            mode: "paper".to_string(),
+            symbol: "ETHUSDC".to_string(),
+            side: "BUY".to_string(),
+            quantity: dec!(1),
+            entry_price: dec!(2000),
+            current_price: None,
+            stop_loss: None,
+            take_profit: None,
+            status: "OPEN".to_string(),
+            pnl: None,
+            opened_at: Utc::now(),
+            closed_at: None,
+            close_reason: None,
+            oco_order_list_id: None,
+            tp_order_id: None,
+            sl_order_id: None,
+            entry_commission: Decimal::ZERO,
+            gross_pnl: None,
+            trail_pct: None,
+            trail_distance: None,
+            high_water_mark: None,
+        };
+        PromptContext {
+            balance: dec!(1000),
+            quote_asset: "USDC".to_string(),
+            open_positions: vec![position],
+            tickers: vec![ticker("SOLUSDC", dec!(1000000))],
+            fear_greed: 50,
+            consecutive_losses: 0,
+        }
+    }
+
+    #[test]
+    fn test_valid_decisions_pass() {
+        let buy = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":80,"reasoning":"Trend",
+            "stop_loss":95.0,"take_profit":110.0}"#;
+        assert_eq!(validate_decision(buy, &context()).unwrap().stop_loss, Some(dec!(95)));
+        let sell = r#"{"action":"SELL","symbol":"ETHUSDC","confidence":70,"reasoning":"Take profit"}"#;
+        assert!(validate_decision(sell, &context()).is_ok());
+        let hold = r#"{"action":"HOLD","confidence":50,"reasoning":"Wait"}"#;
+        assert!(validate_decision(hold, &context()).is_ok());
+    }
+
+    #[test]
+    fn test_violations_are_all_reported() {
+        let buy = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":150,"reasoning":"Moon",
+            "stop_loss":105.0,"take_profit":90.0}"#;
+        let errors = validate_decision(buy, &context()).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "confidence 150 is outside 0-100",
+                "stop_loss 105 must be below the current SOLUSDC price 100.00",
+                "take_profit 90 must be above the entry price 100.00",
+            ]
+        );
+
+        let unlisted = r#"{"action":"BUY","symbol":"DOGEUSDC","confidence":80,"reasoning":"Meme"}"#;
+        assert_eq!(
+            validate_decision(unlisted, &context()).unwrap_err(),
+            vec!["DOGEUSDC is not one of the listed pairs"]
+        );
+
+        // Tradable but below the pairs the prompt listed
+        let mut crowded = context();
+        crowded.tickers.extend((0..10).map(|i| ticker(&format!("ALT{}USDC", i), dec!(5000000))));
+        assert_eq!(
+            validate_decision(r#"{"action":"BUY","symbol":"SOLUSDC","confidence":80,"reasoning":"Go",
+                "stop_loss":95.0,"take_profit":110.0}"#, &crowded)
+            .unwrap_err(),
+            vec!["SOLUSDC is not one of the listed pairs"]
+        );
+
+        let bare = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":80,"reasoning":"Go"}"#;
+        assert_eq!(
+            validate_decision(bare, &context()).unwrap_err(),
+            vec!["stop_loss is required for BUY", "take_profit is required for BUY"]
+        );
+
+        let no_symbol = r#"{"action":"BUY","confidence":90,"reasoning":"Go all in"}"#;
+        assert_eq!(validate_decision(no_symbol, &context()).unwrap_err(), vec!["symbol is required for BUY"]);
+
+        let sell = r#"{"action":"SELL","symbol":"SOLUSDC","confidence":70,"reasoning":"Exit"}"#;
+        assert_eq!(
+            validate_decision(sell, &context()).unwrap_err(),
+            vec!["there is no open position in SOLUSDC to SELL"]
+        );
+
+        assert_eq!(validate_decision("Buy Bitcoin!", &context()).unwrap_err().len(), 1);
+    }
+}
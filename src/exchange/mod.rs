@@ -0,0 +1,131 @@
+pub mod paper;
+
+pub use paper::PaperExchange;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::db::models::RateLimitUsage;
+use crate::binance::{Kline, OcoOrder, OrderFill, OrderResponse, SymbolFilters, Ticker24h};
+
+/// Shared handle to whichever exchange implementation is active.
+pub type SharedExchange = Arc<dyn Exchange>;
+
+/// Everything the trading engine needs from an exchange.
+/// `BinanceClient` is the live implementation; fakes implement this in tests.
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    /// The asset every traded pair is quoted in, and the account is funded with
+    fn quote_asset(&self) -> &str;
+
+    /// Get free quote-asset balance
+    async fn get_quote_balance(&self) -> Result<Decimal>;
+
+    /// Get all non-zero balances other than the quote asset as (asset, free + locked)
+    async fn get_all_balances(&self) -> Result<Vec<(String, Decimal)>>;
+
+    /// Get 24h ticker data for pairs quoted in the quote asset
+    async fn get_tickers(&self) -> Result<Vec<Ticker24h>>;
+
+    /// Get 24h ticker for a specific symbol
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker24h>;
+
+    /// Execute a market buy order (denominated in the quote asset)
+    async fn market_buy(
+        &self,
+        symbol: &str,
+        quote_amount: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse>;
+
+    /// Execute a market sell order (denominated in coin quantity)
+    async fn market_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse>;
+
+    /// Look up an existing order by its exchange order ID
+    async fn get_order(&self, symbol: &str, order_id: u64) -> Result<OrderResponse>;
+
+    /// Look up an order by the client order ID it was submitted with.
+    /// `None` means the exchange has no such order.
+    async fn query_order(&self, symbol: &str, client_order_id: &str)
+        -> Result<Option<OrderResponse>>;
+
+    /// Individual fills of an order — order lookups only report totals
+    async fn get_order_fills(&self, symbol: &str, order_id: u64) -> Result<Vec<OrderFill>>;
+
+    /// Get up to `limit` most recent OHLCV candles for a symbol and interval
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>>;
+
+    /// Get LOT_SIZE / PRICE_FILTER / NOTIONAL rules for a symbol
+    async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters>;
+
+    /// Place exchange-side take-profit and stop-loss orders as one OCO list
+    async fn place_oco_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        take_profit: Decimal,
+        stop_price: Decimal,
+        stop_limit_price: Decimal,
+    ) -> Result<OcoOrder>;
+
+    /// Cancel an OCO list so the position can be sold another way
+    async fn cancel_oco(&self, symbol: &str, order_list_id: i64) -> Result<()>;
+
+    /// Measured exchange-minus-local clock offset in ms, if this exchange signs requests
+    fn clock_drift_ms(&self) -> Option<i64> {
+        None
+    }
+
+    /// API weight and order-rate consumption, if this exchange is rate limited
+    fn rate_limit_usage(&self) -> Option<RateLimitUsage> {
+        None
+    }
+}
+
+/// Quote-asset price of each asset, for valuing commission paid in e.g. BNB.
+/// Assets that can't be priced are left out.
+pub async fn quote_prices(exchange: &dyn Exchange, assets: &[String]) -> HashMap<String, Decimal> {
+    let mut prices = HashMap::new();
+    for asset in assets {
+        let symbol = format!("{}{}", asset, exchange.quote_asset());
+        let price = match exchange.get_ticker(&symbol).await {
+            Ok(ticker) => Some(ticker.last_price),
+            Err(e) => {
+                warn!(asset, error = %e, "Failed to price commission asset");
+                None
+            }
+        };
+        if let Some(price) = price.filter(|p| *p > Decimal::ZERO) {
+            prices.insert(asset.clone(), price);
+        }
+    }
+    prices
+}
+
+/// Fresh `newClientOrderId` for an order intent. Binance allows up to 36
+/// characters of `[A-Za-z0-9_-]`; this is "sb-" plus a 32-char UUID.
+pub fn new_client_order_id() -> String {
+    format!("sb-{}", uuid::Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_order_id_fits_binance_format() {
+        let id = new_client_order_id();
+        assert_eq!(id.len(), 35);
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        assert_ne!(id, new_client_order_id());
+    }
+}
@@ -0,0 +1,345 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::binance::{BinanceClient, BinanceError, Kline, OcoOrder, OrderFill, OrderResponse, SymbolFilters, Ticker24h};
+use crate::db::models::{PaperOrder, RateLimitUsage};
+use crate::db::queries;
+use crate::exchange::Exchange;
+
+/// Paper-trading exchange: real Binance market data, simulated fills.
+/// Balances and orders live in the `paper_balances` / `paper_orders` tables,
+/// so a paper run survives restarts just like a live account would.
+#[derive(Clone)]
+pub struct PaperExchange {
+    pool: PgPool,
+    market: BinanceClient,
+    fee_rate: Decimal,
+    slippage_bps: Decimal,
+}
+
+/// Decimals Binance reports base and quote amounts with
+const AMOUNT_DP: u32 = 8;
+
+/// A simulated market fill, before it is written to the ledger
+#[derive(Debug, Clone, PartialEq)]
+struct SimulatedFill {
+    price: Decimal,
+    quantity: Decimal,
+    quote_qty: Decimal,
+    commission: Decimal,
+}
+
+impl PaperExchange {
+    pub fn new(pool: PgPool, market: BinanceClient, fee_rate: Decimal, slippage_bps: Decimal) -> Self {
+        Self {
+            pool,
+            market,
+            fee_rate,
+            slippage_bps,
+        }
+    }
+
+    /// Fund the paper account on first run. Existing ledgers are left untouched.
+    pub async fn seed(&self, starting_balance: Decimal) -> Result<()> {
+        let quote = self.market.quote_asset();
+        queries::seed_paper_balance(&self.pool, quote, starting_balance).await?;
+        info!(%starting_balance, asset = quote, "Paper ledger ready");
+        Ok(())
+    }
+
+    /// Last traded price for a symbol, from live market data
+    async fn last_price(&self, symbol: &str) -> Result<Decimal> {
+        let ticker = self.market.get_ticker(symbol).await?;
+        let price = ticker.last_price;
+        if price <= Decimal::ZERO {
+            anyhow::bail!("No usable price for {}", symbol);
+        }
+        Ok(price)
+    }
+}
+
+/// Fill a quote-denominated buy. Slippage worsens the price; the fee is
+/// taken from the received base asset, as Binance does by default.
+fn simulate_buy(
+    last_price: Decimal,
+    quote_amount: Decimal,
+    fee_rate: Decimal,
+    slippage_bps: Decimal,
+) -> SimulatedFill {
+    let price = last_price * (Decimal::ONE + slippage_bps / Decimal::from(10_000));
+    let quantity = truncate(quote_amount / price);
+    SimulatedFill {
+        price,
+        quantity,
+        quote_qty: quote_amount,
+        commission: truncate(quantity * fee_rate),
+    }
+}
+
+/// Fill a base-denominated sell. The fee is taken from the quote proceeds.
+fn simulate_sell(
+    last_price: Decimal,
+    quantity: Decimal,
+    fee_rate: Decimal,
+    slippage_bps: Decimal,
+) -> SimulatedFill {
+    let price = last_price * (Decimal::ONE - slippage_bps / Decimal::from(10_000));
+    let quote_qty = truncate(quantity * price);
+    SimulatedFill {
+        price,
+        quantity,
+        quote_qty,
+        commission: truncate(quote_qty * fee_rate),
+    }
+}
+
+/// Cut an amount to the 8 decimals Binance reports, never rounding up
+fn truncate(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(AMOUNT_DP, RoundingStrategy::ToZero)
+}
+
+fn base_asset<'a>(symbol: &'a str, quote_asset: &str) -> Result<&'a str> {
+    symbol
+        .strip_suffix(quote_asset)
+        .filter(|base| !base.is_empty())
+        .with_context(|| format!("Paper exchange only trades {} pairs, got {}", quote_asset, symbol))
+}
+
+impl From<PaperOrder> for OrderResponse {
+    fn from(order: PaperOrder) -> Self {
+        OrderResponse {
+            symbol: order.symbol,
+            order_id: order.order_id as u64,
+            status: order.status,
+            side: order.side,
+            order_type: "MARKET".to_string(),
+            executed_qty: order.executed_qty,
+            cummulative_quote_qty: order.cummulative_quote_qty,
+            fills: vec![OrderFill {
+                price: order.price,
+                qty: order.executed_qty,
+                commission: order.commission,
+                commission_asset: order.commission_asset,
+            }],
+        }
+    }
+}
+
+#[async_trait]
+impl Exchange for PaperExchange {
+    fn quote_asset(&self) -> &str {
+        self.market.quote_asset()
+    }
+
+    async fn get_quote_balance(&self) -> Result<Decimal> {
+        let balance = queries::get_paper_balance(&self.pool, self.quote_asset()).await?;
+        info!(%balance, asset = self.quote_asset(), "Paper quote balance fetched");
+        Ok(balance)
+    }
+
+    async fn get_all_balances(&self) -> Result<Vec<(String, Decimal)>> {
+        let balances = queries::get_paper_balances(&self.pool).await?;
+        Ok(balances
+            .into_iter()
+            .filter(|(asset, _)| asset != self.quote_asset())
+            .collect())
+    }
+
+    async fn get_tickers(&self) -> Result<Vec<Ticker24h>> {
+        self.market.get_tickers().await
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker24h> {
+        self.market.get_ticker(symbol).await
+    }
+
+    async fn market_buy(
+        &self,
+        symbol: &str,
+        quote_amount: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        // A resubmitted client order ID returns the original fill, like a lookup
+        if let Some(existing) = queries::get_paper_order_by_client_id(&self.pool, client_order_id).await? {
+            return Ok(existing.into());
+        }
+
+        let quote = self.quote_asset();
+        let base = base_asset(symbol, quote)?;
+        let filters = self.market.symbol_filters(symbol).await?;
+        let quote_amount = filters.quantize_quote(quote_amount);
+        filters.check_notional(quote_amount)?;
+
+        let last_price = self.last_price(symbol).await?;
+        let fill = simulate_buy(last_price, quote_amount, self.fee_rate, self.slippage_bps);
+
+        let order = queries::record_paper_fill(
+            &self.pool,
+            symbol,
+            "BUY",
+            quote,
+            fill.quote_qty,
+            base,
+            fill.quantity - fill.commission,
+            fill.quantity,
+            fill.quote_qty,
+            fill.price,
+            fill.commission,
+            base,
+            client_order_id,
+        )
+        .await?
+        .ok_or_else(|| insufficient_balance(quote))?;
+
+        info!(
+            symbol,
+            order_id = order.order_id,
+            qty = %fill.quantity,
+            price = %fill.price,
+            "📝 Paper BUY filled"
+        );
+        Ok(order.into())
+    }
+
+    async fn market_sell(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        client_order_id: &str,
+    ) -> Result<OrderResponse> {
+        if let Some(existing) = queries::get_paper_order_by_client_id(&self.pool, client_order_id).await? {
+            return Ok(existing.into());
+        }
+
+        let quote = self.quote_asset();
+        let base = base_asset(symbol, quote)?;
+        let filters = self.market.symbol_filters(symbol).await?;
+        let quantity = filters.quantize_qty(quantity);
+        filters.check_qty(quantity)?;
+
+        let last_price = self.last_price(symbol).await?;
+        filters.check_notional(quantity * last_price)?;
+        let fill = simulate_sell(last_price, quantity, self.fee_rate, self.slippage_bps);
+
+        let order = queries::record_paper_fill(
+            &self.pool,
+            symbol,
+            "SELL",
+            base,
+            fill.quantity,
+            quote,
+            fill.quote_qty - fill.commission,
+            fill.quantity,
+            fill.quote_qty,
+            fill.price,
+            fill.commission,
+            quote,
+            client_order_id,
+        )
+        .await?
+        .ok_or_else(|| insufficient_balance(base))?;
+
+        info!(
+            symbol,
+            order_id = order.order_id,
+            qty = %fill.quantity,
+            price = %fill.price,
+            "📝 Paper SELL filled"
+        );
+        Ok(order.into())
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: u64) -> Result<OrderResponse> {
+        let order = queries::get_paper_order(&self.pool, order_id as i64)
+            .await?
+            .filter(|o| o.symbol == symbol)
+            .with_context(|| format!("Paper order {} not found for {}", order_id, symbol))?;
+        Ok(order.into())
+    }
+
+    async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<Option<OrderResponse>> {
+        let order = queries::get_paper_order_by_client_id(&self.pool, client_order_id)
+            .await?
+            .filter(|o| o.symbol == symbol);
+        Ok(order.map(Into::into))
+    }
+
+    async fn get_order_fills(&self, symbol: &str, order_id: u64) -> Result<Vec<OrderFill>> {
+        Ok(self.get_order(symbol, order_id).await?.fills)
+    }
+
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        self.market.get_klines(symbol, interval, limit).await
+    }
+
+    async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        self.market.symbol_filters(symbol).await
+    }
+
+    /// Resting orders aren't simulated; the engine falls back to
+    /// evaluating stops itself each cycle.
+    async fn place_oco_sell(
+        &self,
+        symbol: &str,
+        _quantity: Decimal,
+        _take_profit: Decimal,
+        _stop_price: Decimal,
+        _stop_limit_price: Decimal,
+    ) -> Result<OcoOrder> {
+        anyhow::bail!("Paper exchange does not support OCO orders ({})", symbol)
+    }
+
+    async fn cancel_oco(&self, symbol: &str, order_list_id: i64) -> Result<()> {
+        anyhow::bail!("Paper exchange has no OCO list {} for {}", order_list_id, symbol)
+    }
+
+    /// Market data still comes from Binance and counts against its weight limit
+    fn rate_limit_usage(&self) -> Option<RateLimitUsage> {
+        Some(self.market.rate_limit_usage())
+    }
+}
+
+/// The error Binance gives for an order the account can't cover (-2010)
+fn insufficient_balance(asset: &str) -> BinanceError {
+    BinanceError::InsufficientBalance {
+        msg: format!("Paper account has insufficient {} balance", asset),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_buy_applies_slippage_and_base_fee() {
+        let fill = simulate_buy(dec!(100), dec!(10), dec!(0.001), dec!(10));
+        assert_eq!(fill.price, dec!(100.1));
+        assert_eq!(fill.quantity, dec!(0.09990009));
+        assert_eq!(fill.commission, dec!(0.00009990));
+        assert_eq!(fill.quote_qty, dec!(10));
+    }
+
+    #[test]
+    fn test_sell_applies_slippage_and_quote_fee() {
+        let fill = simulate_sell(dec!(100), dec!(2), dec!(0.001), dec!(10));
+        assert_eq!(fill.price, dec!(99.9));
+        assert_eq!(fill.quote_qty, dec!(199.8));
+        assert_eq!(fill.commission, dec!(0.1998));
+    }
+
+    #[test]
+    fn test_base_asset_requires_quote_pair() {
+        assert_eq!(base_asset("BTCUSDC", "USDC").unwrap(), "BTC");
+        assert_eq!(base_asset("BTCFDUSD", "FDUSD").unwrap(), "BTC");
+        assert!(base_asset("BTCUSDT", "USDC").is_err());
+        assert!(base_asset("USDC", "USDC").is_err());
+    }
+}
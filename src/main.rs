@@ -2,6 +2,8 @@ mod api;
 mod binance;
 mod config;
 mod db;
+mod decision;
+mod exchange;
 mod market;
 mod openclaw;
 mod scheduler;
@@ -9,7 +11,7 @@ mod trading;
 
 use std::sync::Arc;
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{info, warn};
 
 use axum::{
     routing::{get, post},
@@ -18,18 +20,21 @@ use axum::{
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
-use crate::binance::BinanceClient;
-use crate::config::Config;
-use crate::db::models::CycleUpdate;
-use crate::openclaw::DiscordClient;
+use crate::binance::{BinanceClient, UserDataStream};
+use crate::config::{Config, DecisionProviderKind, TradingMode};
+use crate::db::models::DashboardEvent;
+use crate::decision::{LlmClient, SharedDecisionProvider};
+use crate::exchange::{PaperExchange, SharedExchange};
+use crate::openclaw::{DiscordClient, DiscordGateway};
+use crate::trading::{PriceMonitor, TradingEngine};
 
 /// Shared application state passed to all handlers and the scheduler
 pub struct AppState {
     pub pool: sqlx::PgPool,
     pub config: Arc<Config>,
-    pub binance: BinanceClient,
-    pub discord: DiscordClient,
-    pub broadcast_tx: broadcast::Sender<CycleUpdate>,
+    pub engine: Arc<TradingEngine>,
+    pub exchange: SharedExchange,
+    pub broadcast_tx: broadcast::Sender<DashboardEvent>,
 }
 
 #[tokio::main]
@@ -69,23 +74,97 @@ async fn main() -> anyhow::Result<()> {
         &config.binance_base_url,
         &config.binance_api_key,
         &config.binance_secret_key,
+        config.binance_recv_window_ms,
+        config.binance_weight_budget,
+        &config.quote_asset,
     );
 
-    let discord = DiscordClient::new(
-        &config.discord_bot_token,
-        &config.discord_channel_id,
-        &config.openclaw_user_id,
-    );
+    let exchange: SharedExchange = match config.mode {
+        TradingMode::Live | TradingMode::Testnet => {
+            if config.mode == TradingMode::Live {
+                warn!("💸 LIVE TRADING — real orders with real funds");
+            } else {
+                info!("🧪 TESTNET TRADING — real orders on the Binance Spot Testnet, test funds only");
+            }
+
+            // Signed requests use Binance's clock, not ours
+            if let Err(e) = binance.sync_time().await {
+                warn!(error = %e, "Initial server time sync failed — using local clock");
+            }
+            tokio::spawn(binance.clone().run_time_sync(std::time::Duration::from_secs(
+                config.binance_time_sync_secs,
+            )));
+
+            // Fills and balance changes pushed by Binance instead of polled
+            let user_stream =
+                UserDataStream::new(binance.clone(), pool.clone(), config.mode, &config.binance_stream_url);
+            tokio::spawn(user_stream.run());
+            Arc::new(binance)
+        }
+        TradingMode::Paper => {
+            info!("📝 PAPER TRADING — orders are simulated, no real funds at risk");
+            let paper = PaperExchange::new(
+                pool.clone(),
+                binance,
+                config.paper_fee_rate,
+                config.paper_slippage_bps,
+            );
+            paper.seed(config.paper_starting_balance).await?;
+            Arc::new(paper)
+        }
+    };
+
+    let decisions: SharedDecisionProvider = match config.decision_provider {
+        DecisionProviderKind::Discord => {
+            let discord = DiscordClient::new(
+                &config.discord_bot_token,
+                &config.discord_channel_id,
+                &config.openclaw_user_id,
+            );
+            if config.discord_gateway {
+                // Replies pushed over the websocket; REST polling while it's down
+                let gateway = DiscordGateway::new(
+                    &config.discord_gateway_url,
+                    &config.discord_bot_token,
+                    &config.discord_channel_id,
+                    &config.openclaw_user_id,
+                );
+                let replies = gateway.replies();
+                tokio::spawn(gateway.run());
+                Arc::new(discord.with_gateway(replies))
+            } else {
+                Arc::new(discord)
+            }
+        }
+        DecisionProviderKind::Llm => Arc::new(LlmClient::new(
+            config.llm_api,
+            &config.llm_base_url,
+            &config.llm_api_key,
+            &config.llm_model,
+            config.llm_max_tokens,
+            std::time::Duration::from_secs(config.llm_timeout_secs),
+        )),
+    };
+    info!(provider = decisions.name(), "🧠 Decision provider ready");
 
     // Broadcast channel for WebSocket updates
-    let (broadcast_tx, _) = broadcast::channel::<CycleUpdate>(100);
+    let (broadcast_tx, _) = broadcast::channel::<DashboardEvent>(100);
+
+    // One engine shared by the scheduler, the price monitor and the API
+    let engine = Arc::new(TradingEngine::new(
+        config.clone(),
+        pool.clone(),
+        exchange.clone(),
+        decisions,
+        broadcast_tx.clone(),
+    ));
 
     // Shared state
     let state = Arc::new(AppState {
         pool: pool.clone(),
         config: config.clone(),
-        binance: binance.clone(),
-        discord: discord.clone(),
+        engine: engine.clone(),
+        exchange,
         broadcast_tx: broadcast_tx.clone(),
     });
 
@@ -103,6 +182,8 @@ async fn main() -> anyhow::Result<()> {
         .route("/balance", get(api::routes::balance_history))
         .route("/cycles", get(api::routes::cycles))
         .route("/positions", get(api::routes::positions))
+        .route("/candles", get(api::routes::candles))
+        .route("/discrepancies", get(api::routes::discrepancies))
         .route("/trigger", post(api::routes::trigger))
         .route("/kill", post(api::routes::kill))
         .route("/ws", get(api::websocket::ws_handler))
@@ -111,22 +192,23 @@ async fn main() -> anyhow::Result<()> {
         .with_state(state);
 
     // Start scheduler in background
-    let scheduler_pool = pool.clone();
-    let scheduler_config = config.clone();
-    let scheduler_binance = binance.clone();
-    let scheduler_discord = discord.clone();
-    let scheduler_broadcast = broadcast_tx.clone();
-
-    tokio::spawn(async move {
-        scheduler::start_scheduler(
-            scheduler_config,
-            scheduler_pool,
-            scheduler_binance,
-            scheduler_discord,
-            scheduler_broadcast,
-        )
-        .await;
-    });
+    tokio::spawn(scheduler::start_scheduler(engine.clone()));
+
+    // Check positions against exchange holdings at startup and periodically
+    tokio::spawn(scheduler::start_reconciler(
+        engine.clone(),
+        std::time::Duration::from_secs(config.reconcile_interval_secs),
+    ));
+
+    // Start real-time price monitor for open positions
+    let monitor = PriceMonitor::new(
+        engine.clone(),
+        pool.clone(),
+        config.mode,
+        &config.binance_stream_url,
+        broadcast_tx.clone(),
+    );
+    tokio::spawn(monitor.run());
 
     // Start HTTP server
     let addr = format!("{}:{}", config.api_host, config.api_port);
@@ -0,0 +1,84 @@
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::binance::Ticker24h;
+use crate::db::queries;
+use crate::exchange::Exchange;
+
+/// The `n` highest quote-volume tickers, busiest first
+pub fn top_by_volume(tickers: &[Ticker24h], n: usize) -> Vec<&Ticker24h> {
+    let mut sorted: Vec<&Ticker24h> = tickers.iter().collect();
+    sorted.sort_by_key(|t| std::cmp::Reverse(t.quote_volume));
+    sorted.truncate(n);
+    sorted
+}
+
+/// Symbols of the `n` highest quote-volume tickers, busiest first
+pub fn top_symbols_by_volume(tickers: &[Ticker24h], n: usize) -> Vec<String> {
+    top_by_volume(tickers, n).into_iter().map(|t| t.symbol.clone()).collect()
+}
+
+/// Fetch the latest klines for every symbol/interval pair and upsert them
+/// into `candles`. Non-critical: failures are logged and skipped.
+pub async fn ingest_candles(
+    pool: &PgPool,
+    exchange: &dyn Exchange,
+    symbols: &[String],
+    intervals: &[String],
+    limit: u32,
+) {
+    let mut stored = 0;
+
+    for symbol in symbols {
+        for interval in intervals {
+            let klines = match exchange.get_klines(symbol, interval, limit).await {
+                Ok(k) => k,
+                Err(e) => {
+                    warn!(error = %e, %symbol, %interval, "Failed to fetch klines");
+                    continue;
+                }
+            };
+
+            match queries::upsert_candles(pool, symbol, interval, &klines).await {
+                Ok(()) => stored += klines.len(),
+                Err(e) => warn!(error = %e, %symbol, %interval, "Failed to store candles"),
+            }
+        }
+    }
+
+    info!(symbols = symbols.len(), candles = stored, "🕯️ Candles ingested");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn ticker(symbol: &str, quote_volume: Decimal) -> Ticker24h {
+        Ticker24h {
+            symbol: symbol.to_string(),
+            price_change: Decimal::ZERO,
+            price_change_percent: Decimal::ZERO,
+            last_price: Decimal::ONE,
+            high_price: Decimal::ONE,
+            low_price: Decimal::ONE,
+            volume: Decimal::ZERO,
+            quote_volume,
+        }
+    }
+
+    #[test]
+    fn test_top_symbols_by_volume() {
+        let tickers = vec![
+            ticker("SOLUSDC", dec!(500)),
+            ticker("BTCUSDC", dec!(9000)),
+            ticker("DOGEUSDC", Decimal::ZERO),
+            ticker("ETHUSDC", dec!(3000)),
+        ];
+        assert_eq!(
+            top_symbols_by_volume(&tickers, 2),
+            vec!["BTCUSDC".to_string(), "ETHUSDC".to_string()]
+        );
+    }
+}
@@ -10,6 +10,8 @@ struct FearGreedResponse {
 #[derive(Debug, Deserialize)]
 struct FearGreedData {
     value: String,
+    // Part of the API payload; only the numeric value is used
+    #[allow(dead_code)]
     value_classification: String,
 }
 
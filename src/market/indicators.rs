@@ -0,0 +1,57 @@
+use rust_decimal::Decimal;
+
+use crate::binance::Kline;
+
+/// Average true range over the last `period` klines (oldest first). Each
+/// true range is the widest of high−low and the gaps from the previous close,
+/// so `period + 1` klines are needed.
+pub fn average_true_range(klines: &[Kline], period: usize) -> Option<Decimal> {
+    if period == 0 || klines.len() < period + 1 {
+        return None;
+    }
+    let recent = &klines[klines.len() - period - 1..];
+    let total: Decimal = recent
+        .windows(2)
+        .map(|pair| {
+            let (prev, k) = (&pair[0], &pair[1]);
+            (k.high - k.low)
+                .max((k.high - prev.close).abs())
+                .max((k.low - prev.close).abs())
+        })
+        .sum();
+    Some(total / Decimal::from(period))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn kline(high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline {
+            open_time: Utc::now(),
+            open: close,
+            high,
+            low,
+            close,
+            volume: Decimal::ZERO,
+            quote_volume: Decimal::ZERO,
+            trade_count: 0,
+            close_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_average_true_range() {
+        let klines = [
+            kline(dec!(101), dec!(99), dec!(100)),
+            kline(dec!(102), dec!(100), dec!(101)), // range 2
+            kline(dec!(101), dec!(97), dec!(98)),   // range 4
+            kline(dec!(105.01), dec!(103), dec!(104)), // gap up: 105.01 − 98 = 7.01
+        ];
+        assert_eq!(average_true_range(&klines, 3), Some(dec!(13.01) / dec!(3)));
+        assert_eq!(average_true_range(&klines, 2), Some(dec!(5.505)));
+        assert_eq!(average_true_range(&klines, 4), None);
+    }
+}
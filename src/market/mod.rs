@@ -1,3 +1,7 @@
+pub mod candles;
 pub mod fear_greed;
+pub mod indicators;
 
+pub use candles::{ingest_candles, top_by_volume, top_symbols_by_volume};
 pub use fear_greed::fetch_fear_greed_index;
+pub use indicators::average_true_range;
@@ -1,43 +1,101 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::Deserialize;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+use super::gateway::ReplyRouter;
+use super::{build_correction, build_prompt, is_complete_decision};
+use crate::decision::{DecisionProvider, DecisionResponse, PromptContext};
+
+const DISCORD_API_BASE: &str = "https://discord.com/api/v10";
+/// Discord rejects message content longer than this
+const MESSAGE_LIMIT: usize = 2000;
+/// How long OpenClaw gets to answer a prompt
+const REPLY_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long to wait for the next part of a reply that doesn't parse yet
+const FOLLOW_UP_WAIT: Duration = Duration::from_secs(6);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Attached decision files larger than this are not downloaded
+const MAX_ATTACHMENT_BYTES: u64 = 64 * 1024;
+
 #[derive(Clone)]
 pub struct DiscordClient {
+    api_base: String,
     bot_token: String,
     channel_id: String,
     openclaw_user_id: String,
     http: reqwest::Client,
+    /// Replies pushed by the gateway; REST polling is used while it isn't live
+    replies: Option<ReplyRouter>,
+}
+
+/// A channel message, as returned by REST and in MESSAGE_CREATE events
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct DiscordMessage {
+    pub id: String,
+    #[serde(default)]
+    pub channel_id: String,
+    pub content: String,
+    pub author: DiscordAuthor,
+    pub message_reference: Option<MessageReference>,
+    #[serde(default)]
+    pub attachments: Vec<DiscordAttachment>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DiscordMessage {
-    id: String,
-    content: String,
-    author: DiscordAuthor,
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct DiscordAuthor {
+    pub id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct DiscordAuthor {
-    id: String,
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct MessageReference {
+    pub message_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct DiscordAttachment {
+    pub filename: String,
+    pub url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+impl DiscordMessage {
+    /// ID of the message this one replies to, if it is a reply
+    pub fn reply_to(&self) -> Option<&str> {
+        self.message_reference.as_ref()?.message_id.as_deref()
+    }
 }
 
 impl DiscordClient {
     pub fn new(bot_token: &str, channel_id: &str, openclaw_user_id: &str) -> Self {
         Self {
+            api_base: DISCORD_API_BASE.to_string(),
             bot_token: bot_token.to_string(),
             channel_id: channel_id.to_string(),
             openclaw_user_id: openclaw_user_id.to_string(),
             http: reqwest::Client::new(),
+            replies: None,
         }
     }
 
+    /// Take replies from a running `DiscordGateway` instead of polling
+    pub fn with_gateway(mut self, replies: ReplyRouter) -> Self {
+        self.replies = Some(replies);
+        self
+    }
+
+    /// Point the client at a different API root (e.g. a local stub in tests)
+    #[cfg(test)]
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
     /// Send a message to the Discord channel and return its ID
     pub async fn send_message(&self, content: &str) -> Result<String> {
-        let url = format!(
-            "https://discord.com/api/v10/channels/{}/messages",
-            self.channel_id
-        );
+        let url = format!("{}/channels/{}/messages", self.api_base, self.channel_id);
 
         let body = serde_json::json!({ "content": content });
 
@@ -62,63 +120,130 @@ impl DiscordClient {
         Ok(msg.id)
     }
 
-    /// Poll for a response from OpenClaw after the given message ID
-    /// Timeout: 60 seconds, polling interval: 2 seconds
-    pub async fn poll_response(&self, after_message_id: &str) -> Result<Option<String>> {
-        let max_attempts = 30; // 30 × 2s = 60s
-        let poll_interval = tokio::time::Duration::from_secs(2);
-
-        for attempt in 1..=max_attempts {
-            tokio::time::sleep(poll_interval).await;
-
-            let url = format!(
-                "https://discord.com/api/v10/channels/{}/messages?after={}&limit=10",
-                self.channel_id, after_message_id
-            );
-
-            let resp = self
-                .http
-                .get(&url)
-                .header("Authorization", format!("Bot {}", self.bot_token))
-                .send()
-                .await;
-
-            let resp = match resp {
-                Ok(r) => r,
-                Err(e) => {
-                    warn!(attempt, error = %e, "Discord poll request failed");
+    /// Post a prompt of any length, split into numbered parts that fit
+    /// Discord's limit. Only the last part mentions OpenClaw, so it answers
+    /// once the whole prompt is in the channel; its ID is returned.
+    pub async fn send_prompt(&self, prompt: &str) -> Result<String> {
+        let mention = format!("<@{}> ", self.openclaw_user_id);
+        // Room for the mention and a "**[10/10]** " part marker
+        let parts = split_message(prompt, MESSAGE_LIMIT - mention.len() - 16);
+        let count = parts.len();
+
+        let mut last_id = String::new();
+        for (i, part) in parts.iter().enumerate() {
+            let marker = if count > 1 { format!("**[{}/{}]** ", i + 1, count) } else { String::new() };
+            let mention = if i + 1 == count { mention.as_str() } else { "" };
+            last_id = self.send_message(&format!("{}{}{}", mention, marker, part)).await?;
+        }
+        if count > 1 {
+            info!(parts = count, chars = prompt.len(), "Long prompt split across messages");
+        }
+        Ok(last_id)
+    }
+
+    /// Messages posted after the given message ID, oldest first
+    async fn messages_after(&self, after_message_id: &str) -> Result<Vec<DiscordMessage>> {
+        let url = format!(
+            "{}/channels/{}/messages?after={}&limit=50",
+            self.api_base, self.channel_id, after_message_id
+        );
+
+        let resp = self
+            .http
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            anyhow::bail!("Discord poll returned {}", status);
+        }
+
+        let mut messages: Vec<DiscordMessage> = resp.json().await?;
+        // Snowflake IDs grow over time; compare numerically
+        messages.sort_by(|a, b| (a.id.len(), &a.id).cmp(&(b.id.len(), &b.id)));
+        Ok(messages)
+    }
+
+    /// A message's text followed by the contents of any attached .json files
+    async fn message_text(&self, msg: &DiscordMessage) -> String {
+        let mut text = msg.content.clone();
+
+        for attachment in &msg.attachments {
+            if !attachment.filename.to_ascii_lowercase().ends_with(".json") {
+                continue;
+            }
+            if attachment.size > MAX_ATTACHMENT_BYTES {
+                warn!(filename = %attachment.filename, size = attachment.size, "Attachment too large — skipped");
+                continue;
+            }
+
+            let body = match self.http.get(&attachment.url).send().await {
+                Ok(resp) if resp.status().is_success() => resp.text().await,
+                Ok(resp) => {
+                    warn!(filename = %attachment.filename, status = %resp.status(), "Attachment download failed");
                     continue;
                 }
+                Err(e) => Err(e),
             };
-
-            if !resp.status().is_success() {
-                warn!(
-                    attempt,
-                    status = %resp.status(),
-                    "Discord poll returned non-200"
-                );
-                continue;
+            match body {
+                Ok(body) => text.push_str(&body),
+                Err(e) => warn!(filename = %attachment.filename, error = %e, "Attachment download failed"),
             }
+        }
+        text
+    }
+
+    /// Poll for a response from OpenClaw after the given message ID.
+    /// Replies to other messages are skipped; a plain message counts.
+    /// Consecutive OpenClaw messages are joined until they hold a complete
+    /// decision, someone else posts, or no further part arrives.
+    /// Timeout: 60 seconds, polling interval: 2 seconds
+    pub async fn poll_response(&self, after_message_id: &str) -> Result<Option<String>> {
+        let deadline = Instant::now() + REPLY_TIMEOUT;
+        let mut after = after_message_id.to_string();
+        let mut reply = String::new();
+        let mut parts = 0;
+        let mut last_part_at = Instant::now();
+        let mut attempt = 0;
+
+        while Instant::now() < deadline {
+            attempt += 1;
+            tokio::time::sleep(POLL_INTERVAL).await;
 
-            let messages: Vec<DiscordMessage> = match resp.json().await {
+            let messages = match self.messages_after(&after).await {
                 Ok(m) => m,
                 Err(e) => {
-                    warn!(attempt, error = %e, "Failed to parse Discord messages");
+                    warn!(attempt, error = %e, "Discord poll failed");
                     continue;
                 }
             };
 
-            // Find a response from OpenClaw
+            // Find a response from OpenClaw, then any parts following it
+            let mut interrupted = false;
             for msg in &messages {
-                if msg.author.id == self.openclaw_user_id {
-                    info!(
-                        attempt,
-                        message_id = %msg.id,
-                        content_len = msg.content.len(),
-                        "OpenClaw response received"
-                    );
-                    return Ok(Some(msg.content.clone()));
+                let answers_prompt = msg.reply_to().is_none_or(|id| id == after_message_id);
+                if msg.author.id != self.openclaw_user_id || !answers_prompt {
+                    if parts > 0 {
+                        interrupted = true;
+                        break;
+                    }
+                    after = msg.id.clone();
+                    continue;
                 }
+
+                reply.push_str(&self.message_text(msg).await);
+                parts += 1;
+                last_part_at = Instant::now();
+                after = msg.id.clone();
+            }
+
+            if parts > 0
+                && (interrupted || is_complete_decision(&reply) || last_part_at.elapsed() >= FOLLOW_UP_WAIT)
+            {
+                info!(attempt, parts, content_len = reply.len(), "OpenClaw response received");
+                return Ok(Some(reply));
             }
 
             if attempt % 10 == 0 {
@@ -126,13 +251,172 @@ impl DiscordClient {
             }
         }
 
+        if parts > 0 {
+            warn!(parts, "OpenClaw response incomplete after 60 seconds");
+            return Ok(Some(reply));
+        }
         warn!("OpenClaw response timeout after 60 seconds");
         Ok(None)
     }
 
+    /// Wait for the gateway to deliver OpenClaw's reply to the given
+    /// message, joining parts the same way `poll_response` does
+    async fn await_reply(&self, replies: &ReplyRouter, prompt_id: &str) -> Option<String> {
+        let deadline = Instant::now() + REPLY_TIMEOUT;
+        let mut rx = replies.expect(prompt_id);
+        let mut reply = String::new();
+        let mut parts = 0;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let wait = if parts == 0 { remaining } else { FOLLOW_UP_WAIT.min(remaining) };
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(msg)) => {
+                    reply.push_str(&self.message_text(&msg).await);
+                    parts += 1;
+                    if is_complete_decision(&reply) {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        replies.forget(prompt_id);
+
+        if parts == 0 {
+            warn!("OpenClaw response timeout after 60 seconds");
+            return None;
+        }
+        info!(
+            prompt_id,
+            parts,
+            content_len = reply.len(),
+            "OpenClaw response received via gateway"
+        );
+        Some(reply)
+    }
+
     /// Send prompt and wait for response (convenience wrapper)
     pub async fn ask(&self, prompt: &str) -> Result<Option<String>> {
-        let msg_id = self.send_message(prompt).await?;
-        self.poll_response(&msg_id).await
+        let msg_id = self.send_prompt(prompt).await?;
+        match &self.replies {
+            Some(replies) if replies.is_live() => Ok(self.await_reply(replies, &msg_id).await),
+            _ => self.poll_response(&msg_id).await,
+        }
+    }
+}
+
+/// Split text into pieces of at most `limit` bytes, breaking between lines
+/// where possible and inside a line only when it alone is too long
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for line in text.split_inclusive('\n') {
+        if current.len() + line.len() > limit && !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+        let mut line = line;
+        while line.len() > limit {
+            let mut cut = limit;
+            while !line.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            parts.push(line[..cut].to_string());
+            line = &line[cut..];
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[async_trait]
+impl DecisionProvider for DiscordClient {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn decide(&self, context: &PromptContext) -> Result<Option<DecisionResponse>> {
+        self.respond(&build_prompt(context)).await
+    }
+
+    async fn correct(
+        &self,
+        _context: &PromptContext,
+        rejected: &str,
+        errors: &[String],
+    ) -> Result<Option<DecisionResponse>> {
+        // OpenClaw keeps the conversation, so the follow-up alone is enough
+        self.respond(&build_correction(rejected, errors)).await
+    }
+}
+
+impl DiscordClient {
+    async fn respond(&self, prompt: &str) -> Result<Option<DecisionResponse>> {
+        let started = std::time::Instant::now();
+
+        Ok(self.ask(prompt).await?.map(|raw| DecisionResponse {
+            raw,
+            provider: self.name().to_string(),
+            model: None,
+            latency_ms: started.elapsed().as_millis() as i32,
+            input_tokens: None,
+            output_tokens: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openclaw::mock::{MockDiscord, OPENCLAW_USER_ID};
+
+    #[test]
+    fn test_split_message() {
+        let text = "line one\nline two\n".repeat(100) + &"é".repeat(30);
+        let parts = split_message(&text, 50);
+        assert!(parts.iter().all(|p| p.len() <= 50));
+        assert!(parts[0].ends_with('\n'));
+        assert_eq!(parts.concat(), text);
+        assert_eq!(split_message("short", 2000), vec!["short"]);
+    }
+
+    #[tokio::test]
+    async fn test_long_prompt_and_split_reply() {
+        let discord = MockDiscord::start().await;
+        discord.set_reply_parts(&[
+            r#"Analysis done. {"action":"BUY","symbol":"SOLUSDC","#,
+            r#""confidence":80,"reasoning":"Momentum","stop_loss":95.0,"take_profit":110.0}"#,
+        ]);
+        let prompt = "📈 ticker line with some padding\n".repeat(150);
+
+        let reply = discord.client().ask(&prompt).await.unwrap().unwrap();
+        assert!(is_complete_decision(&reply));
+        assert!(reply.starts_with("Analysis done."));
+
+        let posted = discord.prompts();
+        assert!(posted.len() > 2);
+        assert!(posted.iter().all(|p| p.chars().count() <= MESSAGE_LIMIT));
+        assert!(posted[0].starts_with("**[1/"));
+        let mention = format!("<@{}> ", OPENCLAW_USER_ID);
+        assert!(posted.last().unwrap().starts_with(&mention));
+        assert_eq!(posted.iter().filter(|p| p.contains(&mention)).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reply_in_attached_json() {
+        let discord = MockDiscord::start().await;
+        discord.set_reply("Decision attached.");
+        discord.set_attachment(
+            "decision.json",
+            r#"{"action":"HOLD","confidence":60,"reasoning":"Choppy market"}"#,
+        );
+
+        let reply = discord.client().ask("short prompt").await.unwrap().unwrap();
+        let decision = crate::openclaw::parse_decision(&reply).unwrap();
+        assert_eq!(decision.reasoning, "Choppy market");
     }
 }
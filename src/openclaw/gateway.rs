@@ -0,0 +1,421 @@
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+use super::discord::DiscordMessage;
+
+const GATEWAY_VERSION: u8 = 10;
+
+// Gateway opcodes
+const OP_DISPATCH: u8 = 0;
+const OP_HEARTBEAT: u8 = 1;
+const OP_IDENTIFY: u8 = 2;
+const OP_RESUME: u8 = 6;
+const OP_RECONNECT: u8 = 7;
+const OP_INVALID_SESSION: u8 = 9;
+const OP_HELLO: u8 = 10;
+const OP_HEARTBEAT_ACK: u8 = 11;
+
+/// GUILD_MESSAGES | DIRECT_MESSAGES | MESSAGE_CONTENT (privileged — enable it
+/// for the bot in the Developer Portal)
+const INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+
+/// Delay before reconnecting when Discord asks for it
+const RESUME_DELAY: Duration = Duration::from_secs(1);
+/// Delay before reconnecting after the connection drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Replies nobody is waiting for yet that are kept, oldest dropped first
+const EARLY_REPLY_LIMIT: usize = 16;
+
+/// OpenClaw messages routed from the gateway to the prompts waiting for
+/// them — a reply may come in several parts. Only trusted while a gateway
+/// session is live; otherwise callers poll REST.
+#[derive(Clone, Default)]
+pub struct ReplyRouter {
+    inner: Arc<Mutex<RouterState>>,
+}
+
+#[derive(Default)]
+struct RouterState {
+    live: bool,
+    /// Prompt message ID → caller waiting for its reply
+    waiting: HashMap<String, mpsc::UnboundedSender<DiscordMessage>>,
+    /// (prompt message ID, reply) that arrived before anyone waited for them
+    early: VecDeque<(String, DiscordMessage)>,
+}
+
+impl ReplyRouter {
+    /// Whether a gateway session is connected and receiving events
+    pub fn is_live(&self) -> bool {
+        self.inner.lock().expect("reply router poisoned").live
+    }
+
+    fn set_live(&self, live: bool) {
+        self.inner.lock().expect("reply router poisoned").live = live;
+    }
+
+    /// Receive OpenClaw's messages answering the given prompt message,
+    /// until `forget`
+    pub(super) fn expect(&self, prompt_id: &str) -> mpsc::UnboundedReceiver<DiscordMessage> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut state = self.inner.lock().expect("reply router poisoned");
+        let (early, rest): (VecDeque<_>, VecDeque<_>) =
+            std::mem::take(&mut state.early).into_iter().partition(|(id, _)| id == prompt_id);
+        state.early = rest;
+        for (_, msg) in early {
+            let _ = tx.send(msg);
+        }
+        state.waiting.insert(prompt_id.to_string(), tx);
+        rx
+    }
+
+    /// Stop waiting for a prompt's reply
+    pub(super) fn forget(&self, prompt_id: &str) {
+        self.inner.lock().expect("reply router poisoned").waiting.remove(prompt_id);
+    }
+
+    /// Hand a message to whoever waits for the message it references. A
+    /// plain message (no reference) goes to the waiting prompt only when
+    /// exactly one is waiting.
+    fn deliver(&self, msg: DiscordMessage) {
+        let mut state = self.inner.lock().expect("reply router poisoned");
+        let prompt_id = match msg.reply_to() {
+            Some(id) => id.to_string(),
+            None if state.waiting.len() == 1 => state.waiting.keys().next().cloned().unwrap_or_default(),
+            None => return,
+        };
+
+        match state.waiting.get(&prompt_id) {
+            Some(tx) => {
+                let _ = tx.send(msg);
+            }
+            None => {
+                if state.early.len() >= EARLY_REPLY_LIMIT {
+                    state.early.pop_front();
+                }
+                state.early.push_back((prompt_id, msg));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GatewayPayload {
+    op: u8,
+    #[serde(default)]
+    d: Value,
+    s: Option<u64>,
+    t: Option<String>,
+}
+
+/// What a dropped connection needs to pick up where it left off
+#[derive(Debug, Default)]
+struct ResumeState {
+    session_id: Option<String>,
+    resume_url: Option<String>,
+    /// Last dispatch sequence number received
+    seq: Option<u64>,
+}
+
+/// Why a gateway session ended
+enum SessionEnd {
+    /// Reconnect, resuming the session if it is still valid
+    Reconnect,
+    /// Token, intents or version rejected — reconnecting won't help
+    Fatal(u16),
+}
+
+/// Discord Gateway (websocket) connection delivering OpenClaw's replies as
+/// MESSAGE_CREATE events, replacing REST polling while it is live. Keeps
+/// the heartbeat and resumes the session after reconnects so no reply is
+/// missed.
+pub struct DiscordGateway {
+    url: String,
+    bot_token: String,
+    channel_id: String,
+    openclaw_user_id: String,
+    replies: ReplyRouter,
+}
+
+impl DiscordGateway {
+    pub fn new(url: &str, bot_token: &str, channel_id: &str, openclaw_user_id: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            bot_token: bot_token.to_string(),
+            channel_id: channel_id.to_string(),
+            openclaw_user_id: openclaw_user_id.to_string(),
+            replies: ReplyRouter::default(),
+        }
+    }
+
+    /// Handle for `DiscordClient::with_gateway`
+    pub fn replies(&self) -> ReplyRouter {
+        self.replies.clone()
+    }
+
+    /// Run until Discord rejects the bot outright, reconnecting (and
+    /// resuming) after every disconnect
+    pub async fn run(self) {
+        info!("🛰️ Discord gateway started");
+        let mut resume = ResumeState::default();
+
+        loop {
+            let delay = match self.session(&mut resume).await {
+                Ok(SessionEnd::Reconnect) => RESUME_DELAY,
+                Ok(SessionEnd::Fatal(code)) => {
+                    self.replies.set_live(false);
+                    error!(code, "Discord gateway rejected the bot — falling back to REST polling");
+                    return;
+                }
+                Err(e) => {
+                    warn!(error = %e, "Discord gateway dropped — reconnecting");
+                    RECONNECT_DELAY
+                }
+            };
+            self.replies.set_live(false);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn session(&self, resume: &mut ResumeState) -> Result<SessionEnd> {
+        let base = match (&resume.session_id, &resume.resume_url) {
+            (Some(_), Some(url)) => url.trim_end_matches('/').to_string(),
+            _ => self.url.clone(),
+        };
+        let url = format!("{}/?v={}&encoding=json", base, GATEWAY_VERSION);
+
+        let (mut ws, _) = connect_async(url.as_str())
+            .await
+            .context("Failed to connect to Discord gateway")?;
+
+        // Hello carries the heartbeat interval
+        let hello: GatewayPayload = match ws.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str(&text)?,
+            other => anyhow::bail!("Expected Hello from Discord gateway, got {:?}", other),
+        };
+        if hello.op != OP_HELLO {
+            anyhow::bail!("Expected Hello from Discord gateway, got op {}", hello.op);
+        }
+        let interval = Duration::from_millis(
+            hello.d["heartbeat_interval"]
+                .as_u64()
+                .context("Hello without heartbeat_interval")?,
+        );
+
+        let handshake = match (&resume.session_id, resume.seq) {
+            (Some(session_id), Some(seq)) => json!({
+                "op": OP_RESUME,
+                "d": { "token": self.bot_token, "session_id": session_id, "seq": seq },
+            }),
+            _ => json!({
+                "op": OP_IDENTIFY,
+                "d": {
+                    "token": self.bot_token,
+                    "intents": INTENTS,
+                    "properties": { "os": std::env::consts::OS, "browser": "survival-bot", "device": "survival-bot" },
+                },
+            }),
+        };
+        ws.send(Message::Text(handshake.to_string())).await?;
+
+        let mut heartbeat =
+            tokio::time::interval_at(tokio::time::Instant::now() + interval / 2, interval);
+        let mut acked = true;
+
+        loop {
+            tokio::select! {
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let Ok(payload) = serde_json::from_str::<GatewayPayload>(&text) else {
+                            continue;
+                        };
+                        match payload.op {
+                            OP_DISPATCH => {
+                                if payload.s.is_some() {
+                                    resume.seq = payload.s;
+                                }
+                                self.dispatch(payload.t.as_deref(), payload.d, resume);
+                            }
+                            OP_HEARTBEAT => ws.send(heartbeat_payload(resume.seq)).await?,
+                            OP_HEARTBEAT_ACK => acked = true,
+                            OP_RECONNECT => {
+                                info!("Discord gateway asked for a reconnect");
+                                return Ok(SessionEnd::Reconnect);
+                            }
+                            OP_INVALID_SESSION => {
+                                // `d` says whether the session can still be resumed
+                                if payload.d != Value::Bool(true) {
+                                    *resume = ResumeState::default();
+                                }
+                                warn!("Discord gateway session invalidated");
+                                return Ok(SessionEnd::Reconnect);
+                            }
+                            _ => {}
+                        }
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        let code = frame.map(|f| u16::from(f.code)).unwrap_or(1000);
+                        return Ok(close_action(code, resume));
+                    }
+                    None => anyhow::bail!("Discord gateway closed"),
+                    Some(Err(e)) => return Err(e.into()),
+                    _ => {}
+                },
+                _ = heartbeat.tick() => {
+                    // No ACK since the last beat: the connection is a zombie
+                    if !acked {
+                        anyhow::bail!("Discord gateway heartbeat not acknowledged");
+                    }
+                    ws.send(heartbeat_payload(resume.seq)).await?;
+                    acked = false;
+                }
+            }
+        }
+    }
+
+    fn dispatch(&self, event: Option<&str>, data: Value, resume: &mut ResumeState) {
+        match event {
+            Some("READY") => {
+                resume.session_id = data["session_id"].as_str().map(str::to_string);
+                resume.resume_url = data["resume_gateway_url"].as_str().map(str::to_string);
+                self.replies.set_live(true);
+                info!("🛰️ Discord gateway ready — listening for OpenClaw replies");
+            }
+            Some("RESUMED") => {
+                self.replies.set_live(true);
+                info!(seq = ?resume.seq, "Discord gateway session resumed");
+            }
+            Some("MESSAGE_CREATE") => match serde_json::from_value::<DiscordMessage>(data) {
+                Ok(msg) if msg.channel_id == self.channel_id && msg.author.id == self.openclaw_user_id => {
+                    info!(
+                        message_id = %msg.id,
+                        reply_to = ?msg.reply_to(),
+                        "OpenClaw message received via gateway"
+                    );
+                    self.replies.deliver(msg);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to parse MESSAGE_CREATE"),
+            },
+            _ => {}
+        }
+    }
+}
+
+fn heartbeat_payload(seq: Option<u64>) -> Message {
+    Message::Text(json!({ "op": OP_HEARTBEAT, "d": seq }).to_string())
+}
+
+/// What a close code from Discord means for the next connection
+fn close_action(code: u16, resume: &mut ResumeState) -> SessionEnd {
+    match code {
+        // Authentication failed, invalid shard, sharding required, invalid
+        // API version, invalid or disallowed intents
+        4004 | 4010..=4014 => SessionEnd::Fatal(code),
+        // Invalid seq, session timed out: start a new session
+        4007 | 4009 => {
+            *resume = ResumeState::default();
+            SessionEnd::Reconnect
+        }
+        _ => SessionEnd::Reconnect,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openclaw::mock::{MockDiscord, MockGateway, CHANNEL_ID, OPENCLAW_USER_ID};
+
+    /// A gateway connected to the stub and past READY
+    async fn live_gateway() -> (MockGateway, ReplyRouter) {
+        let stub = MockGateway::start().await;
+        let gateway = DiscordGateway::new(&stub.url, "mock-token", CHANNEL_ID, OPENCLAW_USER_ID);
+        let replies = gateway.replies();
+        tokio::spawn(gateway.run());
+        wait_for(|| replies.is_live()).await;
+        (stub, replies)
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("condition not reached within 5s");
+    }
+
+    async fn reply(rx: &mut mpsc::UnboundedReceiver<DiscordMessage>) -> String {
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .content
+    }
+
+    #[tokio::test]
+    async fn test_replies_are_matched_to_their_prompt() {
+        let (stub, replies) = live_gateway().await;
+        let identify = &stub.received()[0];
+        assert_eq!(identify["op"], OP_IDENTIFY);
+        assert_eq!(identify["d"]["token"], "mock-token");
+        assert_eq!(identify["d"]["intents"], INTENTS);
+
+        let mut rx = replies.expect("100");
+        stub.message("someone-else", "ignored", Some("100"));
+        stub.message(OPENCLAW_USER_ID, "stale", Some("99"));
+        stub.message(OPENCLAW_USER_ID, "fresh", Some("100"));
+        // Follow-up parts need not be replies
+        stub.message(OPENCLAW_USER_ID, "more", None);
+        assert_eq!(reply(&mut rx).await, "fresh");
+        assert_eq!(reply(&mut rx).await, "more");
+        replies.forget("100");
+
+        // A reply that beats the caller to `expect` is kept for it
+        stub.message(OPENCLAW_USER_ID, "early", Some("101"));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(reply(&mut replies.expect("101")).await, "early");
+        replies.forget("101");
+
+        // The client waits on the gateway instead of polling REST
+        let discord = MockDiscord::start().await;
+        discord.set_reply("from REST");
+        let client = discord.client().with_gateway(replies.clone());
+        let ask = tokio::spawn(async move { client.ask("prompt").await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        stub.message(OPENCLAW_USER_ID, r#"{"action":"HOLD","confidence":50,"#, Some("100"));
+        stub.message(OPENCLAW_USER_ID, r#""reasoning":"from gateway"}"#, None);
+        let reply = ask.await.unwrap().unwrap().unwrap();
+        assert_eq!(crate::openclaw::parse_decision(&reply).unwrap().reasoning, "from gateway");
+    }
+
+    #[tokio::test]
+    async fn test_gateway_resumes_after_reconnect() {
+        let (stub, replies) = live_gateway().await;
+        let mut rx = replies.expect("1");
+        stub.message(OPENCLAW_USER_ID, "before", Some("1"));
+        assert_eq!(reply(&mut rx).await, "before");
+        replies.forget("1");
+
+        stub.request_reconnect();
+        wait_for(|| stub.received().len() == 2).await;
+        let resume = &stub.received()[1];
+        assert_eq!(resume["op"], OP_RESUME);
+        assert_eq!(resume["d"]["session_id"], "mock-session");
+        // READY was seq 1, the message seq 2
+        assert_eq!(resume["d"]["seq"], 2);
+
+        wait_for(|| replies.is_live()).await;
+        let mut rx = replies.expect("2");
+        stub.message(OPENCLAW_USER_ID, "after", Some("2"));
+        assert_eq!(reply(&mut rx).await, "after");
+    }
+}
@@ -0,0 +1,278 @@
+//! In-process stand-ins for the Discord channel OpenClaw answers in, for tests.
+//! Over REST, every prompt posted to the channel is recorded and answered
+//! immediately with the scripted reply (in one or more messages, optionally
+//! with an attached file), authored by the OpenClaw user. The
+//! gateway stub speaks enough of the websocket protocol (Hello, Identify,
+//! Resume, heartbeats) to push MESSAGE_CREATE events on demand.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use super::DiscordClient;
+
+pub const CHANNEL_ID: &str = "mock-channel";
+pub const OPENCLAW_USER_ID: &str = "mock-openclaw";
+
+#[derive(Default)]
+struct MockState {
+    api_base: String,
+    /// Messages OpenClaw answers with, in order
+    reply: Vec<String>,
+    /// (filename, contents) attached to the last reply message
+    attachment: Option<(String, String)>,
+    prompts: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct MockDiscord {
+    pub api_base: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockDiscord {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock Discord");
+        let addr = listener.local_addr().expect("mock Discord address");
+        let api_base = format!("http://{}", addr);
+
+        let state = Arc::new(Mutex::new(MockState {
+            api_base: api_base.clone(),
+            ..MockState::default()
+        }));
+        let app = Router::new()
+            .route("/channels/:channel/messages", get(poll).post(send))
+            .route("/attachments/:filename", get(attachment))
+            .with_state(state.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self { api_base, state }
+    }
+
+    /// A client posting to this stub's channel
+    pub fn client(&self) -> DiscordClient {
+        DiscordClient::new("mock-token", CHANNEL_ID, OPENCLAW_USER_ID).with_api_base(&self.api_base)
+    }
+
+    /// What OpenClaw answers to the next prompt
+    pub fn set_reply(&self, reply: &str) {
+        self.set_reply_parts(&[reply]);
+    }
+
+    /// An answer split over several messages; only the first is a reply
+    pub fn set_reply_parts(&self, parts: &[&str]) {
+        self.state.lock().unwrap().reply = parts.iter().map(|p| p.to_string()).collect();
+    }
+
+    /// A file attached to the last message of the answer
+    pub fn set_attachment(&self, filename: &str, contents: &str) {
+        self.state.lock().unwrap().attachment = Some((filename.to_string(), contents.to_string()));
+    }
+
+    /// Every prompt posted so far
+    pub fn prompts(&self) -> Vec<String> {
+        self.state.lock().unwrap().prompts.clone()
+    }
+}
+
+async fn send(
+    State(state): State<Arc<Mutex<MockState>>>,
+    Path(_channel): Path<String>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    let mut state = state.lock().unwrap();
+    let content = body["content"].as_str().unwrap_or_default().to_string();
+    state.prompts.push(content.clone());
+    Json(json!({
+        "id": prompt_id(state.prompts.len()).to_string(),
+        "content": content,
+        "author": { "id": "mock-bot" },
+    }))
+}
+
+/// Message IDs: prompt n is n × 100, its answer's parts follow it
+fn prompt_id(n: usize) -> u64 {
+    n as u64 * 100
+}
+
+/// OpenClaw's answer to the latest prompt, the parts posted after `after`
+async fn poll(
+    State(state): State<Arc<Mutex<MockState>>>,
+    Path(_channel): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<Value> {
+    let state = state.lock().unwrap();
+    let after: u64 = params.get("after").and_then(|a| a.parse().ok()).unwrap_or(0);
+    let prompt = prompt_id(state.prompts.len());
+    let last = state.reply.len().saturating_sub(1);
+
+    let messages: Vec<Value> = state
+        .reply
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| prompt + *i as u64 + 1 > after)
+        .map(|(i, content)| {
+            let attachments = match (&state.attachment, i == last) {
+                (Some((filename, contents)), true) => json!([{
+                    "filename": filename,
+                    "url": format!("{}/attachments/{}", state.api_base, filename),
+                    "size": contents.len(),
+                }]),
+                _ => json!([]),
+            };
+            json!({
+                "id": (prompt + i as u64 + 1).to_string(),
+                "content": content,
+                "author": { "id": OPENCLAW_USER_ID },
+                "message_reference": (i == 0).then(|| json!({ "message_id": prompt.to_string() })),
+                "attachments": attachments,
+            })
+        })
+        .collect();
+    Json(Value::Array(messages))
+}
+
+async fn attachment(
+    State(state): State<Arc<Mutex<MockState>>>,
+    Path(filename): Path<String>,
+) -> String {
+    let state = state.lock().unwrap();
+    match &state.attachment {
+        Some((name, contents)) if *name == filename => contents.clone(),
+        _ => String::new(),
+    }
+}
+
+// ─── Gateway stub ────────────────────────────────────────
+
+#[derive(Default)]
+struct GatewayState {
+    /// Identify / Resume payloads received, in order
+    received: Vec<Value>,
+    seq: u64,
+}
+
+#[derive(Clone)]
+struct GatewayStub {
+    url: String,
+    state: Arc<Mutex<GatewayState>>,
+    /// Payloads pushed to every connected bot
+    events: broadcast::Sender<Value>,
+}
+
+impl GatewayStub {
+    /// A dispatch payload with the next sequence number
+    fn dispatch(&self, event: &str, data: Value) -> Value {
+        let mut state = self.state.lock().unwrap();
+        state.seq += 1;
+        json!({ "op": 0, "t": event, "s": state.seq, "d": data })
+    }
+}
+
+pub struct MockGateway {
+    /// Gateway URL to hand to `DiscordGateway::new`
+    pub url: String,
+    stub: GatewayStub,
+}
+
+impl MockGateway {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock gateway");
+        let addr = listener.local_addr().expect("mock gateway address");
+        let stub = GatewayStub {
+            url: format!("ws://{}", addr),
+            state: Arc::new(Mutex::new(GatewayState::default())),
+            events: broadcast::channel(16).0,
+        };
+        let app = Router::new()
+            .route("/", get(gateway))
+            .with_state(stub.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Self {
+            url: stub.url.clone(),
+            stub,
+        }
+    }
+
+    /// Identify and Resume payloads the bot sent, in order
+    pub fn received(&self) -> Vec<Value> {
+        self.stub.state.lock().unwrap().received.clone()
+    }
+
+    /// Post a message in the channel, optionally as a reply to `reply_to`
+    pub fn message(&self, author: &str, content: &str, reply_to: Option<&str>) {
+        let event = self.stub.dispatch(
+            "MESSAGE_CREATE",
+            json!({
+                "id": uuid::Uuid::new_v4().to_string(),
+                "channel_id": CHANNEL_ID,
+                "content": content,
+                "author": { "id": author },
+                "message_reference": reply_to.map(|id| json!({ "message_id": id })),
+            }),
+        );
+        let _ = self.stub.events.send(event);
+    }
+
+    /// Ask connected bots to reconnect (op 7) and drop their connections
+    pub fn request_reconnect(&self) {
+        let _ = self.stub.events.send(json!({ "op": 7, "d": null }));
+    }
+}
+
+async fn gateway(ws: WebSocketUpgrade, State(stub): State<GatewayStub>) -> Response {
+    ws.on_upgrade(move |socket| serve_gateway(socket, stub))
+}
+
+async fn serve_gateway(mut socket: WebSocket, stub: GatewayStub) {
+    let mut events = stub.events.subscribe();
+    let hello = json!({ "op": 10, "d": { "heartbeat_interval": 1000 } });
+    if socket.send(Message::Text(hello.to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                let Some(Ok(Message::Text(text))) = msg else { return };
+                let Ok(payload) = serde_json::from_str::<Value>(&text) else { continue };
+                let reply = match payload["op"].as_u64() {
+                    Some(1) => json!({ "op": 11, "d": null }),
+                    Some(2) => {
+                        stub.state.lock().unwrap().received.push(payload);
+                        stub.dispatch("READY", json!({ "session_id": "mock-session", "resume_gateway_url": stub.url }))
+                    }
+                    Some(6) => {
+                        stub.state.lock().unwrap().received.push(payload);
+                        stub.dispatch("RESUMED", json!({}))
+                    }
+                    _ => continue,
+                };
+                if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                    return;
+                }
+            }
+            event = events.recv() => {
+                let Ok(event) = event else { return };
+                if socket.send(Message::Text(event.to_string())).await.is_err() || event["op"] == 7 {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,11 @@
 pub mod discord;
+pub mod gateway;
+#[cfg(test)]
+pub mod mock;
 pub mod parser;
 pub mod prompt;
 
 pub use discord::DiscordClient;
-pub use parser::parse_decision;
-pub use prompt::build_prompt;
+pub use gateway::DiscordGateway;
+pub use parser::{is_complete_decision, parse_decision};
+pub use prompt::{build_correction, build_prompt};
@@ -1,20 +1,12 @@
-use crate::db::models::{TradingAction, TradingDecision};
+use crate::db::models::TradingDecision;
 use regex::Regex;
 use tracing::{info, warn};
 
-/// Parse OpenClaw's response into a TradingDecision.
+/// Parse a provider's response into a TradingDecision.
 /// Uses two-stage parsing: regex to extract JSON from markdown, then serde.
-/// Falls back to HOLD on any error.
-pub fn parse_decision(raw_response: &str) -> TradingDecision {
-    let default_hold = TradingDecision {
-        action: TradingAction::Hold,
-        symbol: None,
-        confidence: 0,
-        reasoning: "Failed to parse OpenClaw response — defaulting to HOLD".to_string(),
-        stop_loss: None,
-        take_profit: None,
-    };
-
+/// The error says why the text isn't a decision, worded to be quoted back
+/// to the provider; `validate_decision` checks the values themselves.
+pub fn parse_decision(raw_response: &str) -> Result<TradingDecision, String> {
     // Stage 1: Try to extract JSON from markdown code blocks
     let json_str = extract_json(raw_response).unwrap_or_else(|| raw_response.trim().to_string());
 
@@ -27,22 +19,22 @@ pub fn parse_decision(raw_response: &str) -> TradingDecision {
                 symbol = ?decision.symbol,
                 "Parsed trading decision"
             );
-
-            // Validate: BUY/SELL must have a symbol
-            if decision.action != TradingAction::Hold && decision.symbol.is_none() {
-                warn!("BUY/SELL decision missing symbol — defaulting to HOLD");
-                return default_hold;
-            }
-
-            decision
+            Ok(decision)
         }
         Err(e) => {
             warn!(error = %e, raw = %raw_response, "Failed to parse decision JSON");
-            default_hold
+            Err(format!("the answer is not a decision in the required JSON format ({})", e))
         }
     }
 }
 
+/// Whether the text already holds a complete decision — tells when a reply
+/// split over several messages has fully arrived
+pub fn is_complete_decision(raw_response: &str) -> bool {
+    let json_str = extract_json(raw_response).unwrap_or_else(|| raw_response.trim().to_string());
+    serde_json::from_str::<TradingDecision>(&json_str).is_ok()
+}
+
 /// Extract JSON from potential markdown code blocks
 fn extract_json(text: &str) -> Option<String> {
     // Try to find JSON in ```json ... ``` blocks
@@ -63,6 +55,8 @@ fn extract_json(text: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::models::{TradingAction, TrailingStop};
+    use rust_decimal_macros::dec;
 
     #[test]
     fn test_parse_json_block() {
@@ -80,7 +74,7 @@ Here's my analysis:
 ```
         "#;
 
-        let decision = parse_decision(response);
+        let decision = parse_decision(response).unwrap();
         assert_eq!(decision.action, TradingAction::Buy);
         assert_eq!(decision.symbol.as_deref(), Some("BTCUSDC"));
         assert_eq!(decision.confidence, 85);
@@ -89,20 +83,25 @@ Here's my analysis:
     #[test]
     fn test_parse_raw_json() {
         let response = r#"{"action":"HOLD","confidence":50,"reasoning":"Market uncertain"}"#;
-        let decision = parse_decision(response);
+        let decision = parse_decision(response).unwrap();
         assert_eq!(decision.action, TradingAction::Hold);
     }
 
     #[test]
-    fn test_parse_invalid_defaults_to_hold() {
-        let decision = parse_decision("I think you should buy Bitcoin!");
-        assert_eq!(decision.action, TradingAction::Hold);
+    fn test_parse_invalid_is_rejected() {
+        let err = parse_decision("I think you should buy Bitcoin!").unwrap_err();
+        assert!(err.contains("required JSON format"));
+        assert!(!is_complete_decision(r#"{"action":"BUY","symbol":"#));
     }
 
     #[test]
-    fn test_buy_without_symbol_defaults_to_hold() {
-        let response = r#"{"action":"BUY","confidence":90,"reasoning":"Go all in"}"#;
-        let decision = parse_decision(response);
-        assert_eq!(decision.action, TradingAction::Hold);
+    fn test_parse_trailing_stop() {
+        let response = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":90,"reasoning":"Trend",
+            "stop_loss":95.0,"take_profit":110.0,"trailing_stop":{"atr":2.5}}"#;
+        let decision = parse_decision(response).unwrap();
+        assert_eq!(decision.trailing_stop, Some(TrailingStop::Atr(dec!(2.5))));
+
+        let decision = parse_decision(r#"{"action":"HOLD","confidence":50,"reasoning":"Wait"}"#).unwrap();
+        assert_eq!(decision.trailing_stop, None);
     }
 }
@@ -1,22 +1,29 @@
-use crate::binance::types::Ticker24h;
-use crate::db::models::Position;
+use rust_decimal::Decimal;
 
-/// Build a structured prompt for OpenClaw with all market context
-pub fn build_prompt(
-    balance_usdc: f64,
-    open_positions: &[Position],
-    top_tickers: &[Ticker24h],
-    fear_greed_index: i32,
-    consecutive_losses: i64,
-    openclaw_user_id: &str,
-) -> String {
+use crate::decision::PromptContext;
+
+/// Longest stretch of a rejected answer quoted back in a correction
+const MAX_QUOTED_ANSWER: usize = 600;
+
+/// Build a structured prompt with all market context.
+/// Amounts and the example symbol are in the context's quote asset.
+pub fn build_prompt(context: &PromptContext) -> String {
+    let PromptContext {
+        balance,
+        quote_asset,
+        open_positions,
+        tickers: _,
+        fear_greed: fear_greed_index,
+        consecutive_losses,
+    } = context;
+    let consecutive_losses = *consecutive_losses;
     let mut prompt = String::with_capacity(4096);
 
-    // Header — mention OpenClaw so it triggers a response
-    prompt.push_str(&format!("<@{}> 🤖 **SURVIVAL TRADING BOT — CYCLE ANALYSIS REQUEST**\n\n", openclaw_user_id));
+    // Header
+    prompt.push_str("🤖 **SURVIVAL TRADING BOT — CYCLE ANALYSIS REQUEST**\n\n");
 
     // Balance & Status
-    prompt.push_str(&format!("💰 **Available USDC Balance:** ${:.2}\n", balance_usdc));
+    prompt.push_str(&format!("💰 **Available {} Balance:** {:.2}\n", quote_asset, balance));
     prompt.push_str(&format!("📊 **Fear & Greed Index:** {}/100\n", fear_greed_index));
     prompt.push_str(&format!("📉 **Consecutive Losses:** {}\n\n", consecutive_losses));
 
@@ -27,18 +34,22 @@ pub fn build_prompt(
         prompt.push_str("📂 **Open Positions:**\n");
         for pos in open_positions {
             let current = pos.current_price.unwrap_or(pos.entry_price);
-            let pnl_pct = ((current - pos.entry_price) / pos.entry_price) * 100.0;
+            let pnl_pct = (current - pos.entry_price)
+                .checked_div(pos.entry_price)
+                .unwrap_or_default()
+                * Decimal::ONE_HUNDRED;
             prompt.push_str(&format!(
-                "  • {} | Entry: ${:.6} | Current: ${:.6} | P&L: {:.2}% | SL: {} | TP: {}\n",
+                "  • {} | Entry: {:.6} | Current: {:.6} | P&L: {:.2}% | SL: {}{} | TP: {}\n",
                 pos.symbol,
                 pos.entry_price,
                 current,
                 pnl_pct,
                 pos.stop_loss
-                    .map(|v| format!("${:.6}", v))
+                    .map(|v| format!("{:.6}", v))
                     .unwrap_or_else(|| "N/A".to_string()),
+                if pos.trail_pct.is_some() || pos.trail_distance.is_some() { " (trailing)" } else { "" },
                 pos.take_profit
-                    .map(|v| format!("${:.6}", v))
+                    .map(|v| format!("{:.6}", v))
                     .unwrap_or_else(|| "N/A".to_string()),
             ));
         }
@@ -46,26 +57,22 @@ pub fn build_prompt(
     }
 
     // Top Movers (top 10 by volume)
-    prompt.push_str("📈 **Top USDC Pairs (by 24h volume):**\n");
-    let mut sorted_tickers = top_tickers.to_vec();
-    sorted_tickers.sort_by(|a, b| {
-        let vol_a: f64 = a.quote_volume.parse().unwrap_or(0.0);
-        let vol_b: f64 = b.quote_volume.parse().unwrap_or(0.0);
-        vol_b.partial_cmp(&vol_a).unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    for ticker in sorted_tickers.iter().take(10) {
+    prompt.push_str(&format!("📈 **Top {} Pairs (by 24h volume):**\n", quote_asset));
+    for ticker in context.listed_tickers() {
         prompt.push_str(&format!(
-            "  • {} | Price: ${} | 24h Change: {}% | Volume: ${}\n",
-            ticker.symbol, ticker.last_price, ticker.price_change_percent, ticker.quote_volume
+            "  • {} | Price: {} | 24h Change: {}% | Volume: {} {}\n",
+            ticker.symbol, ticker.last_price, ticker.price_change_percent, ticker.quote_volume, quote_asset
         ));
     }
     prompt.push('\n');
 
     // Rules
     prompt.push_str("⚠️ **RULES (MUST FOLLOW):**\n");
-    prompt.push_str("1. This is a SURVIVAL game. If balance reaches $0, the bot dies forever.\n");
-    prompt.push_str("2. Only HALAL spot trading. No leverage, no shorting, no derivatives.\n");
+    prompt.push_str(&format!(
+        "1. This is a SURVIVAL game. If the {} balance reaches 0, the bot dies forever.\n",
+        quote_asset
+    ));
+    prompt.push_str("2. Only HALAL spot trading. No leverage, no shorting, no derivatives. Only BUY pairs listed above.\n");
     prompt.push_str("3. Max 2 open positions at any time.\n");
     prompt.push_str("4. Max 10% of tradeable balance per trade.\n");
     prompt.push_str("5. Always set stop-loss (max 5% below entry) and take-profit.\n");
@@ -82,13 +89,42 @@ pub fn build_prompt(
     prompt.push_str("```json\n");
     prompt.push_str("{\n");
     prompt.push_str("  \"action\": \"BUY\" | \"SELL\" | \"HOLD\",\n");
-    prompt.push_str("  \"symbol\": \"BTCUSDC\" (required if BUY/SELL),\n");
+    prompt.push_str(&format!("  \"symbol\": \"BTC{}\" (required if BUY/SELL),\n", quote_asset));
     prompt.push_str("  \"confidence\": 0-100,\n");
     prompt.push_str("  \"reasoning\": \"Brief explanation of your decision\",\n");
     prompt.push_str("  \"stop_loss\": 50000.00 (required if BUY, price to cut losses),\n");
-    prompt.push_str("  \"take_profit\": 55000.00 (required if BUY, price to take profit)\n");
+    prompt.push_str("  \"take_profit\": 55000.00 (required if BUY, price to take profit),\n");
+    prompt.push_str("  \"trailing_stop\": {\"percent\": 3.0} or {\"atr\": 2.0} (optional, BUY only — stop-loss follows new highs by that % or that many ATRs, max 5%)\n");
     prompt.push_str("}\n");
     prompt.push_str("```\n");
 
     prompt
 }
+
+/// Follow-up sent once when an answer fails validation: quotes the
+/// answer and every violation, and asks for a single corrected decision.
+pub fn build_correction(rejected: &str, errors: &[String]) -> String {
+    let mut prompt = String::with_capacity(1024);
+    prompt.push_str("❌ **YOUR LAST ANSWER WAS REJECTED:**\n");
+    for error in errors {
+        prompt.push_str(&format!("  • {}\n", error));
+    }
+
+    // Enough of the answer to recognise it without echoing a whole essay
+    let mut end = rejected.len().min(MAX_QUOTED_ANSWER);
+    while !rejected.is_char_boundary(end) {
+        end -= 1;
+    }
+    prompt.push_str("\n📝 **Rejected answer:**\n```\n");
+    prompt.push_str(rejected[..end].trim());
+    if end < rejected.len() {
+        prompt.push_str(" …");
+    }
+    prompt.push_str("\n```\n\n");
+
+    prompt.push_str(
+        "🔁 Reply with ONE corrected decision in the same JSON format (no markdown, no extra text), \
+         or HOLD if no trade satisfies the rules.\n",
+    );
+    prompt
+}
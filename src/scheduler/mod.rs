@@ -1,30 +1,12 @@
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::time::Duration;
 use tracing::{error, info};
 
-use crate::binance::BinanceClient;
-use crate::config::Config;
-use crate::db::models::CycleUpdate;
-use crate::openclaw::DiscordClient;
 use crate::trading::TradingEngine;
 
 /// Start the 10-minute trading cycle scheduler.
 /// Runs indefinitely, executing one cycle every 10 minutes.
-pub async fn start_scheduler(
-    config: Arc<Config>,
-    pool: sqlx::PgPool,
-    binance: BinanceClient,
-    discord: DiscordClient,
-    broadcast_tx: broadcast::Sender<CycleUpdate>,
-) {
-    let engine = TradingEngine::new(
-        config,
-        pool,
-        binance,
-        discord,
-        broadcast_tx,
-    );
-
+pub async fn start_scheduler(engine: Arc<TradingEngine>) {
     info!("⏰ Scheduler started — running every 10 minutes");
 
     // Run initial cycle immediately
@@ -46,3 +28,17 @@ pub async fn start_scheduler(
         }
     }
 }
+
+/// Reconcile open positions against exchange holdings at startup, then
+/// every `every`
+pub async fn start_reconciler(engine: Arc<TradingEngine>, every: Duration) {
+    info!(secs = every.as_secs(), "⚖️ Reconciler started");
+
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await; // First tick fires immediately
+        if let Err(e) = engine.reconcile().await {
+            error!(error = %e, "Reconciliation failed");
+        }
+    }
+}
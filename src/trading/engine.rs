@@ -1,42 +1,63 @@
 use anyhow::Result;
 use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use sqlx::PgPool;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Mutex};
 use tracing::{error, info, warn};
 
-use crate::binance::BinanceClient;
-use crate::config::Config;
+use crate::config::{Config, TradingMode};
 use crate::db::models::*;
 use crate::db::queries;
-use crate::market::fetch_fear_greed_index;
-use crate::openclaw::{build_prompt, parse_decision, DiscordClient};
-use crate::trading::risk::RiskManager;
-use crate::trading::strategy::PositionSizer;
+use crate::exchange::{new_client_order_id, quote_prices, SharedExchange};
+use crate::market::{average_true_range, fetch_fear_greed_index, ingest_candles, top_symbols_by_volume};
+use crate::decision::{validate_decision, DecisionResponse, PromptContext, SharedDecisionProvider};
+use crate::binance::{BinanceError, ExecutedTrade, OrderResponse, SymbolFilters, Ticker24h};
+use crate::trading::reconcile::{self, AssetState, DiscrepancyKind};
+use crate::trading::universe;
+use crate::trading::risk::Trail;
+use crate::trading::{ExitSignal, PositionSizer, RiskManager};
 
 /// The core trading engine. Stateless — reads all state fresh each cycle.
+/// Shared (via `Arc`) by the scheduler, the price monitor and the API.
 pub struct TradingEngine {
     config: Arc<Config>,
     pool: PgPool,
-    binance: BinanceClient,
-    discord: DiscordClient,
-    broadcast_tx: broadcast::Sender<CycleUpdate>,
+    exchange: SharedExchange,
+    decisions: SharedDecisionProvider,
+    broadcast_tx: broadcast::Sender<DashboardEvent>,
+    /// Serializes order placement between the cycle and real-time exits
+    order_lock: Mutex<()>,
 }
 
+/// Klines averaged for ATR-based trailing stops
+const ATR_PERIOD: usize = 14;
+
+/// Lookups of an order whose submission failed ambiguously. Binance's order
+/// lookups can lag the matching engine, so a miss is retried with a growing pause.
+const ORDER_LOOKUP_ATTEMPTS: u32 = 3;
+const ORDER_LOOKUP_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How long an unresolved intent may go unlisted on the exchange before
+/// recovery concludes the order never reached it
+const ORDER_NOT_FOUND_GRACE_MINUTES: i64 = 5;
+
 impl TradingEngine {
     pub fn new(
         config: Arc<Config>,
         pool: PgPool,
-        binance: BinanceClient,
-        discord: DiscordClient,
-        broadcast_tx: broadcast::Sender<CycleUpdate>,
+        exchange: SharedExchange,
+        decisions: SharedDecisionProvider,
+        broadcast_tx: broadcast::Sender<DashboardEvent>,
     ) -> Self {
         Self {
             config,
             pool,
-            binance,
-            discord,
+            exchange,
+            decisions,
             broadcast_tx,
+            order_lock: Mutex::new(()),
         }
     }
 
@@ -52,20 +73,25 @@ impl TradingEngine {
             return Ok(());
         }
 
-        // 2. Fetch USDC balance from Binance
-        let balance = match self.binance.get_usdc_balance().await {
+        // Finish orders a crash or lost response left unrecorded before reading the balance
+        if let Err(e) = self.recover_orders().await {
+            warn!(error = %e, "Order recovery failed");
+        }
+
+        // 2. Fetch quote-asset balance from the exchange
+        let balance = match self.exchange.get_quote_balance().await {
             Ok(b) => b,
             Err(e) => {
                 error!(error = %e, "Failed to fetch balance");
-                self.log_error_cycle(0.0, &e.to_string()).await;
+                self.log_error_cycle(Decimal::ZERO, &e.to_string()).await;
                 return Ok(());
             }
         };
 
-        info!(balance, "Current USDC balance");
+        info!(%balance, asset = self.exchange.quote_asset(), "Current quote balance");
 
         // 3. Check if balance is zero → bot dies
-        if balance <= 0.0 {
+        if balance <= Decimal::ZERO {
             warn!("💀 Balance is ZERO — bot is DEAD");
             queries::kill_bot(&self.pool, "Balance reached zero").await?;
             self.log_error_cycle(balance, "Balance reached zero — bot terminated").await;
@@ -73,56 +99,103 @@ impl TradingEngine {
         }
 
         // 4. Check if below minimum reserve
-        if balance < self.config.min_balance_usdc {
-            warn!(balance, min = self.config.min_balance_usdc, "Below minimum reserve — HOLD");
+        if balance < self.config.min_balance {
+            warn!(%balance, min = %self.config.min_balance, "Below minimum reserve — HOLD");
             self.log_hold_cycle(balance, "Below minimum reserve").await;
             return Ok(());
         }
 
         // 5. Check stop-loss / take-profit on existing positions
-        let positions_to_close = RiskManager::check_positions(&self.pool, &self.binance).await?;
-        for (pos, reason) in &positions_to_close {
-            info!(symbol = %pos.symbol, reason, "Closing position");
-            self.close_position(pos, reason).await?;
+        let order_guard = self.order_lock.lock().await;
+        let positions_to_close = RiskManager::check_positions(&self.pool, self.config.mode, self.exchange.as_ref()).await?;
+        for (pos, signal) in &positions_to_close {
+            match signal {
+                ExitSignal::Triggered(reason) => {
+                    info!(symbol = %pos.symbol, reason, "Closing position");
+                    self.close_position(pos, reason).await?;
+                }
+                ExitSignal::Filled { reason, order } => {
+                    self.record_exit_fill(pos, reason, order).await?;
+                }
+            }
         }
 
+        // Ratchet trailing stops up to the prices the risk check just recorded
+        for pos in queries::get_open_positions(&self.pool, self.config.mode).await? {
+            if let Some(price) = pos.current_price {
+                if let Err(e) = self.ratchet_stop(&pos, price).await {
+                    warn!(symbol = %pos.symbol, error = %e, "Failed to ratchet trailing stop");
+                }
+            }
+        }
+        drop(order_guard);
+
         // 6. Get open positions (refreshed after closures)
-        let open_positions = queries::get_open_positions(&self.pool).await?;
+        let open_positions = queries::get_open_positions(&self.pool, self.config.mode).await?;
 
         // 7. Fetch market data
-        let tickers = self.binance.get_tickers().await.unwrap_or_default();
+        let tickers = self.tradable(self.exchange.get_tickers().await.unwrap_or_default()).await;
         let fear_greed = fetch_fear_greed_index().await;
-        let consecutive_losses = queries::get_consecutive_losses(&self.pool).await.unwrap_or(0);
+        let consecutive_losses = queries::get_consecutive_losses(&self.pool, self.config.mode).await.unwrap_or(0);
+
+        let mut candle_symbols = top_symbols_by_volume(&tickers, 10);
+        for pos in &open_positions {
+            if !candle_symbols.contains(&pos.symbol) {
+                candle_symbols.push(pos.symbol.clone());
+            }
+        }
+        ingest_candles(
+            &self.pool,
+            self.exchange.as_ref(),
+            &candle_symbols,
+            &self.config.kline_intervals,
+            self.config.kline_limit,
+        )
+        .await;
 
-        // 8. Build prompt for OpenClaw
-        let prompt = build_prompt(balance, &open_positions, &tickers, fear_greed, consecutive_losses, &self.config.openclaw_user_id);
+        // 8. Context for the decision provider
+        let context = PromptContext {
+            balance,
+            quote_asset: self.exchange.quote_asset().to_string(),
+            open_positions,
+            tickers,
+            fear_greed,
+            consecutive_losses,
+        };
 
-        // 9. Send to OpenClaw and get decision
-        let raw_response = match self.discord.ask(&prompt).await {
+        // 9. Ask the decision provider
+        let provider = self.decisions.name();
+        let asked_at = std::time::Instant::now();
+        let response = match self.decisions.decide(&context).await {
             Ok(Some(resp)) => resp,
             Ok(None) => {
-                warn!("No response from OpenClaw — defaulting to HOLD");
-                self.log_hold_cycle(balance, "OpenClaw timeout").await;
+                warn!(provider, "No decision received — defaulting to HOLD");
+                self.log_hold_cycle(balance, &format!("{} timeout", provider)).await;
                 return Ok(());
             }
             Err(e) => {
-                error!(error = %e, "Discord communication error");
+                error!(provider, error = %e, "Decision provider error");
                 self.log_error_cycle(balance, &e.to_string()).await;
                 return Ok(());
             }
         };
 
-        // 10. Parse decision
-        let decision = parse_decision(&raw_response);
+        // 10. Validate the decision, re-asking once if it breaks the rules
+        let (response, decision, invalid) = self.validated_decision(&context, response, asked_at).await;
         info!(
             action = %decision.action,
             confidence = decision.confidence,
             symbol = ?decision.symbol,
+            provider = %response.provider,
+            model = ?response.model,
+            latency_ms = response.latency_ms,
             "Trading decision received"
         );
 
         // 11. Execute decision
+        let order_guard = self.order_lock.lock().await;
         let (result, error) = match decision.action {
+            TradingAction::Hold if invalid.is_some() => (Some("HOLD".to_string()), invalid),
             TradingAction::Hold => {
                 info!("📊 Decision: HOLD");
                 (Some("HOLD".to_string()), None)
@@ -130,45 +203,63 @@ impl TradingEngine {
             TradingAction::Buy => {
                 match self.execute_buy(&decision, balance).await {
                     Ok(msg) => (Some(msg), None),
-                    Err(e) => (None, Some(e.to_string())),
+                    Err(e) => (rejected_result(&e), Some(e.to_string())),
                 }
             }
             TradingAction::Sell => {
                 match self.execute_sell(&decision).await {
                     Ok(msg) => (Some(msg), None),
-                    Err(e) => (None, Some(e.to_string())),
+                    Err(e) => (rejected_result(&e), Some(e.to_string())),
                 }
             }
         };
+        drop(order_guard);
 
         // 12. Log the cycle
         let execution_ms = cycle_start.elapsed().as_millis() as i32;
-        queries::insert_cycle_log(
+        let cycle_id = queries::insert_cycle_log(
             &self.pool,
+            self.config.mode,
             balance,
             &decision.action.to_string(),
             decision.symbol.as_deref(),
             Some(decision.confidence),
             Some(&decision.reasoning),
-            Some(&raw_response),
+            Some(&response.raw),
             Some(fear_greed),
             execution_ms,
             result.as_deref(),
             error.as_deref(),
         )
         .await?;
+        queries::set_cycle_decision_source(
+            &self.pool,
+            cycle_id,
+            &response.provider,
+            response.model.as_deref(),
+            response.latency_ms,
+            (response.input_tokens, response.output_tokens),
+        )
+        .await?;
 
         // 13. Record balance snapshot
-        let updated_balance = self.binance.get_usdc_balance().await.unwrap_or(balance);
-        let open_count = queries::count_open_positions(&self.pool).await.unwrap_or(0) as i32;
-        let total_pnl = queries::get_total_pnl(&self.pool).await.unwrap_or(0.0);
-        queries::insert_balance_snapshot(&self.pool, updated_balance, open_count, total_pnl).await?;
+        let updated_balance = self.exchange.get_quote_balance().await.unwrap_or(balance);
+        let open_count = queries::count_open_positions(&self.pool, self.config.mode).await.unwrap_or(0) as i32;
+        let total_pnl = queries::get_total_pnl(&self.pool, self.config.mode).await.unwrap_or_default();
+        queries::insert_balance_snapshot(
+            &self.pool,
+            self.config.mode,
+            updated_balance,
+            self.exchange.quote_asset(),
+            open_count,
+            total_pnl,
+        ).await?;
 
         // 14. Broadcast to WebSocket subscribers
-        let cycle_count = queries::count_cycles(&self.pool).await.unwrap_or(0) as i32;
+        let cycle_count = queries::count_cycles(&self.pool, self.config.mode).await.unwrap_or(0) as i32;
         let update = CycleUpdate {
             cycle_number: cycle_count,
-            balance_usdc: updated_balance,
+            balance: updated_balance,
             action: decision.action.to_string(),
             symbol: decision.symbol,
             confidence: Some(decision.confidence),
@@ -177,89 +268,225 @@ impl TradingEngine {
             fear_greed: Some(fear_greed),
             timestamp: Utc::now(),
         };
-        let _ = self.broadcast_tx.send(update);
+        let _ = self.broadcast_tx.send(DashboardEvent::Cycle(update));
 
         info!(
             execution_ms,
-            balance = updated_balance,
+            balance = %updated_balance,
             "━━━ Cycle complete ━━━"
         );
         Ok(())
     }
 
+    /// Close a position whose stop-loss or take-profit was crossed between cycles.
+    /// Re-reads the position under the order lock so a concurrent cycle can't
+    /// sell it twice, and settles an earlier SELL whose outcome is unknown
+    /// before sending another.
+    pub async fn close_on_trigger(&self, position_id: uuid::Uuid, reason: &str) -> Result<()> {
+        let _guard = self.order_lock.lock().await;
+
+        if let Some(intent) = queries::get_unresolved_sell_intent(&self.pool, position_id).await? {
+            self.recover_order(&intent).await?;
+            if queries::get_unresolved_sell_intent(&self.pool, position_id).await?.is_some() {
+                warn!(%position_id, client_order_id = %intent.client_order_id, "Earlier SELL still unresolved — not selling again");
+                return Ok(());
+            }
+        }
+
+        let position = match queries::get_position_by_id(&self.pool, position_id).await? {
+            Some(p) if p.status == "OPEN" && p.oco_order_list_id.is_none() => p,
+            _ => return Ok(()),
+        };
+
+        info!(symbol = %position.symbol, reason, "Closing position on real-time trigger");
+        self.close_position(&position, reason).await
+    }
+
+    /// Validate the provider's answer. A rule-breaking answer is sent back
+    /// once with its violations while the decision time budget lasts; if
+    /// that doesn't yield a valid decision either, the cycle HOLDs and the
+    /// violations are returned as the cycle error.
+    async fn validated_decision(
+        &self,
+        context: &PromptContext,
+        response: DecisionResponse,
+        asked_at: std::time::Instant,
+    ) -> (DecisionResponse, TradingDecision, Option<String>) {
+        let errors = match validate_decision(&response.raw, context) {
+            Ok(decision) => return (response, decision, None),
+            Err(errors) => errors,
+        };
+        warn!(errors = ?errors, "Decision rejected by validation");
+
+        let budget = std::time::Duration::from_secs(self.config.decision_time_budget_secs);
+        let remaining = budget.saturating_sub(asked_at.elapsed());
+        let correction = if remaining.is_zero() {
+            warn!("Decision time budget spent — not asking for a correction");
+            None
+        } else {
+            info!("🔁 Asking for a corrected decision");
+            match tokio::time::timeout(remaining, self.decisions.correct(context, &response.raw, &errors)).await {
+                Ok(Ok(corrected)) => corrected,
+                Ok(Err(e)) => {
+                    error!(error = %e, "Decision provider error on correction");
+                    None
+                }
+                Err(_) => None,
+            }
+        };
+
+        let (response, errors) = match correction {
+            Some(corrected) => {
+                let response = response.followed_by(corrected);
+                match validate_decision(&response.raw, context) {
+                    Ok(decision) => {
+                        info!("✅ Corrected decision accepted");
+                        return (response, decision, None);
+                    }
+                    Err(errors) => (response, errors),
+                }
+            }
+            None => (response, errors),
+        };
+
+        let errors = errors.join("; ");
+        warn!(errors = %errors, "No valid decision — defaulting to HOLD");
+        let decision = TradingDecision {
+            action: TradingAction::Hold,
+            symbol: None,
+            confidence: 0,
+            reasoning: format!("Invalid decision — holding: {}", errors),
+            stop_loss: None,
+            take_profit: None,
+            trailing_stop: None,
+        };
+        (response, decision, Some(format!("Invalid decision: {}", errors)))
+    }
+
     /// Execute a BUY decision
-    async fn execute_buy(&self, decision: &TradingDecision, balance: f64) -> Result<String> {
-        let symbol = decision.symbol.as_ref().unwrap(); // Validated by parser
+    async fn execute_buy(&self, decision: &TradingDecision, balance: Decimal) -> Result<String> {
+        let symbol = decision.symbol.as_ref().unwrap(); // Validated before execution
 
         // Check position limits
-        if !RiskManager::can_open_position(&self.pool).await? {
+        if !RiskManager::can_open_position(&self.pool, self.config.mode).await? {
             info!("Max positions reached (2) — skipping BUY");
             return Ok("SKIPPED: max positions".to_string());
         }
 
-        // Calculate position size
-        let usdc_amount =
-            PositionSizer::calculate(balance, decision.confidence, self.config.min_balance_usdc);
+        // Only allowlisted spot symbols the exchange is trading right now
+        if let Some(reason) = self.policy_rejection(symbol).await {
+            warn!(symbol, reason = %reason, "BUY refused — symbol not in the tradable universe");
+            return Ok(format!("SKIPPED: {} {}", symbol, reason));
+        }
+        let filters = self.exchange.symbol_filters(symbol).await?;
+        if let Some(reason) = universe::status_rejection(&filters) {
+            warn!(symbol, reason = %reason, "BUY refused — symbol not trading");
+            return Ok(format!("SKIPPED: {} {}", symbol, reason));
+        }
+
+        // Calculate position size within the symbol's exchange filters
+        let quote_amount = PositionSizer::calculate(
+            balance,
+            decision.confidence,
+            self.config.min_balance,
+            filters.min_notional,
+        );
 
-        if usdc_amount <= 0.0 {
+        if quote_amount <= Decimal::ZERO {
             info!("Position size too small — skipping BUY");
             return Ok("SKIPPED: insufficient size".to_string());
         }
 
-        info!(symbol, usdc_amount, "Executing BUY");
+        info!(symbol, %quote_amount, "Executing BUY");
 
-        // Execute on Binance
-        let order = self.binance.market_buy(symbol, usdc_amount).await?;
-        let trade = order.to_executed_trade();
-
-        // Validate and enforce stop-loss
-        let stop_loss = decision.stop_loss.map(|sl| {
-            RiskManager::validate_stop_loss(trade.avg_price, sl)
-        }).unwrap_or(trade.avg_price * 0.95); // Default 5% stop-loss
-
-        // Record position
-        let position_id = queries::insert_position(
-            &self.pool,
-            symbol,
-            "BUY",
-            trade.quantity,
-            trade.avg_price,
-            Some(stop_loss),
-            decision.take_profit,
-        )
-        .await?;
+        // Execute on the exchange
+        let mut intent = order_intent(self.config.mode, symbol, "BUY", quote_amount);
+        intent.stop_loss = decision.stop_loss;
+        intent.take_profit = decision.take_profit;
+        match decision.trailing_stop {
+            Some(TrailingStop::Percent(pct)) => intent.trail_pct = Some(pct),
+            Some(TrailingStop::Atr(multiple)) => intent.trail_atr_multiple = Some(multiple),
+            None => {}
+        }
+        let order = match self.submit_order(&intent).await {
+            Ok(order) => order,
+            // The balance moved since it was read (e.g. a fee or manual trade) — not a bot fault
+            Err(e) if matches!(e.downcast_ref(), Some(BinanceError::InsufficientBalance { .. })) => {
+                warn!(symbol, %quote_amount, error = %e, "BUY refused for insufficient balance");
+                return Ok("SKIPPED: insufficient balance".to_string());
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(result) = self.unfilled_result(&intent, &order).await {
+            return Ok(result);
+        }
+        if !order.is_filled() {
+            warn!(symbol, status = %order.status, filled = %order.executed_qty, "BUY partially filled — position sized to the fill");
+        }
 
-        // Record trade
-        queries::insert_trade(
-            &self.pool,
-            Some(position_id),
-            symbol,
-            "BUY",
-            trade.quantity,
-            trade.avg_price,
-            trade.usdc_amount,
-            trade.commission,
-        )
-        .await?;
+        let trade = self
+            .open_position_from_fill(&order, decision.stop_loss, decision.take_profit, decision.trailing_stop)
+            .await?;
+        self.mark_recorded(&intent, &order).await;
 
         info!(
             symbol,
-            qty = trade.quantity,
-            price = trade.avg_price,
+            qty = %trade.quantity,
+            price = %trade.avg_price,
+            status = %order.status,
             "✅ BUY executed"
         );
         Ok(format!(
-            "BUY {} @ ${:.6} (${:.2} USDC)",
-            symbol, trade.avg_price, trade.usdc_amount
+            "BUY {} @ {:.6} ({:.2} {}{})",
+            symbol,
+            trade.avg_price,
+            trade.quote_amount,
+            self.exchange.quote_asset(),
+            if order.is_filled() { "" } else { ", partial fill" }
         ))
     }
 
+    /// Why the allow/deny lists forbid buying `symbol`, or why it is a
+    /// leveraged token, if either does
+    async fn policy_rejection(&self, symbol: &str) -> Option<String> {
+        let quote_asset = self.exchange.quote_asset();
+        if let Some(reason) = universe::policy_rejection(
+            symbol,
+            quote_asset,
+            self.config.symbol_allowlist.as_ref(),
+            &self.config.symbol_denylist,
+        ) {
+            return Some(reason);
+        }
+
+        // BTCUP only tracks BTC if exchangeInfo lists BTC; SYR isn't, so SYRUP is a plain asset
+        let underlying = symbol.strip_suffix(quote_asset).and_then(universe::leveraged_underlying)?;
+        let tracked = format!("{}{}", underlying, quote_asset);
+        self.exchange.symbol_filters(&tracked).await.ok().map(|_| "leveraged token".to_string())
+    }
+
+    /// Keep only tickers the bot may buy: allowed by the lists and in
+    /// TRADING status on the exchange, so the model never sees the rest
+    async fn tradable(&self, tickers: Vec<Ticker24h>) -> Vec<Ticker24h> {
+        let mut tradable = Vec::with_capacity(tickers.len());
+        for ticker in tickers {
+            if self.policy_rejection(&ticker.symbol).await.is_some() {
+                continue;
+            }
+            match self.exchange.symbol_filters(&ticker.symbol).await {
+                Ok(filters) if universe::status_rejection(&filters).is_none() => tradable.push(ticker),
+                _ => {}
+            }
+        }
+        tradable
+    }
+
     /// Execute a SELL decision
     async fn execute_sell(&self, decision: &TradingDecision) -> Result<String> {
         let symbol = decision.symbol.as_ref().unwrap();
 
         // Find the open position for this symbol
-        let position = match queries::get_position_by_symbol(&self.pool, symbol).await? {
+        let position = match queries::get_position_by_symbol(&self.pool, self.config.mode, symbol).await? {
             Some(p) => p,
             None => {
                 info!(symbol, "No open position to sell — skipping");
@@ -267,97 +494,1395 @@ impl TradingEngine {
             }
         };
 
-        info!(symbol, qty = position.quantity, "Executing SELL");
+        info!(symbol, qty = %position.quantity, "Executing SELL");
+
+        self.cancel_exit_orders(&position).await?;
+
+        // Execute on the exchange
+        let intent = sell_intent(self.config.mode, &position, "SELL_DECISION");
+        let order = self.submit_order(&intent).await?;
+        if let Some(result) = self.unfilled_result(&intent, &order).await {
+            return Ok(result);
+        }
+        let fill = self.record_sell_fill(&position, &order, "SELL_DECISION").await?;
+        self.mark_recorded(&intent, &order).await;
 
-        // Execute on Binance
-        let order = self.binance.market_sell(symbol, position.quantity).await?;
-        let trade = order.to_executed_trade();
+        let result_str = if !fill.closed {
+            "PARTIAL"
+        } else if fill.pnl >= Decimal::ZERO {
+            "WIN"
+        } else {
+            "LOSS"
+        };
+        info!(
+            symbol,
+            qty = %fill.trade.quantity,
+            price = %fill.trade.avg_price,
+            pnl = %fill.pnl,
+            result = result_str,
+            "✅ SELL executed"
+        );
+        Ok(format!(
+            "SELL {} @ ${:.6} (PnL: ${:.4} {})",
+            symbol, fill.trade.avg_price, fill.pnl, result_str
+        ))
+    }
 
-        // Calculate PnL
-        let pnl = (trade.avg_price - position.entry_price) * position.quantity;
+    /// Persist an order intent, then submit it under its client order ID.
+    /// If the submission fails ambiguously the order is looked up by that ID;
+    /// when the lookup can't find it (or fails) the intent stays PENDING, and
+    /// `recover_orders` decides on a later pass.
+    async fn submit_order(&self, intent: &OrderIntent) -> Result<OrderResponse> {
+        queries::insert_order_intent(&self.pool, intent).await?;
+        let client_order_id = intent.client_order_id.as_str();
 
-        // Close position
-        queries::close_position(&self.pool, position.id, pnl, "SELL_DECISION").await?;
+        let result = match intent.side.as_str() {
+            "BUY" => {
+                self.exchange
+                    .market_buy(&intent.symbol, intent.amount, client_order_id)
+                    .await
+            }
+            _ => {
+                self.exchange
+                    .market_sell(&intent.symbol, intent.amount, client_order_id)
+                    .await
+            }
+        };
+
+        let order = match result {
+            Ok(order) => order,
+            Err(e) if is_ambiguous(&e) => {
+                warn!(client_order_id, error = %e, "Order outcome unknown — looking it up");
+                match self.find_submitted_order(intent).await {
+                    Ok(Some(order)) => {
+                        info!(client_order_id, order_id = order.order_id, "Order found after failed submission");
+                        order
+                    }
+                    // Not being listed yet is no proof the order wasn't placed
+                    lookup => {
+                        let note = match lookup {
+                            Err(lookup) => format!("{} (lookup failed: {})", e, lookup),
+                            _ => format!("{} (not found yet)", e),
+                        };
+                        self.set_intent_status(client_order_id, "PENDING", None, Some(&note))
+                            .await;
+                        return Err(e.context("order outcome unknown — will reconcile next cycle"));
+                    }
+                }
+            }
+            Err(e) => {
+                self.set_intent_status(client_order_id, "FAILED", None, Some(&e.to_string()))
+                    .await;
+                return Err(e);
+            }
+        };
+
+        self.set_intent_status(client_order_id, "PLACED", Some(order.order_id as i64), None)
+            .await;
+        Ok(order)
+    }
+
+    /// Look up an order whose submission failed ambiguously, retrying while
+    /// Binance doesn't list it yet
+    async fn find_submitted_order(&self, intent: &OrderIntent) -> Result<Option<OrderResponse>> {
+        for attempt in 1..=ORDER_LOOKUP_ATTEMPTS {
+            if let Some(order) = self.exchange.query_order(&intent.symbol, &intent.client_order_id).await? {
+                return Ok(Some(order));
+            }
+            if attempt < ORDER_LOOKUP_ATTEMPTS {
+                tokio::time::sleep(ORDER_LOOKUP_BACKOFF * attempt).await;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Refuse to record an order that filled nothing. An order still working
+    /// stays PLACED for `recover_orders`; a finished one fails its intent.
+    /// Returns the cycle result to report, or `None` if something filled.
+    async fn unfilled_result(&self, intent: &OrderIntent, order: &OrderResponse) -> Option<String> {
+        if order.executed_qty > Decimal::ZERO {
+            return None;
+        }
+
+        warn!(
+            symbol = %intent.symbol,
+            side = %intent.side,
+            status = %order.status,
+            "Order filled nothing — not recording it"
+        );
+        if order.is_working() {
+            return Some(format!("PENDING: {} {} {}", intent.side, intent.symbol, order.status));
+        }
+        self.set_intent_status(
+            &intent.client_order_id,
+            "FAILED",
+            Some(order.order_id as i64),
+            Some(&format!("unfilled ({})", order.status)),
+        )
+        .await;
+        Some(format!("REJECTED: {} {} {}", intent.side, intent.symbol, order.status))
+    }
+
+    async fn set_intent_status(
+        &self,
+        client_order_id: &str,
+        status: &str,
+        exchange_order_id: Option<i64>,
+        error: Option<&str>,
+    ) {
+        if let Err(e) =
+            queries::update_order_intent(&self.pool, client_order_id, status, exchange_order_id, error)
+                .await
+        {
+            error!(error = %e, client_order_id, status, "Failed to update order intent");
+        }
+    }
+
+    /// Mark an intent done once its position/trade rows are written
+    async fn mark_recorded(&self, intent: &OrderIntent, order: &OrderResponse) {
+        self.set_intent_status(&intent.client_order_id, "RECORDED", Some(order.order_id as i64), None)
+            .await;
+    }
+
+    /// Resolve order intents left PENDING or PLACED by a crash or an ambiguous
+    /// failure: look each order up and write whatever rows are missing
+    pub async fn recover_orders(&self) -> Result<()> {
+        let _guard = self.order_lock.lock().await;
+
+        for intent in queries::get_unresolved_order_intents(&self.pool, self.config.mode).await? {
+            if let Err(e) = self.recover_order(&intent).await {
+                warn!(client_order_id = %intent.client_order_id, error = %e, "Order recovery failed — will retry");
+            }
+        }
+        Ok(())
+    }
+
+    async fn recover_order(&self, intent: &OrderIntent) -> Result<()> {
+        let client_order_id = intent.client_order_id.as_str();
+
+        let order = match self.exchange.query_order(&intent.symbol, client_order_id).await? {
+            Some(order) => order,
+            // Binance may not list a just-submitted order yet
+            None if Utc::now() - intent.created_at < chrono::Duration::minutes(ORDER_NOT_FOUND_GRACE_MINUTES) => {
+                info!(client_order_id, "Unresolved order not listed yet — will look again");
+                return Ok(());
+            }
+            None => {
+                info!(client_order_id, "Unresolved order never reached the exchange");
+                self.set_intent_status(client_order_id, "FAILED", None, Some("not found on exchange"))
+                    .await;
+                return Ok(());
+            }
+        };
+
+        if order.executed_qty <= Decimal::ZERO {
+            if order.is_working() {
+                return Ok(()); // Still working; look again next time
+            }
+            self.set_intent_status(client_order_id, "FAILED", Some(order.order_id as i64), Some(&order.status))
+                .await;
+            return Ok(());
+        }
+
+        match intent.side.as_str() {
+            "BUY" => {
+                let recorded =
+                    queries::get_trade_position_id(&self.pool, self.config.mode, &intent.symbol, order.order_id as i64)
+                        .await?
+                        .is_some();
+                if !recorded {
+                    self.open_position_from_fill(&order, intent.stop_loss, intent.take_profit, intent.trailing_stop())
+                        .await?;
+                }
+            }
+            _ => {
+                let open_position = match intent.position_id {
+                    Some(id) => queries::get_position_by_id(&self.pool, id)
+                        .await?
+                        .filter(|p| p.status == "OPEN"),
+                    None => None,
+                };
+                if let Some(position) = open_position {
+                    let reason = intent.reason.as_deref().unwrap_or("RECOVERED");
+                    self.record_sell_fill(&position, &order, reason).await?;
+                }
+            }
+        }
+
+        self.mark_recorded(intent, &order).await;
+        info!(
+            client_order_id,
+            symbol = %intent.symbol,
+            side = %intent.side,
+            order_id = order.order_id,
+            "♻️ Recovered order"
+        );
+        Ok(())
+    }
+
+    /// Compare open positions with what the exchange account holds. Every
+    /// disagreement is recorded in `discrepancies`; orphaned holdings are
+    /// adopted and phantom positions closed only when configured to.
+    /// Returns how many discrepancies remain open.
+    pub async fn reconcile(&self) -> Result<usize> {
+        let _guard = self.order_lock.lock().await;
+        let mode = self.config.mode;
+
+        let positions = queries::get_open_positions(&self.pool, mode).await?;
+        let holdings = self.exchange.get_all_balances().await?;
+        let states = reconcile::asset_states(
+            &positions,
+            &holdings,
+            self.exchange.quote_asset(),
+            &self.config.reconcile_ignore_assets,
+        );
+
+        let mut still_open = Vec::new();
+        // Assets that couldn't be checked keep whatever is already open for them
+        let mut unchecked = Vec::new();
+        for state in states {
+            // Assets with no market in the quote asset can't be valued or traded
+            let Ok(filters) = self.exchange.symbol_filters(&state.symbol).await else {
+                unchecked.extend(DiscrepancyKind::ALL.map(|k| k.key(&state.asset)));
+                continue;
+            };
+            let price = match self.exchange.get_ticker(&state.symbol).await {
+                Ok(t) => t.last_price,
+                Err(e) => {
+                    warn!(symbol = %state.symbol, error = %e, "Failed to get ticker for reconciliation");
+                    Decimal::ZERO
+                }
+            };
+            if price <= Decimal::ZERO {
+                unchecked.extend(DiscrepancyKind::ALL.map(|k| k.key(&state.asset)));
+                continue;
+            }
+
+            let Some(kind) = reconcile::classify(&state, &filters, price) else {
+                continue;
+            };
+
+            let fixed = match kind {
+                DiscrepancyKind::OrphanedHolding if self.config.reconcile_adopt_orphans => {
+                    self.adopt_holding(&state, &filters, price).await
+                }
+                DiscrepancyKind::PhantomPosition if self.config.reconcile_close_phantoms => {
+                    let phantoms: Vec<&Position> =
+                        positions.iter().filter(|p| state.positions.contains(&p.id)).collect();
+                    self.close_phantoms(&state, &phantoms).await
+                }
+                _ => Ok(false),
+            };
+            match fixed {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => warn!(asset = %state.asset, error = %e, "Failed to fix discrepancy — flagging it"),
+            }
+
+            warn!(
+                asset = %state.asset,
+                kind = kind.as_str(),
+                expected = %state.expected,
+                actual = %state.actual,
+                "⚖️ Position discrepancy"
+            );
+            queries::flag_discrepancy(
+                &self.pool,
+                mode,
+                kind.as_str(),
+                &state.asset,
+                &state.symbol,
+                state.expected,
+                state.actual,
+            )
+            .await?;
+            still_open.push(kind.key(&state.asset));
+        }
+
+        let keep: Vec<String> = still_open.iter().cloned().chain(unchecked).collect();
+        let resolved = queries::resolve_discrepancies(&self.pool, mode, &keep).await?;
+        info!(open = still_open.len(), resolved, "⚖️ Reconciliation complete");
+        Ok(still_open.len())
+    }
+
+    /// Track an untracked holding as a position entered at the current price,
+    /// with the default 5% stop-loss and no take-profit
+    async fn adopt_holding(&self, state: &AssetState, filters: &SymbolFilters, price: Decimal) -> Result<bool> {
+        let quantity = filters.quantize_qty(state.actual);
+        let stop_loss = filters.quantize_price(price * dec!(0.95));
+
+        let position_id = queries::insert_position(
+            &self.pool,
+            self.config.mode,
+            &state.symbol,
+            "BUY",
+            quantity,
+            price,
+            Decimal::ZERO,
+            Some(stop_loss),
+            None,
+        )
+        .await?;
+        queries::insert_fixed_discrepancy(
+            &self.pool,
+            self.config.mode,
+            DiscrepancyKind::OrphanedHolding.as_str(),
+            &state.asset,
+            &state.symbol,
+            position_id,
+            state.expected,
+            state.actual,
+            "ADOPTED",
+        )
+        .await?;
+
+        info!(symbol = %state.symbol, %quantity, %price, "🧲 Adopted untracked holding");
+        Ok(true)
+    }
+
+    /// Close positions for an asset the account no longer holds. Positions
+    /// with exchange-side exit orders are left to the OCO check, which books
+    /// the PnL of a filled leg.
+    async fn close_phantoms(&self, state: &AssetState, phantoms: &[&Position]) -> Result<bool> {
+        if phantoms.iter().any(|p| p.oco_order_list_id.is_some()) {
+            return Ok(false);
+        }
+
+        for position in phantoms {
+            queries::close_unsettled_position(&self.pool, position.id, "RECONCILED: not held on exchange")
+                .await?;
+            queries::insert_fixed_discrepancy(
+                &self.pool,
+                self.config.mode,
+                DiscrepancyKind::PhantomPosition.as_str(),
+                &state.asset,
+                &state.symbol,
+                position.id,
+                position.quantity,
+                state.actual,
+                "CLOSED",
+            )
+            .await?;
+            info!(symbol = %position.symbol, quantity = %position.quantity, "🧹 Closed position not held on exchange");
+        }
+        Ok(true)
+    }
+
+    /// Write the position and trade for a filled BUY and protect it with exit orders
+    async fn open_position_from_fill(
+        &self,
+        order: &OrderResponse,
+        stop_loss: Option<Decimal>,
+        take_profit: Option<Decimal>,
+        trailing_stop: Option<TrailingStop>,
+    ) -> Result<ExecutedTrade> {
+        let symbol = order.symbol.as_str();
+        let filters = self.exchange.symbol_filters(symbol).await?;
+        let trade = self.executed_trade(order).await;
+
+        // Validate and enforce stop-loss
+        let stop_loss = stop_loss.map(|sl| {
+            RiskManager::validate_stop_loss(trade.avg_price, sl)
+        }).unwrap_or(trade.avg_price * dec!(0.95)); // Default 5% stop-loss
+
+        // A trailing stop starts from the entry price and is never looser than the fixed one
+        let trail = match trailing_stop {
+            Some(requested) => self.resolve_trail(symbol, requested, trade.avg_price).await,
+            None => None,
+        };
+        let stop_loss = match trail {
+            Some(trail) => stop_loss.max(trail.stop_below(trade.avg_price)),
+            None => stop_loss,
+        };
+
+        // Keep exit prices on the symbol's tick grid so they can be placed as orders
+        let stop_loss = filters.quantize_price(stop_loss);
+        let take_profit = take_profit.map(|tp| filters.quantize_price(tp));
+
+        // Record position — only what's left after base-asset fees can be sold
+        let position_id = queries::insert_position(
+            &self.pool,
+            self.config.mode,
+            symbol,
+            "BUY",
+            trade.net_quantity,
+            trade.avg_price,
+            trade.commission_quote,
+            Some(stop_loss),
+            take_profit,
+        )
+        .await?;
+
+        if let Some(trail) = trail {
+            let (trail_pct, trail_distance) = trail.columns();
+            queries::set_position_trail(&self.pool, position_id, trail_pct, trail_distance, trade.avg_price)
+                .await?;
+        }
 
         // Record trade
         queries::insert_trade(
             &self.pool,
-            Some(position.id),
+            self.config.mode,
+            Some(position_id),
             symbol,
-            "SELL",
+            "BUY",
             trade.quantity,
             trade.avg_price,
-            trade.usdc_amount,
-            trade.commission,
+            trade.quote_amount,
+            trade.commission_quote,
+            Some(trade.order_id as i64),
         )
         .await?;
 
-        let result_str = if pnl >= 0.0 { "WIN" } else { "LOSS" };
-        info!(
-            symbol,
-            qty = trade.quantity,
-            price = trade.avg_price,
-            pnl,
-            result = result_str,
-            "✅ SELL executed"
-        );
-        Ok(format!(
-            "SELL {} @ ${:.6} (PnL: ${:.4} {})",
-            symbol, trade.avg_price, pnl, result_str
-        ))
+        // Hand exits to the exchange so they trigger between cycles
+        if let Some(take_profit) = take_profit {
+            self.place_exit_orders(position_id, symbol, trade.net_quantity, &filters, take_profit, stop_loss)
+                .await;
+        }
+
+        Ok(trade)
     }
 
-    /// Close a position triggered by risk management
-    async fn close_position(&self, position: &Position, reason: &str) -> Result<()> {
-        let order = self
-            .binance
-            .market_sell(&position.symbol, position.quantity)
-            .await?;
-        let trade = order.to_executed_trade();
+    /// Record a SELL fill against a position. A full fill closes it; a partial
+    /// one shrinks it unless the remainder is too small to ever sell. PnL is
+    /// net: the price move less exit commission and the entry commission of
+    /// the quantity sold.
+    async fn record_sell_fill(
+        &self,
+        position: &Position,
+        order: &OrderResponse,
+        reason: &str,
+    ) -> Result<SellFill> {
+        let trade = self.executed_trade(order).await;
 
-        let pnl = (trade.avg_price - position.entry_price) * position.quantity;
-        queries::close_position(&self.pool, position.id, pnl, reason).await?;
+        let sold_share = trade
+            .quantity
+            .checked_div(position.quantity)
+            .unwrap_or(Decimal::ONE)
+            .min(Decimal::ONE);
+        let entry_commission = position.entry_commission * sold_share;
+        let gross_pnl = (trade.avg_price - position.entry_price) * trade.quantity;
+        let pnl = gross_pnl - entry_commission - trade.commission_quote;
+
+        let remaining = position.quantity - trade.quantity;
+        let closed = order.is_filled() || !self.is_sellable(&position.symbol, remaining).await;
+        if closed {
+            queries::close_position(&self.pool, position.id, pnl, gross_pnl, reason).await?;
+        } else {
+            warn!(
+                symbol = %position.symbol,
+                status = %order.status,
+                sold = %trade.quantity,
+                %remaining,
+                "SELL partially filled — position stays open with the remainder"
+            );
+            queries::reduce_position(&self.pool, position.id, remaining, entry_commission, pnl, gross_pnl)
+                .await?;
+        }
 
         queries::insert_trade(
             &self.pool,
+            self.config.mode,
             Some(position.id),
             &position.symbol,
             "SELL",
             trade.quantity,
             trade.avg_price,
-            trade.usdc_amount,
-            trade.commission,
+            trade.quote_amount,
+            trade.commission_quote,
+            Some(trade.order_id as i64),
         )
         .await?;
 
+        Ok(SellFill { trade, pnl, closed })
+    }
+
+    /// Whether a leftover quantity still clears the symbol's LOT_SIZE minimum
+    async fn is_sellable(&self, symbol: &str, quantity: Decimal) -> bool {
+        match self.exchange.symbol_filters(symbol).await {
+            Ok(filters) => {
+                let quantity = filters.quantize_qty(quantity);
+                quantity > Decimal::ZERO && quantity >= filters.min_qty
+            }
+            Err(_) => quantity > Decimal::ZERO,
+        }
+    }
+
+    /// Value an order's fills in the quote asset. Order lookups carry no fills, so they
+    /// are fetched separately; if that fails only the commission is lost.
+    async fn executed_trade(&self, order: &OrderResponse) -> ExecutedTrade {
+        let mut order = order.clone();
+        if order.fills.is_empty() && order.executed_qty > Decimal::ZERO {
+            match self.exchange.get_order_fills(&order.symbol, order.order_id).await {
+                Ok(fills) => order.fills = fills,
+                Err(e) => {
+                    warn!(order_id = order.order_id, error = %e, "Failed to fetch fills — commission unknown")
+                }
+            }
+        }
+        let quote = self.exchange.quote_asset();
+        let prices = quote_prices(self.exchange.as_ref(), &order.foreign_commission_assets(quote)).await;
+        order.to_executed_trade(quote, &prices)
+    }
+
+    /// Turn a requested trailing stop into a trail for a position entered at
+    /// `entry_price`. ATR trails are measured now and kept as a fixed distance.
+    async fn resolve_trail(&self, symbol: &str, requested: TrailingStop, entry_price: Decimal) -> Option<Trail> {
+        let trail = match requested {
+            TrailingStop::Percent(pct) => Trail::Percent(pct),
+            TrailingStop::Atr(multiple) => {
+                let interval = &self.config.trailing_atr_interval;
+                let klines = match self.exchange.get_klines(symbol, interval, ATR_PERIOD as u32 + 1).await {
+                    Ok(k) => k,
+                    Err(e) => {
+                        warn!(symbol, error = %e, "Failed to fetch klines for ATR — using a fixed stop");
+                        return None;
+                    }
+                };
+                let Some(atr) = average_true_range(&klines, ATR_PERIOD) else {
+                    warn!(symbol, "Not enough klines for ATR — using a fixed stop");
+                    return None;
+                };
+                Trail::Distance(atr * multiple)
+            }
+        };
+        trail.capped(entry_price)
+    }
+
+    /// Ratchet a trailing position's stop after a new high (real-time path)
+    pub async fn trail_stop(&self, position_id: uuid::Uuid, price: Decimal) -> Result<()> {
+        let _guard = self.order_lock.lock().await;
+        match queries::get_position_by_id(&self.pool, position_id).await? {
+            Some(position) if position.status == "OPEN" => self.ratchet_stop(&position, price).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a new high for a trailing position and raise its stop. A live
+    /// OCO is replaced so the exchange-side stop follows, but only once the
+    /// stop has risen by `trailing_oco_min_move_pct`, to limit order churn.
+    async fn ratchet_stop(&self, position: &Position, price: Decimal) -> Result<()> {
+        let Some(trail) = Trail::of(position) else {
+            return Ok(());
+        };
+        let high = position.high_water_mark.unwrap_or(position.entry_price);
+        if price <= high {
+            return Ok(());
+        }
+
+        let filters = self.exchange.symbol_filters(&position.symbol).await?;
+        let stop = filters.quantize_price(trail.stop_below(price));
+        let current = position.stop_loss.unwrap_or_default();
+        let min_move = match position.oco_order_list_id {
+            Some(_) => current * self.config.trailing_oco_min_move_pct / Decimal::ONE_HUNDRED,
+            None => Decimal::ZERO,
+        };
+        if stop <= current + min_move {
+            queries::update_trailing_stop(&self.pool, position.id, price, None).await?;
+            return Ok(());
+        }
+
+        match (position.oco_order_list_id, position.take_profit) {
+            (Some(_), Some(take_profit)) => {
+                self.cancel_exit_orders(position).await?;
+                queries::update_trailing_stop(&self.pool, position.id, price, Some(stop)).await?;
+                self.place_exit_orders(position.id, &position.symbol, position.quantity, &filters, take_profit, stop)
+                    .await;
+            }
+            _ => queries::update_trailing_stop(&self.pool, position.id, price, Some(stop)).await?,
+        }
+
+        info!(symbol = %position.symbol, high = %price, from = %current, to = %stop, "📈 Trailing stop raised");
+        Ok(())
+    }
+
+    /// Place an OCO (take-profit limit + stop-loss-limit) for a freshly bought position.
+    /// Failure is logged, not fatal: the cycle keeps evaluating the stops itself.
+    async fn place_exit_orders(
+        &self,
+        position_id: uuid::Uuid,
+        symbol: &str,
+        quantity: Decimal,
+        filters: &SymbolFilters,
+        take_profit: Decimal,
+        stop_loss: Decimal,
+    ) {
+        let stop_limit = filters.quantize_price(
+            stop_loss * (Decimal::ONE - self.config.oco_stop_limit_buffer_pct / Decimal::ONE_HUNDRED),
+        );
+
+        let oco = match self
+            .exchange
+            .place_oco_sell(symbol, quantity, take_profit, stop_loss, stop_limit)
+            .await
+        {
+            Ok(oco) => oco,
+            Err(e) => {
+                warn!(symbol, error = %e, "OCO placement failed — stops will be checked each cycle");
+                return;
+            }
+        };
+
+        if let Err(e) = queries::set_position_oco(
+            &self.pool,
+            position_id,
+            oco.order_list_id,
+            oco.take_profit_order_id as i64,
+            oco.stop_loss_order_id as i64,
+        )
+        .await
+        {
+            error!(error = %e, order_list_id = oco.order_list_id, "Failed to record OCO on position");
+        }
+    }
+
+    /// Cancel a position's OCO exits before selling it another way
+    async fn cancel_exit_orders(&self, position: &Position) -> Result<()> {
+        if let Some(order_list_id) = position.oco_order_list_id {
+            self.exchange
+                .cancel_oco(&position.symbol, order_list_id)
+                .await?;
+            queries::clear_position_oco(&self.pool, position.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Record a position sold by one of its OCO legs on the exchange
+    async fn record_exit_fill(
+        &self,
+        position: &Position,
+        reason: &str,
+        order: &OrderResponse,
+    ) -> Result<()> {
+        let fill = self.record_sell_fill(position, order, reason).await?;
+
+        info!(
+            symbol = %position.symbol,
+            reason,
+            pnl = %fill.pnl,
+            "Position closed by exchange exit order"
+        );
+        Ok(())
+    }
+
+    /// Close a position triggered by risk management
+    async fn close_position(&self, position: &Position, reason: &str) -> Result<()> {
+        self.cancel_exit_orders(position).await?;
+
+        let intent = sell_intent(self.config.mode, position, reason);
+        let order = self.submit_order(&intent).await?;
+        if self.unfilled_result(&intent, &order).await.is_some() {
+            return Ok(()); // Still open; the next check retries
+        }
+        let fill = self.record_sell_fill(position, &order, reason).await?;
+        self.mark_recorded(&intent, &order).await;
+
         info!(
             symbol = %position.symbol,
             reason,
-            pnl,
+            pnl = %fill.pnl,
+            closed = fill.closed,
             "Position closed by risk manager"
         );
         Ok(())
     }
 
     /// Log a HOLD cycle (for timeouts, low balance, etc.)
-    async fn log_hold_cycle(&self, balance: f64, reason: &str) {
+    async fn log_hold_cycle(&self, balance: Decimal, reason: &str) {
         let _ = queries::insert_cycle_log(
-            &self.pool, balance, "HOLD", None, None,
+            &self.pool,
+            self.config.mode, balance, "HOLD", None, None,
             Some(reason), None, None, 0, Some("HOLD"), None,
         )
         .await;
 
-        let open_count = queries::count_open_positions(&self.pool).await.unwrap_or(0) as i32;
-        let total_pnl = queries::get_total_pnl(&self.pool).await.unwrap_or(0.0);
-        let _ = queries::insert_balance_snapshot(&self.pool, balance, open_count, total_pnl).await;
+        let open_count = queries::count_open_positions(&self.pool, self.config.mode).await.unwrap_or(0) as i32;
+        let total_pnl = queries::get_total_pnl(&self.pool, self.config.mode).await.unwrap_or_default();
+        let _ = queries::insert_balance_snapshot(
+            &self.pool,
+            self.config.mode,
+            balance,
+            self.exchange.quote_asset(),
+            open_count,
+            total_pnl,
+        )
+        .await;
     }
 
     /// Log an error cycle
-    async fn log_error_cycle(&self, balance: f64, error: &str) {
+    async fn log_error_cycle(&self, balance: Decimal, error: &str) {
         let _ = queries::insert_cycle_log(
-            &self.pool, balance, "ERROR", None, None,
+            &self.pool,
+            self.config.mode, balance, "ERROR", None, None,
             None, None, None, 0, None, Some(error),
         )
         .await;
     }
 }
+
+/// A SELL fill recorded against a position
+struct SellFill {
+    trade: ExecutedTrade,
+    /// Net PnL realized by this fill
+    pnl: Decimal,
+    /// False when a partial fill left the position open
+    closed: bool,
+}
+
+/// A new PENDING market order with a fresh client order ID
+fn order_intent(mode: TradingMode, symbol: &str, side: &str, amount: Decimal) -> OrderIntent {
+    let now = Utc::now();
+    OrderIntent {
+        client_order_id: new_client_order_id(),
+        mode: mode.to_string(),
+        symbol: symbol.to_string(),
+        side: side.to_string(),
+        amount,
+        position_id: None,
+        stop_loss: None,
+        take_profit: None,
+        reason: None,
+        trail_pct: None,
+        trail_atr_multiple: None,
+        status: "PENDING".to_string(),
+        exchange_order_id: None,
+        error: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// A market SELL of a whole position
+fn sell_intent(mode: TradingMode, position: &Position, reason: &str) -> OrderIntent {
+    let mut intent = order_intent(mode, &position.symbol, "SELL", position.quantity);
+    intent.position_id = Some(position.id);
+    intent.reason = Some(reason.to_string());
+    intent
+}
+
+/// Cycle result for an order the exchange refused outright
+fn rejected_result(e: &anyhow::Error) -> Option<String> {
+    match e.downcast_ref::<BinanceError>() {
+        Some(BinanceError::Rejected { code, .. }) => Some(format!("REJECTED: {}", code)),
+        _ => None,
+    }
+}
+
+/// Whether an order submission error leaves it unknown if the order exists
+fn is_ambiguous(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<BinanceError>()
+        .map(BinanceError::is_ambiguous)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::mock::{Failure, MockBinance};
+    use crate::db::test_db;
+    use crate::decision::mock::{MockLlm, MockProvider};
+    use crate::openclaw::mock::MockDiscord;
+    use axum::http::Method;
+
+    const BUY_SOL: &str = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":95,"reasoning":"test","stop_loss":96.0,"take_profit":110.0}"#;
+
+    const BUY_ETH: &str = r#"{"action":"BUY","symbol":"ETHUSDC","confidence":95,"reasoning":"test","stop_loss":1950.0,"take_profit":2200.0}"#;
+
+    fn test_config(mock: &MockBinance) -> Config {
+        Config {
+            database_url: String::new(),
+            binance_api_key: crate::binance::mock::API_KEY.to_string(),
+            binance_secret_key: crate::binance::mock::SECRET_KEY.to_string(),
+            binance_base_url: mock.base_url.clone(),
+            binance_stream_url: String::new(),
+            binance_recv_window_ms: 5000,
+            binance_time_sync_secs: 300,
+            binance_weight_budget: 6000,
+            mode: TradingMode::Testnet,
+            quote_asset: "USDC".to_string(),
+            paper_starting_balance: Decimal::ZERO,
+            paper_fee_rate: Decimal::ZERO,
+            paper_slippage_bps: Decimal::ZERO,
+            decision_provider: crate::config::DecisionProviderKind::Discord,
+            decision_time_budget_secs: 30,
+            discord_bot_token: String::new(),
+            discord_channel_id: crate::openclaw::mock::CHANNEL_ID.to_string(),
+            openclaw_user_id: crate::openclaw::mock::OPENCLAW_USER_ID.to_string(),
+            discord_gateway: false,
+            discord_gateway_url: String::new(),
+            llm_api: crate::config::LlmApi::Messages,
+            llm_base_url: String::new(),
+            llm_api_key: String::new(),
+            llm_model: String::new(),
+            llm_max_tokens: 1024,
+            llm_timeout_secs: 5,
+            api_host: String::new(),
+            api_port: 0,
+            min_balance: dec!(5),
+            oco_stop_limit_buffer_pct: dec!(0.5),
+            trailing_atr_interval: "1h".to_string(),
+            trailing_oco_min_move_pct: dec!(0.5),
+            symbol_allowlist: Some(["SOL".to_string(), "ETH".to_string()].into()),
+            symbol_denylist: ["ETH".to_string()].into(),
+            reconcile_interval_secs: 900,
+            reconcile_adopt_orphans: false,
+            reconcile_close_phantoms: false,
+            reconcile_ignore_assets: vec!["BNB".to_string()],
+            kline_intervals: vec!["1h".to_string()],
+            kline_limit: 10,
+            kill_secret: String::new(),
+        }
+    }
+
+    /// An engine trading SOLUSDC at 100 against a mock exchange holding 1000 USDC
+    async fn setup(pool: PgPool, reply: &str) -> (TradingEngine, MockBinance, MockDiscord) {
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("SOLUSDC", dec!(100));
+        let discord = MockDiscord::start().await;
+        discord.set_reply(reply);
+
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool,
+            Arc::new(mock.client()),
+            Arc::new(discord.client()),
+            broadcast::channel(16).0,
+        );
+        (engine, mock, discord)
+    }
+
+    async fn intent_statuses(pool: &PgPool) -> Vec<String> {
+        sqlx::query_scalar("SELECT status FROM order_intents ORDER BY created_at")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_buys_and_places_exits() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, discord) = setup(pool.clone(), BUY_SOL).await;
+
+        engine.run_cycle().await.unwrap();
+
+        assert!(discord.prompts()[0].starts_with("<@mock-openclaw> "));
+        assert!(discord.prompts()[0].contains("Available USDC Balance:** 1000.00"));
+        // 10% of the 995 above the reserve buys 0.995 SOL; 0.1% of it goes to fees
+        let positions = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        let position = &positions[0];
+        assert_eq!(position.mode, "testnet");
+        assert_eq!(position.quantity, dec!(0.994005));
+        assert_eq!(position.entry_price, dec!(100));
+        assert_eq!(position.entry_commission, dec!(0.0995));
+        assert!(position.oco_order_list_id.is_some());
+        assert_eq!(mock.balance("USDC"), dec!(900.5));
+
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert!(cycles[0].result.as_deref().unwrap().starts_with("BUY SOLUSDC"));
+        assert_eq!(cycles[0].decision_provider.as_deref(), Some("discord"));
+        assert_eq!(queries::count_trades(&pool, TradingMode::Testnet).await.unwrap(), 1);
+        assert_eq!(intent_statuses(&pool).await, ["RECORDED"]);
+        let history = queries::get_balance_history(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(history[0].balance, dec!(900.5));
+
+        // Nothing leaks into another mode's history
+        assert_eq!(queries::count_cycles(&pool, TradingMode::Live).await.unwrap(), 0);
+        assert!(mock.requests().contains(&"POST /api/v3/orderList/oco".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_with_another_decision_provider() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("SOLUSDC", dec!(100));
+        let provider = MockProvider::new(Some(BUY_SOL));
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(provider.clone()),
+            broadcast::channel(16).0,
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        let context = &provider.contexts()[0];
+        assert_eq!(context.balance, dec!(1000));
+        assert_eq!(context.quote_asset, "USDC");
+        assert_eq!(context.tickers.len(), 1);
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert!(cycles[0].result.as_deref().unwrap().starts_with("BUY SOLUSDC"));
+        assert_eq!(cycles[0].decision_provider.as_deref(), Some("mock"));
+        assert_eq!(cycles[0].decision_model.as_deref(), Some("scripted"));
+
+        // No answer means HOLD
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(MockProvider::new(None)),
+            broadcast::channel(16).0,
+        );
+        engine.run_cycle().await.unwrap();
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(cycles[0].action, "HOLD");
+        assert_eq!(cycles[0].reasoning.as_deref(), Some("mock timeout"));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_records_llm_token_usage() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("SOLUSDC", dec!(100));
+        let llm = MockLlm::start().await;
+        llm.set_decision(serde_json::from_str(BUY_SOL).unwrap());
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(llm.client(crate::config::LlmApi::ChatCompletions)),
+            broadcast::channel(16).0,
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert!(cycles[0].result.as_deref().unwrap().starts_with("BUY SOLUSDC"));
+        assert_eq!(cycles[0].decision_provider.as_deref(), Some("llm"));
+        assert_eq!(cycles[0].decision_model.as_deref(), Some(crate::decision::mock::LLM_MODEL));
+        assert_eq!(cycles[0].decision_input_tokens, Some(1200));
+        assert_eq!(cycles[0].decision_output_tokens, Some(80));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_reasks_once_for_an_invalid_decision() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("SOLUSDC", dec!(100));
+        let bad = r#"{"action":"BUY","symbol":"SOLUSDC","confidence":120,"reasoning":"test","stop_loss":101.0}"#;
+        let provider = MockProvider::new(Some(bad)).with_correction(BUY_SOL);
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(provider.clone()),
+            broadcast::channel(16).0,
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        assert_eq!(
+            provider.corrections(),
+            [[
+                "confidence 120 is outside 0-100",
+                "stop_loss 101 must be below the current SOLUSDC price 100",
+                "take_profit is required for BUY",
+            ]]
+        );
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert!(cycles[0].result.as_deref().unwrap().starts_with("BUY SOLUSDC"));
+        assert_eq!(cycles[0].error, None);
+
+        // A correction that is still invalid means HOLD, with the violations kept
+        let engine = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(MockProvider::new(Some("I would buy SOL"))),
+            broadcast::channel(16).0,
+        );
+        engine.run_cycle().await.unwrap();
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(cycles[0].action, "HOLD");
+        assert_eq!(cycles[0].confidence, Some(0));
+        assert!(cycles[0].error.as_deref().unwrap().starts_with("Invalid decision: the answer is not a decision"));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_recovers_lost_order_response() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.fail_next(
+            Method::POST,
+            "/api/v3/order",
+            Failure::new(503, -1001, "Internal error; unable to process your request.").after_executing(),
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        // The order filled even though its response was lost; it is found by client order ID
+        let positions = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, dec!(0.994005));
+        assert_eq!(intent_statuses(&pool).await, ["RECORDED"]);
+        let orders = mock.requests().iter().filter(|r| *r == "POST /api/v3/order").count();
+        assert_eq!(orders, 1, "order placement must not be retried");
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_keeps_looking_for_an_order_binance_lists_late() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        let timeout = "Timeout waiting for response from backend server. Send status unknown; execution status unknown.";
+        mock.fail_next(Method::POST, "/api/v3/order", Failure::new(503, -1007, timeout).after_executing());
+        mock.fail_next(Method::GET, "/api/v3/order", Failure::new(400, -2013, "Order does not exist."));
+
+        engine.run_cycle().await.unwrap();
+
+        // The order filled, but the first lookup didn't list it yet
+        let positions = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, dec!(0.994005));
+        assert_eq!(intent_statuses(&pool).await, ["RECORDED"]);
+        let lookups = mock.requests().iter().filter(|r| *r == "GET /api/v3/order").count();
+        assert_eq!(lookups, 2);
+    }
+
+    #[tokio::test]
+    async fn test_order_missing_from_every_lookup_is_left_for_recovery() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.fail_next(
+            Method::POST,
+            "/api/v3/order",
+            Failure::new(503, -1001, "Internal error; unable to process your request.").after_executing(),
+        );
+        for _ in 0..ORDER_LOOKUP_ATTEMPTS {
+            mock.fail_next(Method::GET, "/api/v3/order", Failure::new(400, -2013, "Order does not exist."));
+        }
+
+        engine.run_cycle().await.unwrap();
+
+        // An ambiguous failure never fails the intent outright
+        assert!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().is_empty());
+        assert_eq!(intent_statuses(&pool).await, ["PENDING"]);
+
+        engine.recover_orders().await.unwrap();
+        let positions = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].stop_loss, Some(dec!(96)));
+        assert_eq!(intent_statuses(&pool).await, ["RECORDED"]);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_does_not_resell_while_a_sell_is_unresolved() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.set_balance("SOL", dec!(1));
+        let position_id = queries::insert_position(
+            &pool, TradingMode::Testnet, "SOLUSDC", "BUY", dec!(1), dec!(110), Decimal::ZERO, Some(dec!(104)), None,
+        )
+        .await
+        .unwrap();
+        mock.fail_next(
+            Method::POST,
+            "/api/v3/order",
+            Failure::new(503, -1001, "Internal error; unable to process your request."),
+        );
+
+        assert!(engine.close_on_trigger(position_id, "STOP_LOSS").await.is_err());
+        assert_eq!(intent_statuses(&pool).await, ["PENDING"]);
+
+        // The next tick finds the earlier SELL unsettled and leaves the position alone
+        engine.close_on_trigger(position_id, "STOP_LOSS").await.unwrap();
+        let sells = mock.requests().iter().filter(|r| *r == "POST /api/v3/order").count();
+        assert_eq!(sells, 1);
+        assert_eq!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().len(), 1);
+
+        // Once it is known to have failed, the stop fires again
+        sqlx::query("UPDATE order_intents SET created_at = created_at - INTERVAL '10 minutes'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        engine.close_on_trigger(position_id, "STOP_LOSS").await.unwrap();
+        assert!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().is_empty());
+        assert_eq!(intent_statuses(&pool).await, ["FAILED", "RECORDED"]);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_fails_an_unlisted_order_only_after_the_grace_period() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.fail_next(
+            Method::POST,
+            "/api/v3/order",
+            Failure::new(503, -1001, "Internal error; unable to process your request."),
+        );
+
+        engine.run_cycle().await.unwrap();
+        assert_eq!(intent_statuses(&pool).await, ["PENDING"]);
+
+        // Never placed, but too recent to be sure of that
+        engine.recover_orders().await.unwrap();
+        assert_eq!(intent_statuses(&pool).await, ["PENDING"]);
+
+        sqlx::query("UPDATE order_intents SET created_at = created_at - INTERVAL '10 minutes'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        engine.recover_orders().await.unwrap();
+        assert_eq!(intent_statuses(&pool).await, ["FAILED"]);
+        assert!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().is_empty());
+    }
+
+    /// Answers BUY_SOL, but the quote balance is spent while it "thinks",
+    /// so the order finds less than the cycle read
+    struct SpendingProvider {
+        exchange: MockBinance,
+        paper_ledger: Option<PgPool>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::decision::DecisionProvider for SpendingProvider {
+        fn name(&self) -> &str {
+            "spending"
+        }
+
+        async fn decide(&self, context: &PromptContext) -> Result<Option<DecisionResponse>> {
+            self.exchange.set_balance("USDC", dec!(1));
+            if let Some(pool) = &self.paper_ledger {
+                sqlx::query("UPDATE paper_balances SET free = 1 WHERE asset = 'USDC'").execute(pool).await?;
+            }
+            MockProvider::new(Some(BUY_SOL)).decide(context).await
+        }
+
+        async fn correct(&self, _: &PromptContext, _: &str, _: &[String]) -> Result<Option<DecisionResponse>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paper_and_live_skip_a_buy_the_balance_no_longer_covers() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("SOLUSDC", dec!(100));
+
+        let live = TradingEngine::new(
+            Arc::new(test_config(&mock)),
+            pool.clone(),
+            Arc::new(mock.client()),
+            Arc::new(SpendingProvider { exchange: mock.clone(), paper_ledger: None }),
+            broadcast::channel(16).0,
+        );
+        live.run_cycle().await.unwrap();
+
+        let paper_exchange = crate::exchange::PaperExchange::new(pool.clone(), mock.client(), Decimal::ZERO, Decimal::ZERO);
+        paper_exchange.seed(dec!(1000)).await.unwrap();
+        let mut config = test_config(&mock);
+        config.mode = TradingMode::Paper;
+        let paper = TradingEngine::new(
+            Arc::new(config),
+            pool.clone(),
+            Arc::new(paper_exchange),
+            Arc::new(SpendingProvider { exchange: mock.clone(), paper_ledger: Some(pool.clone()) }),
+            broadcast::channel(16).0,
+        );
+        paper.run_cycle().await.unwrap();
+
+        for mode in [TradingMode::Testnet, TradingMode::Paper] {
+            let cycles = queries::get_recent_cycles(&pool, mode, 1).await.unwrap();
+            assert_eq!(cycles[0].result.as_deref(), Some("SKIPPED: insufficient balance"), "{}", mode);
+            assert_eq!(cycles[0].error, None);
+            assert!(queries::get_open_positions(&pool, mode).await.unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_reports_rejected_order() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, _discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.fail_next(
+            Method::POST,
+            "/api/v3/order",
+            Failure::new(400, -1013, "Filter failure: NOTIONAL"),
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        assert!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().is_empty());
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(cycles[0].result.as_deref(), Some("REJECTED: -1013"));
+        assert!(cycles[0].error.as_deref().unwrap().contains("NOTIONAL"));
+        assert_eq!(intent_statuses(&pool).await, ["FAILED"]);
+        assert_eq!(mock.balance("USDC"), dec!(1000));
+    }
+
+    #[tokio::test]
+    async fn test_trailing_stop_ratchets_and_replaces_oco() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let reply = BUY_SOL.replace("\"take_profit\":110.0", "\"take_profit\":130.0,\"trailing_stop\":{\"percent\":3}");
+        let (engine, mock, _discord) = setup(pool.clone(), &reply).await;
+
+        engine.run_cycle().await.unwrap();
+
+        // The 3% trail from the entry is tighter than the requested 96 stop
+        let position = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().remove(0);
+        assert_eq!(position.trail_pct, Some(dec!(3)));
+        assert_eq!(position.high_water_mark, Some(dec!(100)));
+        assert_eq!(position.stop_loss, Some(dec!(97)));
+        let first_oco = position.oco_order_list_id.unwrap();
+
+        engine.trail_stop(position.id, dec!(120)).await.unwrap();
+        let raised = queries::get_position_by_id(&pool, position.id).await.unwrap().unwrap();
+        assert_eq!(raised.high_water_mark, Some(dec!(120)));
+        assert_eq!(raised.stop_loss, Some(dec!(116.4)));
+        assert_ne!(raised.oco_order_list_id, Some(first_oco));
+        assert!(raised.oco_order_list_id.is_some());
+        assert!(mock.requests().contains(&"DELETE /api/v3/orderList".to_string()));
+
+        // A pullback never lowers the stop
+        engine.trail_stop(position.id, dec!(118)).await.unwrap();
+        let held = queries::get_position_by_id(&pool, position.id).await.unwrap().unwrap();
+        assert_eq!(held.stop_loss, Some(dec!(116.4)));
+        assert_eq!(held.high_water_mark, Some(dec!(120)));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_refuses_untradable_symbols() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, discord) = setup(pool.clone(), BUY_ETH).await;
+        mock.set_price("ETHUSDC", dec!(2000));
+        mock.set_price("BTCUPUSDC", dec!(10));
+
+        engine.run_cycle().await.unwrap();
+
+        // Denylisted and leveraged pairs are neither offered to the model nor bought
+        let prompt = &discord.prompts()[0];
+        assert!(prompt.contains("• SOLUSDC"));
+        assert!(!prompt.contains("• ETHUSDC"));
+        assert!(!prompt.contains("• BTCUPUSDC"));
+        assert_eq!(discord.prompts().len(), 2);
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(cycles[0].result.as_deref(), Some("HOLD"));
+        assert_eq!(cycles[0].error.as_deref(), Some("Invalid decision: ETHUSDC is not one of the listed pairs"));
+        assert!(queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap().is_empty());
+        assert!(!mock.requests().contains(&"POST /api/v3/order".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_leveraged_tokens_need_their_tracked_asset_listed() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_price("BTCUSDC", dec!(50000));
+        mock.set_price("BTCUPUSDC", dec!(10));
+        mock.set_price("SYRUPUSDC", dec!(0.5));
+        let discord = MockDiscord::start().await;
+        discord.set_reply(r#"{"action":"HOLD","confidence":50,"reasoning":"test"}"#);
+        let config = Config { symbol_allowlist: None, ..test_config(&mock) };
+        let engine = TradingEngine::new(
+            Arc::new(config),
+            pool,
+            Arc::new(mock.client()),
+            Arc::new(discord.client()),
+            broadcast::channel(16).0,
+        );
+
+        engine.run_cycle().await.unwrap();
+
+        // BTCUP tracks the listed BTC; SYRUP is a spot asset, as no SYR is listed
+        let prompt = &discord.prompts()[0];
+        assert!(prompt.contains("• BTCUSDC"));
+        assert!(prompt.contains("• SYRUPUSDC"));
+        assert!(!prompt.contains("• BTCUPUSDC"));
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_refuses_symbol_in_break() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock, discord) = setup(pool.clone(), BUY_SOL).await;
+        mock.set_status("SOLUSDC", "BREAK");
+
+        engine.run_cycle().await.unwrap();
+
+        assert!(!discord.prompts()[0].contains("• SOLUSDC"));
+        let cycles = queries::get_recent_cycles(&pool, TradingMode::Testnet, 1).await.unwrap();
+        assert_eq!(cycles[0].result.as_deref(), Some("HOLD"));
+        assert_eq!(cycles[0].error.as_deref(), Some("Invalid decision: SOLUSDC is not one of the listed pairs"));
+        assert_eq!(mock.balance("USDC"), dec!(1000));
+    }
+
+    /// A testnet engine whose exchange holds 2 SOL with no position for it,
+    /// and an open ETH position the exchange holds none of
+    async fn setup_discrepancies(pool: PgPool, fix: bool) -> (TradingEngine, MockBinance) {
+        let mock = MockBinance::start().await;
+        mock.set_balance("USDC", dec!(1000));
+        mock.set_balance("SOL", dec!(2));
+        mock.set_balance("BNB", dec!(0.5));
+        mock.set_price("SOLUSDC", dec!(100));
+        mock.set_price("ETHUSDC", dec!(2000));
+        mock.set_price("BNBUSDC", dec!(500));
+        queries::insert_position(
+            &pool, TradingMode::Testnet, "ETHUSDC", "BUY", dec!(0.1), dec!(2000), Decimal::ZERO, None, None,
+        )
+        .await
+        .unwrap();
+
+        let mut config = test_config(&mock);
+        config.reconcile_adopt_orphans = fix;
+        config.reconcile_close_phantoms = fix;
+        let engine = TradingEngine::new(
+            Arc::new(config),
+            pool,
+            Arc::new(mock.client()),
+            Arc::new(MockProvider::new(None)),
+            broadcast::channel(16).0,
+        );
+        (engine, mock)
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_flags_and_resolves_discrepancies() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, mock) = setup_discrepancies(pool.clone(), false).await;
+
+        assert_eq!(engine.reconcile().await.unwrap(), 2);
+        // A second pass refreshes the open rows instead of adding new ones
+        assert_eq!(engine.reconcile().await.unwrap(), 2);
+
+        let open = queries::get_discrepancies(&pool, TradingMode::Testnet, true, 10).await.unwrap();
+        let mut kinds: Vec<(&str, &str)> = open.iter().map(|d| (d.kind.as_str(), d.asset.as_str())).collect();
+        kinds.sort();
+        assert_eq!(kinds, [("ORPHANED_HOLDING", "SOL"), ("PHANTOM_POSITION", "ETH")]);
+        assert!(open.iter().all(|d| d.action == "FLAGGED"));
+        // Flagging alone changes no positions
+        assert_eq!(queries::count_open_positions(&pool, TradingMode::Testnet).await.unwrap(), 1);
+
+        // Selling the stray SOL by hand clears that discrepancy on the next pass
+        mock.set_balance("SOL", Decimal::ZERO);
+        assert_eq!(engine.reconcile().await.unwrap(), 1);
+        let all = queries::get_discrepancies(&pool, TradingMode::Testnet, false, 10).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let sol = all.iter().find(|d| d.asset == "SOL").unwrap();
+        assert!(sol.resolved_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_adopts_orphans_and_closes_phantoms() {
+        let Some((_guard, pool)) = test_db::connect().await else { return };
+        let (engine, _mock) = setup_discrepancies(pool.clone(), true).await;
+
+        assert_eq!(engine.reconcile().await.unwrap(), 0);
+
+        let positions = queries::get_open_positions(&pool, TradingMode::Testnet).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].symbol, "SOLUSDC");
+        assert_eq!(positions[0].quantity, dec!(2));
+        assert_eq!(positions[0].stop_loss, Some(dec!(95)));
+
+        let all = queries::get_discrepancies(&pool, TradingMode::Testnet, false, 10).await.unwrap();
+        let mut actions: Vec<&str> = all.iter().map(|d| d.action.as_str()).collect();
+        actions.sort();
+        assert_eq!(actions, ["ADOPTED", "CLOSED"]);
+        assert!(all.iter().all(|d| d.resolved_at.is_some()));
+
+        // The adopted position now matches the holding
+        assert_eq!(engine.reconcile().await.unwrap(), 0);
+    }
+}
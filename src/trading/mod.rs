@@ -1,7 +1,11 @@
 pub mod engine;
+pub mod monitor;
+pub mod reconcile;
 pub mod risk;
 pub mod strategy;
+pub mod universe;
 
 pub use engine::TradingEngine;
-pub use risk::RiskManager;
+pub use monitor::PriceMonitor;
+pub use risk::{ExitSignal, RiskManager};
 pub use strategy::PositionSizer;
@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, info, warn};
+
+use crate::binance::streams::{market_stream_url, parse_market_event, MarketEvent};
+use crate::db::models::{DashboardEvent, Position, PriceTick};
+use crate::config::TradingMode;
+use crate::db::queries;
+use crate::trading::risk::Trail;
+use crate::trading::{RiskManager, TradingEngine};
+
+/// How often the set of open positions is re-read from the database
+const POSITION_REFRESH: Duration = Duration::from_secs(15);
+/// Minimum gap between `positions.current_price` writes for one symbol
+const PRICE_PERSIST_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay before reconnecting after the stream drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Real-time price monitor: streams miniTicker/bookTicker for every open
+/// position and fires stop-loss/take-profit exits the moment a price
+/// crosses, instead of waiting for the next cycle.
+pub struct PriceMonitor {
+    engine: Arc<TradingEngine>,
+    pool: PgPool,
+    mode: TradingMode,
+    stream_base: String,
+    broadcast_tx: broadcast::Sender<DashboardEvent>,
+}
+
+impl PriceMonitor {
+    pub fn new(
+        engine: Arc<TradingEngine>,
+        pool: PgPool,
+        mode: TradingMode,
+        stream_base: &str,
+        broadcast_tx: broadcast::Sender<DashboardEvent>,
+    ) -> Self {
+        Self {
+            engine,
+            pool,
+            mode,
+            stream_base: stream_base.to_string(),
+            broadcast_tx,
+        }
+    }
+
+    /// Run forever, resubscribing whenever the set of open positions changes
+    pub async fn run(self) {
+        info!("📡 Price monitor started");
+
+        loop {
+            let positions = match queries::get_open_positions(&self.pool, self.mode).await {
+                Ok(p) => p,
+                Err(e) => {
+                    warn!(error = %e, "Price monitor failed to load positions");
+                    tokio::time::sleep(POSITION_REFRESH).await;
+                    continue;
+                }
+            };
+
+            if positions.is_empty() {
+                tokio::time::sleep(POSITION_REFRESH).await;
+                continue;
+            }
+
+            if let Err(e) = self.watch(positions).await {
+                warn!(error = %e, "Market stream dropped — reconnecting");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+
+    /// Stream prices for the given positions. Returns `Ok` when the set of
+    /// symbols changes and the subscription needs rebuilding.
+    async fn watch(&self, positions: Vec<Position>) -> Result<()> {
+        let symbols = symbol_set(&positions);
+        let url = market_stream_url(&self.stream_base, &symbols);
+
+        let (mut ws, _) = connect_async(url.as_str())
+            .await
+            .context("Failed to connect to market stream")?;
+        info!(symbols = ?symbols, "Subscribed to market streams");
+
+        let mut by_symbol = group_by_symbol(positions);
+        let mut last_persist: HashMap<String, Instant> = HashMap::new();
+        let mut refresh = tokio::time::interval(POSITION_REFRESH);
+        refresh.tick().await; // First tick fires immediately
+
+        loop {
+            tokio::select! {
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Some(event) = parse_market_event(&text) {
+                            self.handle_event(event, &mut by_symbol, &mut last_persist).await;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => anyhow::bail!("Market stream closed"),
+                    Some(Err(e)) => return Err(e.into()),
+                    _ => {}
+                },
+                _ = refresh.tick() => {
+                    let fresh = queries::get_open_positions(&self.pool, self.mode).await?;
+                    if symbol_set(&fresh) != symbols {
+                        info!("Open positions changed — resubscribing");
+                        return Ok(());
+                    }
+                    by_symbol = group_by_symbol(fresh);
+                }
+            }
+        }
+    }
+
+    async fn handle_event(
+        &self,
+        event: MarketEvent,
+        by_symbol: &mut HashMap<String, Vec<Position>>,
+        last_persist: &mut HashMap<String, Instant>,
+    ) {
+        match event {
+            MarketEvent::MiniTicker { symbol, close } => {
+                let _ = self.broadcast_tx.send(DashboardEvent::PriceTick(PriceTick {
+                    symbol: symbol.clone(),
+                    price: close,
+                    timestamp: Utc::now(),
+                }));
+
+                let due = last_persist
+                    .get(&symbol)
+                    .map(|t| t.elapsed() >= PRICE_PERSIST_INTERVAL)
+                    .unwrap_or(true);
+                if due {
+                    for pos in by_symbol.get_mut(&symbol).into_iter().flatten() {
+                        let _ = queries::update_position_price(&self.pool, pos.id, close).await;
+                        self.trail(pos, close);
+                    }
+                    last_persist.insert(symbol.clone(), Instant::now());
+                }
+
+                self.evaluate(&symbol, close, by_symbol);
+            }
+            // The best bid is what a market sell would actually get
+            MarketEvent::BookTicker { symbol, bid } => self.evaluate(&symbol, bid, by_symbol),
+        }
+    }
+
+    /// Ratchet a trailing position's stop when the price makes a new high.
+    /// The local high is bumped so the same high isn't sent twice; the raised
+    /// stop itself arrives with the next position refresh.
+    fn trail(&self, pos: &mut Position, price: Decimal) {
+        if Trail::of(pos).is_none() || price <= pos.high_water_mark.unwrap_or(pos.entry_price) {
+            return;
+        }
+        pos.high_water_mark = Some(price);
+
+        let engine = self.engine.clone();
+        let position_id = pos.id;
+        tokio::spawn(async move {
+            if let Err(e) = engine.trail_stop(position_id, price).await {
+                warn!(error = %e, %position_id, "Failed to ratchet trailing stop");
+            }
+        });
+    }
+
+    /// Fire exits for positions whose stops were crossed. Positions with a
+    /// live OCO are left to the exchange.
+    fn evaluate(&self, symbol: &str, price: Decimal, by_symbol: &mut HashMap<String, Vec<Position>>) {
+        let Some(positions) = by_symbol.get_mut(symbol) else {
+            return;
+        };
+
+        positions.retain(|pos| {
+            if pos.oco_order_list_id.is_some() {
+                return true;
+            }
+            let Some(reason) = RiskManager::exit_trigger(pos, price) else {
+                return true;
+            };
+
+            let engine = self.engine.clone();
+            let position_id = pos.id;
+            tokio::spawn(async move {
+                if let Err(e) = engine.close_on_trigger(position_id, reason).await {
+                    error!(error = %e, %position_id, "Real-time exit failed");
+                }
+            });
+            // Stop tracking; the next refresh picks it up again if the sell failed
+            false
+        });
+    }
+}
+
+/// Sorted, de-duplicated symbols of the given positions
+fn symbol_set(positions: &[Position]) -> Vec<String> {
+    let mut symbols: Vec<String> = positions.iter().map(|p| p.symbol.clone()).collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+}
+
+fn group_by_symbol(positions: Vec<Position>) -> HashMap<String, Vec<Position>> {
+    let mut by_symbol: HashMap<String, Vec<Position>> = HashMap::new();
+    for pos in positions {
+        by_symbol.entry(pos.symbol.clone()).or_default().push(pos);
+    }
+    by_symbol
+}
@@ -0,0 +1,160 @@
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::binance::SymbolFilters;
+use crate::db::models::Position;
+
+/// How the bot's open positions disagree with what the exchange account holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscrepancyKind {
+    /// The account holds a tradable amount of an asset no open position covers
+    OrphanedHolding,
+    /// Open positions claim an asset the account no longer holds
+    PhantomPosition,
+    /// Both exist but the quantities differ by more than one lot step
+    QuantityMismatch,
+}
+
+impl DiscrepancyKind {
+    pub const ALL: [DiscrepancyKind; 3] = [
+        DiscrepancyKind::OrphanedHolding,
+        DiscrepancyKind::PhantomPosition,
+        DiscrepancyKind::QuantityMismatch,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscrepancyKind::OrphanedHolding => "ORPHANED_HOLDING",
+            DiscrepancyKind::PhantomPosition => "PHANTOM_POSITION",
+            DiscrepancyKind::QuantityMismatch => "QUANTITY_MISMATCH",
+        }
+    }
+
+    /// Identifies the open discrepancy of this kind for an asset
+    pub fn key(&self, asset: &str) -> String {
+        format!("{}:{}", self.as_str(), asset)
+    }
+}
+
+/// One base asset as the bot and the exchange each see it
+#[derive(Debug, Clone)]
+pub struct AssetState {
+    pub asset: String,
+    pub symbol: String,
+    /// Open positions in this asset
+    pub positions: Vec<Uuid>,
+    /// Total quantity of those positions
+    pub expected: Decimal,
+    /// Free + locked on the exchange
+    pub actual: Decimal,
+}
+
+/// Group open positions and exchange holdings by base asset. Holdings of the
+/// quote asset and of `ignored` assets (e.g. BNB kept for fees) are skipped.
+pub fn asset_states(
+    positions: &[Position],
+    holdings: &[(String, Decimal)],
+    quote_asset: &str,
+    ignored: &[String],
+) -> Vec<AssetState> {
+    let new_state = |asset: &str| AssetState {
+        asset: asset.to_string(),
+        symbol: format!("{}{}", asset, quote_asset),
+        positions: Vec::new(),
+        expected: Decimal::ZERO,
+        actual: Decimal::ZERO,
+    };
+
+    // Positions first so an ignored asset the bot did open a position in is still checked
+    let mut by_asset: BTreeMap<String, AssetState> = BTreeMap::new();
+    for pos in positions {
+        let asset = pos.symbol.strip_suffix(quote_asset).unwrap_or(&pos.symbol);
+        let state = by_asset.entry(asset.to_string()).or_insert_with(|| new_state(asset));
+        state.positions.push(pos.id);
+        state.expected += pos.quantity;
+    }
+    for (asset, total) in holdings {
+        if asset == quote_asset || (ignored.contains(asset) && !by_asset.contains_key(asset)) {
+            continue;
+        }
+        by_asset.entry(asset.clone()).or_insert_with(|| new_state(asset)).actual += *total;
+    }
+
+    by_asset.into_values().collect()
+}
+
+/// Whether an asset's positions and holdings disagree. Amounts the exchange
+/// wouldn't let us trade (below LOT_SIZE minQty or the NOTIONAL minimum at
+/// `price`) count as nothing held, so leftover dust is never flagged.
+pub fn classify(state: &AssetState, filters: &SymbolFilters, price: Decimal) -> Option<DiscrepancyKind> {
+    let is_dust = |qty: Decimal| qty < filters.min_qty || qty * price < filters.min_notional;
+    let gap = (state.actual - state.expected).abs();
+
+    match (state.positions.is_empty(), is_dust(state.actual)) {
+        (true, false) => Some(DiscrepancyKind::OrphanedHolding),
+        (false, true) if gap > filters.step_size => Some(DiscrepancyKind::PhantomPosition),
+        (false, false) if gap > filters.step_size => Some(DiscrepancyKind::QuantityMismatch),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn filters() -> SymbolFilters {
+        SymbolFilters {
+            symbol: "SOLUSDC".to_string(),
+            status: "TRADING".to_string(),
+            tick_size: dec!(0.01),
+            step_size: dec!(0.001),
+            min_qty: dec!(0.001),
+            max_qty: dec!(9000),
+            min_notional: dec!(5),
+            quote_precision: 8,
+        }
+    }
+
+    fn state(positions: usize, expected: Decimal, actual: Decimal) -> AssetState {
+        AssetState {
+            asset: "SOL".to_string(),
+            symbol: "SOLUSDC".to_string(),
+            positions: (0..positions).map(|_| Uuid::new_v4()).collect(),
+            expected,
+            actual,
+        }
+    }
+
+    #[test]
+    fn test_classify_discrepancies() {
+        let f = filters();
+        let price = dec!(100);
+        assert_eq!(classify(&state(0, dec!(0), dec!(1)), &f, price), Some(DiscrepancyKind::OrphanedHolding));
+        assert_eq!(classify(&state(1, dec!(1), dec!(0)), &f, price), Some(DiscrepancyKind::PhantomPosition));
+        assert_eq!(classify(&state(1, dec!(1), dec!(0.5)), &f, price), Some(DiscrepancyKind::QuantityMismatch));
+        assert_eq!(classify(&state(1, dec!(1), dec!(1.0005)), &f, price), None);
+    }
+
+    #[test]
+    fn test_dust_is_not_a_holding() {
+        let f = filters();
+        // 0.04 SOL is above minQty but only 4 USDC, under the 5 USDC notional
+        assert_eq!(classify(&state(0, dec!(0), dec!(0.04)), &f, dec!(100)), None);
+        assert_eq!(classify(&state(0, dec!(0), dec!(0.0005)), &f, dec!(100)), None);
+    }
+
+    #[test]
+    fn test_asset_states_skip_quote_and_ignored_assets() {
+        let holdings = vec![
+            ("USDC".to_string(), dec!(100)),
+            ("BNB".to_string(), dec!(0.2)),
+            ("SOL".to_string(), dec!(1)),
+        ];
+        let states = asset_states(&[], &holdings, "USDC", &["BNB".to_string()]);
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].symbol, "SOLUSDC");
+        assert_eq!(states[0].actual, dec!(1));
+    }
+}
@@ -1,27 +1,121 @@
 use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use sqlx::PgPool;
 use tracing::{info, warn};
 
-use crate::binance::BinanceClient;
+use crate::binance::OrderResponse;
+use crate::config::TradingMode;
 use crate::db::models::Position;
 use crate::db::queries;
+use crate::exchange::Exchange;
 
 /// Risk management: stop-loss/take-profit checking and position limits.
 pub struct RiskManager;
 
+/// Stops may sit at most this far below entry (and below the high, when trailing)
+const MAX_STOP_PCT: Decimal = dec!(5);
+
+/// How far a position's trailing stop follows below its high-water mark
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trail {
+    /// A percentage of the high
+    Percent(Decimal),
+    /// A fixed distance in the quote asset
+    Distance(Decimal),
+}
+
+impl Trail {
+    /// The trail stored on a position, if it has one
+    pub fn of(pos: &Position) -> Option<Trail> {
+        match (pos.trail_pct, pos.trail_distance) {
+            (Some(pct), _) => Some(Trail::Percent(pct)),
+            (None, Some(distance)) => Some(Trail::Distance(distance)),
+            (None, None) => None,
+        }
+    }
+
+    /// A trail for a position entered at `entry_price`, capped at the 5% stop
+    /// rule. `None` if the requested width isn't positive.
+    pub fn capped(self, entry_price: Decimal) -> Option<Trail> {
+        match self {
+            Trail::Percent(pct) if pct > Decimal::ZERO => Some(Trail::Percent(pct.min(MAX_STOP_PCT))),
+            Trail::Distance(d) if d > Decimal::ZERO => {
+                Some(Trail::Distance(d.min(entry_price * MAX_STOP_PCT / Decimal::ONE_HUNDRED)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Where the stop sits when the high-water mark is `high`
+    pub fn stop_below(&self, high: Decimal) -> Decimal {
+        match self {
+            Trail::Percent(pct) => high * (Decimal::ONE - pct / Decimal::ONE_HUNDRED),
+            Trail::Distance(distance) => high - distance,
+        }
+    }
+
+    /// `(trail_pct, trail_distance)` column values
+    pub fn columns(&self) -> (Option<Decimal>, Option<Decimal>) {
+        match self {
+            Trail::Percent(pct) => (Some(*pct), None),
+            Trail::Distance(distance) => (None, Some(*distance)),
+        }
+    }
+}
+
+/// Why a position is leaving the book
+#[derive(Debug)]
+pub enum ExitSignal {
+    /// Price crossed the stop-loss or take-profit; the bot must sell
+    Triggered(String),
+    /// An exchange-side OCO leg already sold the position
+    Filled { reason: String, order: OrderResponse },
+}
+
+/// State of a position's exchange-side OCO exit orders
+enum OcoState {
+    Live,
+    Filled { reason: String, order: OrderResponse },
+    Inactive,
+}
+
 impl RiskManager {
     /// Check all open positions for stop-loss or take-profit triggers.
+    /// Positions protected by an OCO are only checked for filled legs —
+    /// the exchange enforces their exits between cycles.
     /// Returns positions that need to be closed.
     pub async fn check_positions(
         pool: &PgPool,
-        binance: &BinanceClient,
-    ) -> Result<Vec<(Position, String)>> {
-        let positions = queries::get_open_positions(pool).await?;
-        let mut to_close: Vec<(Position, String)> = Vec::new();
+        mode: TradingMode,
+        exchange: &dyn Exchange,
+    ) -> Result<Vec<(Position, ExitSignal)>> {
+        let positions = queries::get_open_positions(pool, mode).await?;
+        let mut to_close: Vec<(Position, ExitSignal)> = Vec::new();
 
         for pos in positions {
+            let mut oco_live = false;
+            if pos.oco_order_list_id.is_some() {
+                match Self::check_oco(exchange, &pos).await {
+                    Ok(OcoState::Filled { reason, order }) => {
+                        info!(symbol = %pos.symbol, reason = %reason, "OCO exit filled on exchange");
+                        to_close.push((pos, ExitSignal::Filled { reason, order }));
+                        continue;
+                    }
+                    Ok(OcoState::Live) => oco_live = true,
+                    Ok(OcoState::Inactive) => {
+                        warn!(symbol = %pos.symbol, "OCO exit orders no longer live — reverting to cycle stops");
+                        let _ = queries::clear_position_oco(pool, pos.id).await;
+                    }
+                    Err(e) => {
+                        warn!(symbol = %pos.symbol, error = %e, "Failed to query OCO exit orders");
+                        continue;
+                    }
+                }
+            }
+
             // Get current price
-            let ticker = match binance.get_ticker(&pos.symbol).await {
+            let ticker = match exchange.get_ticker(&pos.symbol).await {
                 Ok(t) => t,
                 Err(e) => {
                     warn!(symbol = %pos.symbol, error = %e, "Failed to get ticker for risk check");
@@ -29,61 +123,105 @@ impl RiskManager {
                 }
             };
 
-            let current_price: f64 = ticker.last_price.parse().unwrap_or(0.0);
-            if current_price <= 0.0 {
+            let current_price = ticker.last_price;
+            if current_price <= Decimal::ZERO {
                 continue;
             }
 
             // Update position's current price in DB
             let _ = queries::update_position_price(pool, pos.id, current_price).await;
 
-            // Check stop-loss
-            if let Some(stop_loss) = pos.stop_loss {
-                if current_price <= stop_loss {
-                    info!(
-                        symbol = %pos.symbol,
-                        current_price,
-                        stop_loss,
-                        "🛑 Stop-loss triggered"
-                    );
-                    to_close.push((pos, "STOP_LOSS".to_string()));
-                    continue;
-                }
+            if oco_live {
+                continue;
             }
 
-            // Check take-profit
-            if let Some(take_profit) = pos.take_profit {
-                if current_price >= take_profit {
-                    info!(
-                        symbol = %pos.symbol,
-                        current_price,
-                        take_profit,
-                        "🎯 Take-profit triggered"
-                    );
-                    to_close.push((pos, "TAKE_PROFIT".to_string()));
-                    continue;
-                }
+            if let Some(reason) = Self::exit_trigger(&pos, current_price) {
+                to_close.push((pos, ExitSignal::Triggered(reason.to_string())));
             }
         }
 
         Ok(to_close)
     }
 
+    /// Check a price against a position's stop-loss and take-profit.
+    /// Returns the close reason if either has been crossed.
+    pub fn exit_trigger(pos: &Position, current_price: Decimal) -> Option<&'static str> {
+        // Check stop-loss
+        if let Some(stop_loss) = pos.stop_loss {
+            if current_price <= stop_loss {
+                info!(
+                    symbol = %pos.symbol,
+                    %current_price,
+                    %stop_loss,
+                    "🛑 Stop-loss triggered"
+                );
+                return Some(if Trail::of(pos).is_some() { "TRAILING_STOP" } else { "STOP_LOSS" });
+            }
+        }
+
+        // Check take-profit
+        if let Some(take_profit) = pos.take_profit {
+            if current_price >= take_profit {
+                info!(
+                    symbol = %pos.symbol,
+                    %current_price,
+                    %take_profit,
+                    "🎯 Take-profit triggered"
+                );
+                return Some("TAKE_PROFIT");
+            }
+        }
+
+        None
+    }
+
+    /// Look up both OCO legs and report whether either has filled
+    async fn check_oco(exchange: &dyn Exchange, pos: &Position) -> Result<OcoState> {
+        let legs = [
+            (pos.tp_order_id, "TAKE_PROFIT"),
+            (pos.sl_order_id, "STOP_LOSS"),
+        ];
+
+        let mut inactive = 0;
+        for (order_id, reason) in legs {
+            let Some(order_id) = order_id else {
+                inactive += 1;
+                continue;
+            };
+            let order = exchange.get_order(&pos.symbol, order_id as u64).await?;
+            match order.status.as_str() {
+                "FILLED" => {
+                    return Ok(OcoState::Filled {
+                        reason: reason.to_string(),
+                        order,
+                    })
+                }
+                "CANCELED" | "EXPIRED" | "REJECTED" | "EXPIRED_IN_MATCH" => inactive += 1,
+                _ => {}
+            }
+        }
+
+        if inactive == legs.len() {
+            Ok(OcoState::Inactive)
+        } else {
+            Ok(OcoState::Live)
+        }
+    }
+
     /// Check if we can open a new position (max 2 open at a time)
-    pub async fn can_open_position(pool: &PgPool) -> Result<bool> {
-        let count = queries::count_open_positions(pool).await?;
+    pub async fn can_open_position(pool: &PgPool, mode: TradingMode) -> Result<bool> {
+        let count = queries::count_open_positions(pool, mode).await?;
         Ok(count < 2)
     }
 
     /// Validate a proposed stop-loss: must be within 5% of entry price
-    pub fn validate_stop_loss(entry_price: f64, stop_loss: f64) -> f64 {
-        let max_loss_pct = 0.05; // 5%
-        let min_stop = entry_price * (1.0 - max_loss_pct);
+    pub fn validate_stop_loss(entry_price: Decimal, stop_loss: Decimal) -> Decimal {
+        let min_stop = entry_price * (Decimal::ONE - MAX_STOP_PCT / Decimal::ONE_HUNDRED);
 
         if stop_loss < min_stop {
             warn!(
-                proposed = stop_loss,
-                enforced = min_stop,
+                proposed = %stop_loss,
+                enforced = %min_stop,
                 "Stop-loss too wide — enforcing 5% max"
             );
             min_stop
@@ -92,3 +230,67 @@ impl RiskManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn position(stop_loss: Option<Decimal>, take_profit: Option<Decimal>) -> Position {
+        Position {
+            mode: "paper".to_string(),
+            id: Uuid::new_v4(),
+            symbol: "SOLUSDC".to_string(),
+            side: "BUY".to_string(),
+            quantity: dec!(1),
+            entry_price: dec!(100),
+            current_price: None,
+            stop_loss,
+            take_profit,
+            status: "OPEN".to_string(),
+            pnl: None,
+            opened_at: Utc::now(),
+            closed_at: None,
+            close_reason: None,
+            oco_order_list_id: None,
+            tp_order_id: None,
+            sl_order_id: None,
+            entry_commission: Decimal::ZERO,
+            gross_pnl: None,
+            trail_pct: None,
+            trail_distance: None,
+            high_water_mark: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_trigger_stop_and_target() {
+        let pos = position(Some(dec!(95)), Some(dec!(110)));
+        assert_eq!(RiskManager::exit_trigger(&pos, dec!(94.9)), Some("STOP_LOSS"));
+        assert_eq!(RiskManager::exit_trigger(&pos, dec!(110)), Some("TAKE_PROFIT"));
+        assert_eq!(RiskManager::exit_trigger(&pos, dec!(100)), None);
+    }
+
+    #[test]
+    fn test_trailing_stop_follows_high() {
+        let trail = Trail::Percent(dec!(3));
+        assert_eq!(trail.stop_below(dec!(120)), dec!(116.4));
+        assert_eq!(Trail::Distance(dec!(2.5)).stop_below(dec!(120)), dec!(117.5));
+
+        // Wider than the 5% rule allows is narrowed to it
+        assert_eq!(Trail::Percent(dec!(8)).capped(dec!(100)), Some(Trail::Percent(dec!(5))));
+        assert_eq!(Trail::Distance(dec!(9)).capped(dec!(100)), Some(Trail::Distance(dec!(5))));
+        assert_eq!(Trail::Percent(Decimal::ZERO).capped(dec!(100)), None);
+
+        let mut pos = position(Some(dec!(116.4)), None);
+        pos.trail_pct = Some(dec!(3));
+        assert_eq!(RiskManager::exit_trigger(&pos, dec!(116)), Some("TRAILING_STOP"));
+    }
+
+    #[test]
+    fn test_exit_trigger_without_levels() {
+        let pos = position(None, None);
+        assert_eq!(RiskManager::exit_trigger(&pos, dec!(1)), None);
+    }
+}
@@ -1,30 +1,39 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
 /// Position sizing based on OpenClaw's confidence level.
 /// Higher confidence = larger position (up to 10% of tradeable balance).
 pub struct PositionSizer;
 
 impl PositionSizer {
-    /// Calculate position size in USDC based on confidence level.
-    /// Returns 0.0 if confidence is below 70 (forced HOLD).
-    pub fn calculate(balance_usdc: f64, confidence: i32, min_balance: f64) -> f64 {
+    /// Calculate position size in the quote asset based on confidence level.
+    /// Returns zero if confidence is below 70 (forced HOLD) or the size
+    /// would fall below the symbol's minimum notional.
+    pub fn calculate(
+        balance: Decimal,
+        confidence: i32,
+        min_balance: Decimal,
+        min_notional: Decimal,
+    ) -> Decimal {
         // Reserve minimum balance for infrastructure costs
-        let tradeable = (balance_usdc - min_balance).max(0.0);
+        let tradeable = (balance - min_balance).max(Decimal::ZERO);
 
-        if tradeable <= 0.0 {
-            return 0.0;
+        if tradeable <= Decimal::ZERO {
+            return Decimal::ZERO;
         }
 
         let percentage = match confidence {
-            90..=100 => 0.10, // 10%
-            80..=89 => 0.06,  // 6%
-            70..=79 => 0.03,  // 3%
-            _ => 0.0,         // Below 70: forced HOLD
+            90..=100 => dec!(0.10), // 10%
+            80..=89 => dec!(0.06),  // 6%
+            70..=79 => dec!(0.03),  // 3%
+            _ => Decimal::ZERO,     // Below 70: forced HOLD
         };
 
         let size = tradeable * percentage;
 
-        // Enforce minimum order size (Binance requires ~$5 minimum for most pairs)
-        if size < 5.0 {
-            return 0.0;
+        // Enforce the symbol's NOTIONAL filter so the order isn't rejected
+        if size < min_notional {
+            return Decimal::ZERO;
         }
 
         size
@@ -37,25 +46,33 @@ mod tests {
 
     #[test]
     fn test_high_confidence() {
-        let size = PositionSizer::calculate(100.0, 95, 5.0);
-        assert!((size - 9.5).abs() < 0.01); // 10% of 95 tradeable
+        let size = PositionSizer::calculate(dec!(100), 95, dec!(5), dec!(5));
+        assert_eq!(size, dec!(9.5)); // 10% of 95 tradeable
     }
 
     #[test]
     fn test_medium_confidence() {
-        let size = PositionSizer::calculate(100.0, 85, 5.0);
-        assert!((size - 5.7).abs() < 0.01); // 6% of 95 tradeable
+        let size = PositionSizer::calculate(dec!(100), 85, dec!(5), dec!(5));
+        assert_eq!(size, dec!(5.7)); // 6% of 95 tradeable
     }
 
     #[test]
     fn test_low_confidence_forced_hold() {
-        let size = PositionSizer::calculate(100.0, 60, 5.0);
-        assert_eq!(size, 0.0);
+        let size = PositionSizer::calculate(dec!(100), 60, dec!(5), dec!(5));
+        assert_eq!(size, Decimal::ZERO);
     }
 
     #[test]
     fn test_below_minimum_balance() {
-        let size = PositionSizer::calculate(4.0, 95, 5.0);
-        assert_eq!(size, 0.0);
+        let size = PositionSizer::calculate(dec!(4), 95, dec!(5), dec!(5));
+        assert_eq!(size, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_below_min_notional() {
+        // 3% of 95 tradeable = 2.85, under a 5.0 notional but over a 1.0 notional
+        assert_eq!(PositionSizer::calculate(dec!(100), 75, dec!(5), dec!(5)), Decimal::ZERO);
+        let size = PositionSizer::calculate(dec!(100), 75, dec!(5), dec!(1));
+        assert_eq!(size, dec!(2.85));
     }
 }
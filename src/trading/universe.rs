@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use crate::binance::SymbolFilters;
+
+/// Suffixes of Binance leveraged tokens (BTCUP, ETHDOWN, BNBBULL…)
+const LEVERAGED_SUFFIXES: [&str; 4] = ["UP", "DOWN", "BULL", "BEAR"];
+
+/// Why a symbol may not be bought under the allow/deny lists, or `None` if it may.
+/// `allowlist` of `None` allows any asset that isn't denied.
+pub fn policy_rejection(
+    symbol: &str,
+    quote_asset: &str,
+    allowlist: Option<&HashSet<String>>,
+    denylist: &HashSet<String>,
+) -> Option<String> {
+    let Some(base) = symbol.strip_suffix(quote_asset).filter(|b| !b.is_empty()) else {
+        return Some(format!("not a {} pair", quote_asset));
+    };
+    if denylist.contains(base) {
+        return Some("denylisted".to_string());
+    }
+    if allowlist.is_some_and(|allowed| !allowed.contains(base)) {
+        return Some("not allowlisted".to_string());
+    }
+    None
+}
+
+/// The asset a leveraged token would track going by its name alone (BTC for
+/// BTCUP). Real assets such as SYRUP also match, so the caller must confirm
+/// the tracked asset is listed before treating `base` as leveraged.
+pub fn leveraged_underlying(base: &str) -> Option<&str> {
+    LEVERAGED_SUFFIXES
+        .iter()
+        .find_map(|suffix| base.strip_suffix(suffix).filter(|prefix| !prefix.is_empty()))
+}
+
+/// Why exchangeInfo says a symbol can't be traded now (BREAK, HALT, …), or `None`
+pub fn status_rejection(filters: &SymbolFilters) -> Option<String> {
+    (filters.status != "TRADING").then(|| format!("status {}", filters.status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(assets: &[&str]) -> HashSet<String> {
+        assets.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_leveraged_underlying() {
+        assert_eq!(leveraged_underlying("BTCUP"), Some("BTC"));
+        assert_eq!(leveraged_underlying("ETHDOWN"), Some("ETH"));
+        assert_eq!(leveraged_underlying("BNBBULL"), Some("BNB"));
+        assert_eq!(leveraged_underlying("SYRUP"), Some("SYR"));
+        assert_eq!(leveraged_underlying("UP"), None);
+        assert_eq!(leveraged_underlying("SOL"), None);
+    }
+
+    #[test]
+    fn test_policy_rejection() {
+        let allow = set(&["BTC", "SOL", "AAVE"]);
+        let deny = set(&["AAVE"]);
+        assert_eq!(policy_rejection("SOLUSDC", "USDC", Some(&allow), &deny), None);
+        assert_eq!(policy_rejection("AAVEUSDC", "USDC", Some(&allow), &deny).as_deref(), Some("denylisted"));
+        assert_eq!(policy_rejection("DOGEUSDC", "USDC", Some(&allow), &deny).as_deref(), Some("not allowlisted"));
+        assert_eq!(policy_rejection("SOLBTC", "USDC", None, &deny).as_deref(), Some("not a USDC pair"));
+        assert_eq!(policy_rejection("DOGEUSDC", "USDC", None, &deny), None);
+    }
+}